@@ -1,5 +1,6 @@
 use crate::{CpuidResult};
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AddressSize {
     pub physical: u8,
     pub virtual_: u8,
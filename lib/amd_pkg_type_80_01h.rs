@@ -7,7 +7,8 @@ use crate::{cpuid, CpuidResult, FamModStep};
 /* ref: https://github.com/illumos/illumos-gate/blob/master/usr/src/uts/intel/os/cpuid_subr.c */
 /* ref: https://github.com/coreboot/coreboot/blob/master/src/soc/amd/picasso/include/soc/soc_util.h */
 
-#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum AmdPkgType {
     F1207,
     AM2r2,
@@ -44,6 +45,9 @@ pub enum AmdPkgType {
     // FP7r7, // ?, AMD Ryzen 9 6900HS, Ryzen 7 6800HS
     AM5,
     FT6,
+    FL1, // Dragon Range, Fire Range
+    FP8,
+    FP11,
     Unknown(u32),
 }
 
@@ -142,7 +146,9 @@ impl From<&CpuidResult> for AmdPkgType {
             /* Rome */
             FamModStep { syn_fam: 0x17, syn_mod: 0x30..=0x3F, .. } |
             /* Matisse */
-            FamModStep { syn_fam: 0x17, syn_mod: 0x71, .. } => match pkg_type {
+            FamModStep { syn_fam: 0x17, syn_mod: 0x71, .. } |
+            /* Hygon Dhyana, same packages as Zen (SP3, AM4) */
+            FamModStep { syn_fam: 0x18, syn_mod: 0x00..=0x0F, .. } => match pkg_type {
                 0x0 => Self::FP5,
                 0x2 => Self::AM4,
                 0x3 => Self::FT5,
@@ -186,13 +192,30 @@ impl From<&CpuidResult> for AmdPkgType {
                 _ => Self::Unknown(pkg_type),
             },
             /* Genoa */
-            FamModStep { syn_fam: 0x19, syn_mod: 0x10..=0x1F, .. } => match pkg_type {
+            FamModStep { syn_fam: 0x19, syn_mod: 0x10..=0x1F, .. } |
+            /* Turin, Turin Dense */
+            FamModStep { syn_fam: 0x1A, syn_mod: 0x00..=0x1F, .. } => match pkg_type {
                 0x4 => Self::SP5,
                 _ => Self::Unknown(pkg_type),
             },
-            /* Raphael */
-            FamModStep { syn_fam: 0x19, syn_mod: 0x60..=0x6F, .. } => match pkg_type {
+            /* Raphael, Dragon Range */
+            FamModStep { syn_fam: 0x19, syn_mod: 0x60..=0x6F, .. } |
+            /* Granite Ridge, Fire Range */
+            FamModStep { syn_fam: 0x1A, syn_mod: 0x40..=0x4F, .. } => match pkg_type {
                 0x0 => Self::AM5,
+                0x1 => Self::FL1,
+                _ => Self::Unknown(pkg_type),
+            },
+            /* Strix Point */
+            FamModStep { syn_fam: 0x1A, syn_mod: 0x20..=0x2F, .. } |
+            /* Krackan Point */
+            FamModStep { syn_fam: 0x1A, syn_mod: 0x60..=0x6F, .. } => match pkg_type {
+                0x1 => Self::FP8,
+                _ => Self::Unknown(pkg_type),
+            },
+            /* Strix Halo */
+            FamModStep { syn_fam: 0x1A, syn_mod: 0x70..=0x7F, .. } => match pkg_type {
+                0x1 => Self::FP11,
                 _ => Self::Unknown(pkg_type),
             },
             _ => Self::Unknown(pkg_type),
@@ -205,3 +228,37 @@ impl AmdPkgType {
         Self::from(&cpuid!(0x8000_0001, 0x0))
     }
 }
+
+#[test]
+fn test_amd_pkg_type() {
+    /* Ryzen 5 5600G */
+    let cpuid = CpuidResult { eax: 0x00A50F00, ebx: 0x20000000, ecx: 0x75C237FF, edx: 0x2FD3FBFF };
+    assert!(matches!(AmdPkgType::from(&cpuid), AmdPkgType::AM4));
+
+    /* Synthetic vectors, no Family 1Ah dump is in dump_result/:
+       EAX is the Family/Model/Stepping of the codename database entry, EBX[31:28] is PkgType,
+       ECX/EDX are not used */
+    /* Turin */
+    let cpuid = CpuidResult { eax: 0x00B00F21, ebx: 0x40000000, ecx: 0x0, edx: 0x0 };
+    assert!(matches!(AmdPkgType::from(&cpuid), AmdPkgType::SP5));
+
+    /* Granite Ridge */
+    let cpuid = CpuidResult { eax: 0x00B40F40, ebx: 0x00000000, ecx: 0x0, edx: 0x0 };
+    assert!(matches!(AmdPkgType::from(&cpuid), AmdPkgType::AM5));
+
+    /* Fire Range */
+    let cpuid = CpuidResult { eax: 0x00B40F40, ebx: 0x10000000, ecx: 0x0, edx: 0x0 };
+    assert!(matches!(AmdPkgType::from(&cpuid), AmdPkgType::FL1));
+
+    /* Strix Point */
+    let cpuid = CpuidResult { eax: 0x00B20F40, ebx: 0x10000000, ecx: 0x0, edx: 0x0 };
+    assert!(matches!(AmdPkgType::from(&cpuid), AmdPkgType::FP8));
+
+    /* Krackan Point */
+    let cpuid = CpuidResult { eax: 0x00B60F00, ebx: 0x10000000, ecx: 0x0, edx: 0x0 };
+    assert!(matches!(AmdPkgType::from(&cpuid), AmdPkgType::FP8));
+
+    /* Strix Halo */
+    let cpuid = CpuidResult { eax: 0x00B70F00, ebx: 0x10000000, ecx: 0x0, edx: 0x0 };
+    assert!(matches!(AmdPkgType::from(&cpuid), AmdPkgType::FP11));
+}
@@ -1,5 +1,6 @@
 use crate::CpuidResult;
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AmdProcTopo {
     pub ext_apic_id: u32,
     pub threads_per_core: u8,
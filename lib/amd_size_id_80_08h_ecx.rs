@@ -1,5 +1,6 @@
 use crate::{cpuid, CpuidResult};
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AmdSizeId {
     pub perf_tsc_size: u8,
     pub apic_id_size: u8,
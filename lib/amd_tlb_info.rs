@@ -1,6 +1,7 @@
 #[cfg(feature = "std")]
 use std::fmt;
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone)]
 pub enum TlbType {
     L1d,
@@ -16,6 +17,7 @@ impl fmt::Display for TlbType {
     }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone)]
 pub enum TlbAssoc {
     Disabled,
@@ -38,6 +40,7 @@ impl fmt::Display for TlbAssoc {
     }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone)]
 pub struct TlbInfo {
     pub size: u16,
@@ -93,6 +96,7 @@ impl TlbInfo {
     }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone)]
 pub struct Tlb {
     pub type_: TlbType,
@@ -0,0 +1,165 @@
+use std::convert::TryFrom;
+use std::fmt::Write;
+
+/* Convert the codename database (codename/codename_db.toml) into static tables */
+
+const DB_PATH: &str = "codename/codename_db.toml";
+
+const ENTRY_KEYS: &[&str] = &[
+    "vendor", "family", "model", "model_last", "step", "step_last",
+    "codename", "microarch", "node", "steppings",
+];
+const STEPPING_KEYS: &[&str] = &["model", "step", "name"];
+const LINE_KEYS: &[&str] = &["vendor", "name", "generations"];
+
+macro_rules! fail {
+    ($($arg: tt)*) => {
+        panic!("{}: {}", DB_PATH, format!($($arg)*))
+    };
+}
+
+fn int(v: &toml::Value, key: &str, ctx: &str) -> u32 {
+    v.as_integer()
+        .and_then(|v| u32::try_from(v).ok())
+        .unwrap_or_else(|| fail!("{ctx}: \"{key}\" must be an unsigned integer"))
+}
+
+fn opt_int(table: &toml::Table, key: &str, ctx: &str) -> String {
+    match table.get(key) {
+        Some(v) => format!("Some({:#X})", int(v, key, ctx)),
+        None => "None".to_string(),
+    }
+}
+
+fn string<'a>(v: &'a toml::Value, key: &str, ctx: &str) -> &'a str {
+    v.as_str().unwrap_or_else(|| fail!("{ctx}: \"{key}\" must be a string"))
+}
+
+fn check_keys(table: &toml::Table, keys: &[&str], ctx: &str) {
+    for key in table.keys() {
+        if !keys.contains(&key.as_str()) {
+            fail!("{ctx}: unknown key \"{key}\"");
+        }
+    }
+}
+
+/* "7 nm", "65 nm", "1 um", "Intel 7" */
+fn node(s: &str, ctx: &str) -> String {
+    let (variant, size) = if let Some(size) = s.strip_suffix(" nm") {
+        ("NM", size)
+    } else if let Some(size) = s.strip_suffix(" um") {
+        ("_UM", size)
+    } else if let Some(size) = s.strip_prefix("Intel ") {
+        ("Intel", size)
+    } else {
+        fail!("{ctx}: invalid node \"{s}\"")
+    };
+    let size: u8 = size.trim().parse().unwrap_or_else(|_| fail!("{ctx}: invalid node \"{s}\""));
+
+    format!("Some(ProcessNode::{variant}({size}))")
+}
+
+fn vendor(s: &str, ctx: &str) -> &'static str {
+    match s {
+        "AMD" => "DbVendor::Amd",
+        "Intel" => "DbVendor::Intel",
+        "Zhaoxin" => "DbVendor::Zhaoxin",
+        "Hygon" => "DbVendor::Hygon",
+        _ => fail!("{ctx}: unknown vendor \"{s}\""),
+    }
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed={DB_PATH}");
+
+    let src = std::fs::read_to_string(DB_PATH).unwrap();
+    let db: toml::Table = src.parse().unwrap_or_else(|err| fail!("{err}"));
+    check_keys(&db, &["cpu", "line"], "top level");
+
+    let entries = db.get("cpu").and_then(|v| v.as_array()).expect("[[cpu]] entries");
+    let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    let mut out = String::from("&[\n");
+
+    for (i, entry) in entries.iter().enumerate() {
+        let ctx = format!("cpu[{i}]");
+        let table = entry.as_table().unwrap();
+        check_keys(table, ENTRY_KEYS, &ctx);
+
+        let get = |key: &str| table.get(key)
+            .unwrap_or_else(|| fail!("{ctx}: \"{key}\" is required"));
+        let opt_str = |key: &str| match table.get(key) {
+            Some(v) => format!("Some(Cow::Borrowed({:?}))", string(v, key, &ctx)),
+            None => "None".to_string(),
+        };
+
+        let mut steppings = String::new();
+
+        for (j, step) in table.get("steppings").and_then(|v| v.as_array()).into_iter().flatten().enumerate() {
+            let ctx = format!("{ctx}.steppings[{j}]");
+            let step = step.as_table().unwrap();
+            check_keys(step, STEPPING_KEYS, &ctx);
+
+            let name = step.get("name").unwrap_or_else(|| fail!("{ctx}: \"name\" is required"));
+
+            writeln!(
+                steppings,
+                "            SteppingName {{ model: {}, step: {}, name: Cow::Borrowed({:?}) }},",
+                opt_int(step, "model", &ctx),
+                opt_int(step, "step", &ctx),
+                string(name, "name", &ctx),
+            ).unwrap();
+        }
+
+        writeln!(out, "    CodenameEntry {{").unwrap();
+        writeln!(out, "        vendor: {},", vendor(string(get("vendor"), "vendor", &ctx), &ctx)).unwrap();
+        writeln!(out, "        family: {:#X},", int(get("family"), "family", &ctx)).unwrap();
+        writeln!(out, "        model: {:#X},", int(get("model"), "model", &ctx)).unwrap();
+        writeln!(out, "        model_last: {},", opt_int(table, "model_last", &ctx)).unwrap();
+        writeln!(out, "        step: {},", opt_int(table, "step", &ctx)).unwrap();
+        writeln!(out, "        step_last: {},", opt_int(table, "step_last", &ctx)).unwrap();
+        writeln!(out, "        codename: Cow::Borrowed({:?}),", string(get("codename"), "codename", &ctx)).unwrap();
+        writeln!(out, "        microarch: {},", opt_str("microarch")).unwrap();
+        writeln!(out, "        node: {},", match table.get("node") {
+            Some(v) => node(string(v, "node", &ctx), &ctx),
+            None => "None".to_string(),
+        }).unwrap();
+        writeln!(out, "        steppings: Cow::Borrowed(&[\n{steppings}        ]),").unwrap();
+        writeln!(out, "    }},").unwrap();
+    }
+
+    out.push(']');
+    std::fs::write(out_dir.join("codename_db.rs"), out).unwrap();
+
+    let lines = db.get("line").and_then(|v| v.as_array()).expect("[[line]] entries");
+    let mut out = String::from("&[\n");
+
+    for (i, line) in lines.iter().enumerate() {
+        let ctx = format!("line[{i}]");
+        let table = line.as_table().unwrap();
+        check_keys(table, LINE_KEYS, &ctx);
+
+        let get = |key: &str| table.get(key)
+            .unwrap_or_else(|| fail!("{ctx}: \"{key}\" is required"));
+        let mut generations = String::new();
+
+        for (j, gen) in get("generations").as_array().into_iter().flatten().enumerate() {
+            let ctx = format!("{ctx}.generations[{j}]");
+            let names: Vec<String> = gen.as_array()
+                .unwrap_or_else(|| fail!("{ctx}: must be an array of microarch"))
+                .iter()
+                .map(|name| format!("Cow::Borrowed({:?})", string(name, "microarch", &ctx)))
+                .collect();
+
+            writeln!(generations, "            Cow::Borrowed(&[{}]),", names.join(", ")).unwrap();
+        }
+
+        writeln!(out, "    MicroArchLine {{").unwrap();
+        writeln!(out, "        vendor: {},", vendor(string(get("vendor"), "vendor", &ctx), &ctx)).unwrap();
+        writeln!(out, "        name: Cow::Borrowed({:?}),", string(get("name"), "name", &ctx)).unwrap();
+        writeln!(out, "        generations: Cow::Borrowed(&[\n{generations}        ]),").unwrap();
+        writeln!(out, "    }},").unwrap();
+    }
+
+    out.push(']');
+    std::fs::write(out_dir.join("micro_arch_line.rs"), out).unwrap();
+}
@@ -1,5 +1,6 @@
 use crate::{cpuid, CpuidResult, CpuVendor};
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Unit {
     Byte,
@@ -41,6 +42,7 @@ impl std::fmt::Display for Unit {
     }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum CacheType {
     Data,
@@ -68,6 +70,7 @@ impl std::fmt::Display for CacheType {
     }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct CacheProp {
     pub cache_type: CacheType,
@@ -116,7 +119,8 @@ impl From<&CpuidResult> for CacheProp {
 impl CacheProp {
     pub fn get_cache_prop_leaf() -> Option<u32> {
         match CpuVendor::get() {
-            CpuVendor::AuthenticAMD => {
+            CpuVendor::AuthenticAMD |
+            CpuVendor::HygonGenuine => {
                 /* AMD TopologyExtensions: CPUID[Leaf=0x8000_0001, SubLeaf=0x0].ECX[22] */
                 let amd_topo_ext = ((cpuid!(0x8000_0001, 0x0).ecx >> 22) & 0b1) != 0;
 
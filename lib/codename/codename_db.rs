@@ -0,0 +1,279 @@
+use crate::{CpuVendor, ProcInfo, CpuCodename, CpuMicroArch, CpuStepping, ProcessNode};
+use super::*;
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+use alloc::{borrow::Cow, vec::Vec};
+#[cfg(feature = "std")]
+use std::borrow::Cow;
+
+/* Vendor of the codename database entry; CentaurHauls and Shanghai share the Zhaoxin entries */
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DbVendor {
+    #[cfg_attr(feature = "serde", serde(rename = "AMD"))]
+    Amd,
+    Intel,
+    Zhaoxin,
+    Hygon,
+}
+
+impl DbVendor {
+    pub fn from_cpu_vendor(vendor: &CpuVendor) -> Option<Self> {
+        match vendor {
+            CpuVendor::AuthenticAMD => Some(Self::Amd),
+            CpuVendor::GenuineIntel => Some(Self::Intel),
+            CpuVendor::CentaurHauls |
+            CpuVendor::Shanghai => Some(Self::Zhaoxin),
+            CpuVendor::HygonGenuine => Some(Self::Hygon),
+            CpuVendor::Unknown(_) => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Amd => "AMD",
+            Self::Intel => "Intel",
+            Self::Zhaoxin => "Zhaoxin",
+            Self::Hygon => "Hygon",
+        }
+    }
+
+    /* `None` for the names not in the enums (user entries), `CpuCodename::Other` at the call site */
+    fn codename(&self, name: &str) -> Option<CpuCodename> {
+        match self {
+            Self::Amd => AmdCodename::from_name(name).map(CpuCodename::Amd),
+            Self::Intel => IntelCodename::from_name(name).map(CpuCodename::Intel),
+            Self::Zhaoxin => ZhaoxinCodename::from_name(name).map(CpuCodename::Zhaoxin),
+            Self::Hygon => HygonCodename::from_name(name).map(CpuCodename::Hygon),
+        }
+    }
+
+    fn micro_arch(&self, name: &str) -> Option<CpuMicroArch> {
+        match self {
+            Self::Amd => AmdMicroArch::from_name(name).map(CpuMicroArch::Amd),
+            Self::Intel => IntelMicroArch::from_name(name).map(CpuMicroArch::Intel),
+            Self::Zhaoxin => ZhaoxinMicroArch::from_name(name).map(CpuMicroArch::Zhaoxin),
+            Self::Hygon => HygonMicroArch::from_name(name).map(CpuMicroArch::Hygon),
+        }
+    }
+}
+
+/* Stepping name, `None` matches any Model/Stepping of the entry */
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SteppingName {
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub model: Option<u32>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub step: Option<u32>,
+    pub name: Cow<'static, str>,
+}
+
+/* An entry of the codename database, see codename/codename_db.toml for the layout */
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodenameEntry {
+    pub vendor: DbVendor,
+    pub family: u32,
+    pub model: u32,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub model_last: Option<u32>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub step: Option<u32>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub step_last: Option<u32>,
+    pub codename: Cow<'static, str>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub microarch: Option<Cow<'static, str>>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", with = "node_str"))]
+    pub node: Option<ProcessNode>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "<[_]>::is_empty"))]
+    pub steppings: Cow<'static, [SteppingName]>,
+}
+
+impl CodenameEntry {
+    pub fn matches(&self, vendor: DbVendor, f: u32, m: u32, s: u32) -> bool {
+        let model = self.model..=self.model_last.unwrap_or(self.model);
+        let step = match self.step {
+            Some(step) => (step..=self.step_last.unwrap_or(step)).contains(&s),
+            None => true,
+        };
+
+        self.vendor == vendor && self.family == f && model.contains(&m) && step
+    }
+
+    pub fn proc_info(&self, m: u32, s: u32) -> ProcInfo {
+        let step_info = self.steppings.iter()
+            .find(|step| step.model.is_none_or(|v| v == m) && step.step.is_none_or(|v| v == s))
+            .map(|step| CpuStepping::from_name(step.name.clone()))
+            .unwrap_or(CpuStepping::Unknown(s));
+        let archname = match &self.microarch {
+            Some(name) => self.vendor.micro_arch(name)
+                .unwrap_or_else(|| CpuMicroArch::Other(self.vendor, name.clone())),
+            None => CpuMicroArch::Unknown,
+        };
+        let codename = self.vendor.codename(&self.codename)
+            .unwrap_or_else(|| CpuCodename::Other(self.vendor, self.codename.clone()));
+
+        ProcInfo {
+            codename,
+            archname,
+            step_info,
+            node: self.node.clone(),
+        }
+    }
+}
+
+/* Codename database, `cpu` and `line` are the arrays of entries like codename/codename_db.toml */
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CodenameDb {
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub cpu: Vec<CodenameEntry>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub line: Vec<MicroArchLine>,
+}
+
+#[cfg(feature = "std")]
+static USER_DB: std::sync::RwLock<CodenameDb> = std::sync::RwLock::new(CodenameDb {
+    cpu: Vec::new(),
+    line: Vec::new(),
+});
+
+impl CodenameDb {
+    /* compiled in from codename/codename_db.toml by build.rs */
+    pub const BUILTIN: &'static [CodenameEntry] = include!(concat!(env!("OUT_DIR"), "/codename_db.rs"));
+    pub const BUILTIN_LINES: &'static [MicroArchLine] = include!(concat!(env!("OUT_DIR"), "/micro_arch_line.rs"));
+
+    pub fn find(&self, vendor: DbVendor, f: u32, m: u32, s: u32) -> Option<ProcInfo> {
+        find_entry(&self.cpu, vendor, f, m, s)
+    }
+
+    /* The installed entries are searched before the built-in table by `ProcInfo::from_fms`,
+       replacing the previously installed entries. */
+    #[cfg(feature = "std")]
+    pub fn install(self) {
+        *USER_DB.write().unwrap_or_else(|err| err.into_inner()) = self;
+    }
+
+    /* The installed entries followed by the built-in table, in the search order */
+    pub fn entries() -> Vec<CodenameEntry> {
+        #[cfg(feature = "std")]
+        let mut entries = USER_DB.read().unwrap_or_else(|err| err.into_inner()).cpu.clone();
+        #[cfg(not(feature = "std"))]
+        let mut entries = Vec::new();
+
+        entries.extend_from_slice(Self::BUILTIN);
+        entries
+    }
+
+    /* The installed lines followed by the built-in lines */
+    pub fn lines() -> Vec<MicroArchLine> {
+        #[cfg(feature = "std")]
+        let mut lines = USER_DB.read().unwrap_or_else(|err| err.into_inner()).line.clone();
+        #[cfg(not(feature = "std"))]
+        let mut lines = Vec::new();
+
+        lines.extend_from_slice(Self::BUILTIN_LINES);
+        lines
+    }
+
+    pub(crate) fn lookup(vendor: DbVendor, f: u32, m: u32, s: u32) -> Option<ProcInfo> {
+        #[cfg(feature = "std")]
+        {
+            let user = USER_DB.read().unwrap_or_else(|err| err.into_inner());
+
+            if let Some(info) = find_entry(&user.cpu, vendor, f, m, s) {
+                return Some(info);
+            }
+        }
+
+        find_entry(Self::BUILTIN, vendor, f, m, s)
+    }
+}
+
+fn find_entry(entries: &[CodenameEntry], vendor: DbVendor, f: u32, m: u32, s: u32) -> Option<ProcInfo> {
+    entries.iter().find(|entry| entry.matches(vendor, f, m, s)).map(|entry| entry.proc_info(m, s))
+}
+
+/* "7 nm", "Intel 7" */
+#[cfg(feature = "serde")]
+mod node_str {
+    use super::*;
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    pub fn serialize<S: Serializer>(node: &Option<ProcessNode>, serializer: S) -> Result<S::Ok, S::Error> {
+        match node {
+            Some(ProcessNode::_UM(size)) => serializer.collect_str(&format_args!("{size} um")),
+            Some(ProcessNode::NM(size)) => serializer.collect_str(&format_args!("{size} nm")),
+            Some(ProcessNode::Intel(size)) => serializer.collect_str(&format_args!("Intel {size}")),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<ProcessNode>, D::Error> {
+        Option::<Cow<str>>::deserialize(deserializer)?
+            .map(|s| s.parse().map_err(D::Error::custom))
+            .transpose()
+    }
+}
+
+#[test]
+fn test_codename_db() {
+    #[cfg(not(feature = "std"))]
+    use alloc::vec;
+
+    /* Ryzen 5 5600G */
+    let info = CodenameDb::lookup(DbVendor::Amd, 0x19, 0x50, 0x0).unwrap();
+
+    assert_eq!(info.codename, CpuCodename::Amd(AmdCodename::Cezanne_Barcelo));
+    assert_eq!(info.archname, CpuMicroArch::Amd(AmdMicroArch::Zen3));
+    assert_eq!(info.step_info, CpuStepping::A0);
+    assert_eq!(info.node, Some(ProcessNode::NM(7)));
+
+    /* Xeon Gold 6248R, the stepping-specific entry is used before Sky Lake-SP */
+    let info = CodenameDb::lookup(DbVendor::Intel, 0x6, 0x55, 0x7).unwrap();
+
+    assert_eq!(info.codename, CpuCodename::Intel(IntelCodename::CascadeLake_X));
+    assert_eq!(info.step_info, CpuStepping::B1);
+
+    let info = CodenameDb::lookup(DbVendor::Intel, 0x6, 0x55, 0x4).unwrap();
+
+    assert_eq!(info.codename, CpuCodename::Intel(IntelCodename::SkyLake_X));
+    assert_eq!(info.step_info, CpuStepping::Unknown(0x4));
+
+    /* Hygon C86 7185 */
+    let vendor = CpuVendor::from(&crate::CpuidResult { eax: 0xD, ebx: 0x6F67_7948, ecx: 0x656E_6975, edx: 0x6E65_476E });
+    assert_eq!(vendor, CpuVendor::HygonGenuine);
+    assert!(vendor.is_amd_compatible());
+
+    let info = CodenameDb::lookup(DbVendor::from_cpu_vendor(&vendor).unwrap(), 0x18, 0x0, 0x1).unwrap();
+
+    assert_eq!(info.codename, CpuCodename::Hygon(HygonCodename::Dhyana));
+    assert_eq!(info.node, Some(ProcessNode::NM(14)));
+
+    /* engineering sample */
+    let user = CodenameDb {
+        cpu: vec![CodenameEntry {
+            vendor: DbVendor::Amd,
+            family: 0x19,
+            model: 0x50,
+            model_last: None,
+            step: Some(0x0),
+            step_last: None,
+            codename: Cow::Borrowed("Cezanne ES"),
+            microarch: None,
+            node: None,
+            steppings: Cow::Borrowed(&[]),
+        }],
+        line: Vec::new(),
+    };
+
+    assert_eq!(user.find(DbVendor::Amd, 0x19, 0x50, 0x0).unwrap().codename, CpuCodename::Other(DbVendor::Amd, Cow::Borrowed("Cezanne ES")));
+    assert!(user.find(DbVendor::Amd, 0x19, 0x50, 0x1).is_none());
+}
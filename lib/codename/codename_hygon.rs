@@ -0,0 +1,13 @@
+/* ref: https://github.com/torvalds/linux/blob/master/arch/x86/kernel/cpu/hygon.c */
+
+db_name_enum! {
+    pub enum HygonCodename {
+        Dhyana,
+    }
+}
+
+db_name_enum! {
+    pub enum HygonMicroArch {
+        Dhyana, // derived from AMD Zen
+    }
+}
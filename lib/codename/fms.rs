@@ -1,58 +1,37 @@
 use crate::{cpuid, CpuidResult, CpuVendor};
-use crate::codename::{AmdCodename, IntelCodename, ZhaoxinCodename};
-use crate::codename::{AmdMicroArch, IntelMicroArch, ZhaoxinMicroArch};
+use crate::codename::*;
 #[cfg(feature = "std")]
 use std::fmt;
 
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+use alloc::borrow::Cow;
+#[cfg(feature = "std")]
+use std::borrow::Cow;
+
 impl ProcInfo {
+    /* Look up the codename database, the installed user entries first (see `CodenameDb::install`) */
     pub fn from_fms(fms: &FamModStep, vendor: &CpuVendor) -> Self {
         let [f, m, s] = [fms.syn_fam, fms.syn_mod, fms.step];
-        let vendor = vendor.clone();
-
-        macro_rules! unknown {
-            ($vendor: expr, $family: expr, $model: expr, $step: expr) => {
-                Self {
-                    codename: CpuCodename::Unknown($vendor, $family, $model),
-                    archname: CpuMicroArch::Unknown,
-                    step_info: CpuStepping::Unknown($step),
-                    node: None,
-                }
-            };
-        }
 
-        match vendor {
-            CpuVendor::AuthenticAMD => match f {
-                0x10 => Self::amd_fam10h(m, s),
-                0x11 => Self::amd_fam11h(m, s),
-                0x12 => Self::amd_fam12h(m, s),
-                0x14 => Self::amd_fam14h(m, s),
-                0x15 => Self::amd_fam15h(m, s),
-                0x16 => Self::amd_fam16h(m, s),
-                0x17 => Self::amd_fam17h(m, s),
-                0x19 => Self::amd_fam19h(m, s),
-                _ => unknown!(vendor, f, m, s),
-            },
-            CpuVendor::GenuineIntel => match f {
-                0x5 => Self::intel_fam05h(m, s),
-                0x6 => Self::intel_fam06h(m, s),
-                _ => unknown!(vendor, f, m, s),
-            },
-            CpuVendor::CentaurHauls |
-            CpuVendor::Shanghai => match f {
-                0x6 => Self::zhaoxin_fam06h(m, s),
-                0x7 => Self::zhaoxin_fam07h(m, s),
-                _ => unknown!(vendor, f, m, s),
-            },
-            CpuVendor::Unknown(_) => Self {
-                codename: CpuCodename::Unknown(vendor, f, m),
+        DbVendor::from_cpu_vendor(vendor)
+            .and_then(|db_vendor| CodenameDb::lookup(db_vendor, f, m, s))
+            .unwrap_or_else(|| Self {
+                /* the unknown models of the Zhaoxin Family 6h/7h are reported as Shanghai */
+                codename: match (vendor, f) {
+                    (CpuVendor::CentaurHauls, 0x6 | 0x7) => CpuCodename::Unknown(CpuVendor::Shanghai, f, m),
+                    _ => CpuCodename::Unknown(vendor.clone(), f, m),
+                },
                 archname: CpuMicroArch::Unknown,
                 step_info: CpuStepping::Unknown(s),
                 node: None,
-            },
-        }
+            })
     }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ProcInfo {
     pub codename: CpuCodename,
     pub archname: CpuMicroArch,
@@ -60,13 +39,32 @@ pub struct ProcInfo {
     pub node: Option<ProcessNode>,
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CpuCodename {
     Amd(AmdCodename),
     Intel(IntelCodename),
     Zhaoxin(ZhaoxinCodename),
+    Hygon(HygonCodename),
+    /* a codename of the user codename database, not in the enums */
+    Other(DbVendor, Cow<'static, str>),
     Unknown(CpuVendor, u32, u32),
 }
 
+impl CpuCodename {
+    /* The name in the codename database, e.g. "Cezanne/Barcelo" */
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Self::Amd(codename) => Some(codename.name()),
+            Self::Intel(codename) => Some(codename.name()),
+            Self::Zhaoxin(codename) => Some(codename.name()),
+            Self::Hygon(codename) => Some(codename.name()),
+            Self::Other(_, name) => Some(name),
+            Self::Unknown(..) => None,
+        }
+    }
+}
+
 #[cfg(feature = "std")]
 impl fmt::Display for CpuCodename {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -74,18 +72,50 @@ impl fmt::Display for CpuCodename {
             Self::Amd(arch) => write!(f, "AMD {arch}"),
             Self::Intel(arch) => write!(f, "Intel {arch}"),
             Self::Zhaoxin(arch) => write!(f, "Zhaoxin {arch}"),
+            Self::Hygon(arch) => write!(f, "Hygon {arch}"),
+            Self::Other(vendor, arch) => write!(f, "{} {arch}", vendor.name()),
             Self::Unknown(vendor, fam, model) => write!(f, "{vendor} Fam{fam}h Model{model}h"),
         }
     }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CpuMicroArch {
     Amd(AmdMicroArch),
     Intel(IntelMicroArch),
     Zhaoxin(ZhaoxinMicroArch),
+    Hygon(HygonMicroArch),
+    /* a microarchitecture of the user codename database, not in the enums */
+    Other(DbVendor, Cow<'static, str>),
     Unknown,
 }
 
+impl CpuMicroArch {
+    pub fn db_vendor(&self) -> Option<DbVendor> {
+        match self {
+            Self::Amd(_) => Some(DbVendor::Amd),
+            Self::Intel(_) => Some(DbVendor::Intel),
+            Self::Zhaoxin(_) => Some(DbVendor::Zhaoxin),
+            Self::Hygon(_) => Some(DbVendor::Hygon),
+            Self::Other(vendor, _) => Some(*vendor),
+            Self::Unknown => None,
+        }
+    }
+
+    /* The name in the codename database, e.g. "Zen 3", "GoldenCove + Gracemont" */
+    pub fn name(&self) -> Option<Cow<'_, str>> {
+        match self {
+            Self::Amd(arch) => Some(Cow::Borrowed(arch.name())),
+            Self::Intel(arch) => Some(arch.name()),
+            Self::Zhaoxin(arch) => Some(Cow::Borrowed(arch.name())),
+            Self::Hygon(arch) => Some(Cow::Borrowed(arch.name())),
+            Self::Other(_, name) => Some(Cow::Borrowed(name)),
+            Self::Unknown => None,
+        }
+    }
+}
+
 #[cfg(feature = "std")]
 impl fmt::Display for CpuMicroArch {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -93,14 +123,46 @@ impl fmt::Display for CpuMicroArch {
             Self::Amd(arch) => write!(f, "AMD {arch}"),
             Self::Intel(arch) => write!(f, "Intel {arch}"),
             Self::Zhaoxin(arch) => write!(f, "Zhaoxin {arch}"),
+            Self::Hygon(arch) => write!(f, "Hygon {arch}"),
+            Self::Other(vendor, arch) => write!(f, "{} {arch}", vendor.name()),
             Self::Unknown => write!(f, "Unknown"),
         }
     }
 }
 
-#[derive(Debug)]
-#[allow(non_camel_case_types)]
-pub enum CpuStepping {
+macro_rules! cpu_stepping {
+    ($($step: ident,)*) => {
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        #[allow(non_camel_case_types)]
+        pub enum CpuStepping {
+            $($step,)*
+            /* a stepping name of the user codename database, not in the enum */
+            Other(Cow<'static, str>),
+            Unknown(u32),
+        }
+
+        impl CpuStepping {
+            /* The name in the codename database, e.g. "B2_B3" */
+            pub fn name(&self) -> Option<&str> {
+                match self {
+                    $(Self::$step => Some(stringify!($step)),)*
+                    Self::Other(name) => Some(name),
+                    Self::Unknown(_) => None,
+                }
+            }
+
+            pub fn from_name(name: Cow<'static, str>) -> Self {
+                match name.as_ref() {
+                    $(stringify!($step) => Self::$step,)*
+                    _ => Self::Other(name),
+                }
+            }
+        }
+    };
+}
+
+cpu_stepping! {
     A0,
     A1,
     A0_A1,
@@ -111,6 +173,7 @@ pub enum CpuStepping {
     B3,
     BA,
     C0,
+    C1,
     C2,
     C3,
     D0,
@@ -143,7 +206,6 @@ pub enum CpuStepping {
     HB0,
     HQ0,
     HR0,
-    Unknown(u32),
 }
 
 #[cfg(feature = "std")]
@@ -151,11 +213,13 @@ impl fmt::Display for CpuStepping {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Self::Unknown(val) => write!(f, "{val:X}"),
-            _ => write!(f, "{:?}", self),
+            _ => f.write_str(self.name().unwrap_or_default()),
         }
     }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ProcessNode {
     _UM(u8),
     NM(u8),
@@ -173,6 +237,27 @@ impl fmt::Display for ProcessNode {
     }
 }
 
+/* "7 nm", "1 um", "Intel 7" */
+impl core::str::FromStr for ProcessNode {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (node, size): (fn(u8) -> Self, _) = if let Some(size) = s.strip_suffix("nm") {
+            (Self::NM, size)
+        } else if let Some(size) = s.strip_suffix("um") {
+            (Self::_UM, size)
+        } else if let Some(size) = s.strip_prefix("Intel") {
+            (Self::Intel, size)
+        } else {
+            return Err("expected \"<N> nm\", \"<N> um\" or \"Intel <N>\"");
+        };
+
+        size.trim().parse().map(node).map_err(|_| "invalid process node size")
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FamModStep {
     pub syn_fam: u32,
     pub syn_mod: u32,
@@ -202,3 +287,64 @@ impl FamModStep {
         Self::from(&cpuid!(0x1))
     }
 }
+
+#[test]
+fn test_proc_info_zen5() {
+    /* Synthetic CPUID[Leaf=0x1].EAX of the Family 1Ah entries, not taken from dumps */
+    let list = [
+        /* EPYC 9755 */
+        (0x00B00F21, AmdCodename::Turin, AmdMicroArch::Zen5, CpuStepping::C1, ProcessNode::NM(4)),
+        /* EPYC 9965 */
+        (0x00B10F10, AmdCodename::TurinDense, AmdMicroArch::Zen5c, CpuStepping::B0, ProcessNode::NM(3)),
+        /* Ryzen AI 9 HX 370 */
+        (0x00B20F40, AmdCodename::StrixPoint, AmdMicroArch::Zen5, CpuStepping::B0, ProcessNode::NM(4)),
+        /* Ryzen 9 9950X */
+        (0x00B40F40, AmdCodename::GraniteRidge, AmdMicroArch::Zen5, CpuStepping::B0, ProcessNode::NM(4)),
+        /* Ryzen AI 7 350 */
+        (0x00B60F00, AmdCodename::KrackanPoint, AmdMicroArch::Zen5, CpuStepping::Unknown(0x0), ProcessNode::NM(4)),
+        /* Ryzen AI Max+ 395 */
+        (0x00B70F00, AmdCodename::StrixHalo, AmdMicroArch::Zen5, CpuStepping::Unknown(0x0), ProcessNode::NM(4)),
+    ];
+
+    for (eax, codename, arch, step, node) in list {
+        let info = ProcInfo::from_fms(&FamModStep::from(eax), &CpuVendor::AuthenticAMD);
+
+        assert_eq!(info.codename, CpuCodename::Amd(codename));
+        assert_eq!(info.archname, CpuMicroArch::Amd(arch));
+        assert_eq!(info.step_info, step);
+        assert_eq!(info.node, Some(node));
+    }
+}
+
+#[test]
+fn test_proc_info_typed() {
+    /* every built-in entry maps to the enums, not `Other` */
+    for entry in CodenameDb::BUILTIN {
+        let info = entry.proc_info(entry.model, entry.step.unwrap_or(0));
+
+        assert!(!matches!(info.codename, CpuCodename::Other(..)), "{}", entry.codename);
+        assert!(!matches!(info.archname, CpuMicroArch::Other(..)), "{:?}", entry.microarch);
+        assert!(!matches!(info.step_info, CpuStepping::Other(_)), "{:?}", entry.steppings);
+    }
+
+    /* Core i7-4770, the codename database keeps the previous mapping of Family 6 Model 3Ch */
+    let info = ProcInfo::from_fms(&FamModStep::from(0x000306C3), &CpuVendor::GenuineIntel);
+
+    assert_eq!(info.codename, CpuCodename::Intel(IntelCodename::Haswell_X));
+    assert_eq!(info.archname, CpuMicroArch::Intel(IntelMicroArch::Haswell));
+    assert_eq!(info.step_info, CpuStepping::C0);
+
+    /* Core i7-1260P */
+    let info = ProcInfo::from_fms(&FamModStep::from(0x000906A3), &CpuVendor::GenuineIntel);
+
+    assert_eq!(
+        info.archname,
+        CpuMicroArch::Intel(IntelMicroArch::hybrid(IntelMicroArch::GoldenCove, IntelMicroArch::Gracemont)),
+    );
+    assert_eq!(info.archname.name().unwrap(), "GoldenCove + Gracemont");
+
+    /* VIA Nano, an unknown model of the Zhaoxin Family 6h */
+    let info = ProcInfo::from_fms(&FamModStep::from(0x000006A0), &CpuVendor::CentaurHauls);
+
+    assert_eq!(info.codename, CpuCodename::Unknown(CpuVendor::Shanghai, 0x6, 0xA));
+}
@@ -0,0 +1,250 @@
+use crate::{AmdPkgType, CpuidResult, CpuMicroArch, ProcInfo};
+use super::{CodenameDb, CodenameEntry, DbVendor};
+use core::cmp::Ordering;
+use core::ops::RangeInclusive;
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+use alloc::{borrow::Cow, string::String, vec::Vec};
+#[cfg(feature = "std")]
+use std::borrow::Cow;
+
+/* A series of microarchitectures, `generations` is ordered oldest first */
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MicroArchLine {
+    pub vendor: DbVendor,
+    pub name: Cow<'static, str>,
+    pub generations: Cow<'static, [Cow<'static, [Cow<'static, str>]>]>,
+}
+
+impl MicroArchLine {
+    pub fn generation(&self, microarch: &str) -> Option<MicroArchGen> {
+        let rank = self.generations.iter().position(|gen| gen.iter().any(|name| name == microarch))?;
+
+        Some(MicroArchGen { vendor: self.vendor, line: self.name.clone(), rank })
+    }
+}
+
+/* Generation of a microarchitecture, comparable only within the same vendor and line */
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MicroArchGen {
+    pub vendor: DbVendor,
+    pub line: Cow<'static, str>,
+    pub rank: usize,
+}
+
+impl PartialOrd for MicroArchGen {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.vendor == other.vendor && self.line == other.line {
+            Some(self.rank.cmp(&other.rank))
+        } else {
+            None
+        }
+    }
+}
+
+impl MicroArchGen {
+    /* `microarch` is the name in the codename database, e.g. "Zen 3", "GoldenCove + Gracemont" */
+    pub fn from_microarch(vendor: DbVendor, microarch: &str) -> Option<Self> {
+        CodenameDb::lines()
+            .iter()
+            .filter(|line| line.vendor == vendor)
+            .find_map(|line| line.generation(microarch))
+    }
+
+    /* All generations for a microarchitecture or codename query, e.g. "Zen 3", "Golden Cove", "Alder Lake" */
+    pub fn find_all(query: &str) -> Vec<Self> {
+        let mut gens: Vec<Self> = Vec::new();
+        let mut push = |gen: Self| if !gens.contains(&gen) { gens.push(gen) };
+
+        for line in CodenameDb::lines() {
+            for microarch in line.generations.iter().flat_map(|gen| gen.iter()) {
+                if name_matches(query, microarch) {
+                    push(line.generation(microarch).unwrap());
+                }
+            }
+        }
+
+        for entry in CodenameDb::query(query) {
+            if let Some(gen) = entry.generation() {
+                push(gen);
+            }
+        }
+
+        gens
+    }
+}
+
+impl CodenameDb {
+    /* The entries whose codename or microarch matches the query,
+       ignoring case, spaces, '-' and the codename suffix ("Alder Lake" matches "AlderLake_S") */
+    pub fn query(query: &str) -> Vec<CodenameEntry> {
+        Self::entries()
+            .into_iter()
+            .filter(|entry| {
+                name_matches(query, &entry.codename)
+                || entry.microarch.as_ref().is_some_and(|arch| name_matches(query, arch))
+            })
+            .collect()
+    }
+
+    /* The entries of the microarchitecture, a hybrid entry ("GoldenCove + Gracemont") matches either core */
+    pub fn query_micro_arch(arch: &CpuMicroArch) -> Vec<CodenameEntry> {
+        let (vendor, name) = match (arch.db_vendor(), arch.name()) {
+            (Some(vendor), Some(name)) => (vendor, name),
+            _ => return Vec::new(),
+        };
+
+        Self::entries()
+            .into_iter()
+            .filter(|entry| entry.vendor == vendor && entry.microarch.as_ref().is_some_and(|arch| {
+                *arch == name || arch.split(" + ").any(|core| core == name)
+            }))
+            .collect()
+    }
+}
+
+impl CodenameEntry {
+    pub fn models(&self) -> RangeInclusive<u32> {
+        self.model..=self.model_last.unwrap_or(self.model)
+    }
+
+    /* `None` matches any Stepping */
+    pub fn steps(&self) -> Option<RangeInclusive<u32>> {
+        self.step.map(|step| step..=self.step_last.unwrap_or(step))
+    }
+
+    pub fn generation(&self) -> Option<MicroArchGen> {
+        MicroArchGen::from_microarch(self.vendor, self.microarch.as_ref()?)
+    }
+
+    /* Package types decoded from CPUID[Leaf=0x8000_0001].EBX for the models of the entry, AMD and Hygon only */
+    pub fn pkg_types(&self) -> Vec<AmdPkgType> {
+        let mut pkg_types = Vec::new();
+
+        if !matches!(self.vendor, DbVendor::Amd | DbVendor::Hygon) {
+            return pkg_types;
+        }
+
+        for model in self.models() {
+            let eax = fms_to_eax(self.family, model, self.step.unwrap_or(0));
+
+            for pkg_type in 0x0..=0xF {
+                let pkg = AmdPkgType::from(&CpuidResult { eax, ebx: pkg_type << 28, ecx: 0x0, edx: 0x0 });
+
+                if !matches!(pkg, AmdPkgType::Unknown(_)) && !pkg_types.contains(&pkg) {
+                    pkg_types.push(pkg);
+                }
+            }
+        }
+
+        pkg_types
+    }
+}
+
+impl CpuMicroArch {
+    pub fn generation(&self) -> Option<MicroArchGen> {
+        MicroArchGen::from_microarch(self.db_vendor()?, &self.name()?)
+    }
+}
+
+impl ProcInfo {
+    pub fn generation(&self) -> Option<MicroArchGen> {
+        self.archname.generation()
+    }
+
+    /* `query` is a microarchitecture or codename, e.g. "Zen 3", "Alder Lake",
+       `None` if the database has no generation for the query */
+    pub fn is_same_or_newer(&self, query: &str) -> Option<bool> {
+        let queries = MicroArchGen::find_all(query);
+
+        if queries.is_empty() {
+            return None;
+        }
+
+        Some(self.generation().is_some_and(|gen| queries.iter().any(|q| &gen >= q)))
+    }
+
+    /* `None` if the database has no generation for `arch` */
+    pub fn is_same_or_newer_than(&self, arch: &CpuMicroArch) -> Option<bool> {
+        let target = arch.generation()?;
+
+        Some(self.generation().is_some_and(|gen| gen >= target))
+    }
+}
+
+/* Family/Model/Stepping to CPUID[Leaf=0x1].EAX */
+fn fms_to_eax(f: u32, m: u32, s: u32) -> u32 {
+    let (base_fam, ext_fam) = if 0xF <= f { (0xF, f - 0xF) } else { (f, 0x0) };
+
+    (ext_fam << 20) | ((m >> 4) << 16) | (base_fam << 8) | ((m & 0xF) << 4) | (s & 0xF)
+}
+
+fn normalize(s: &str) -> String {
+    s.chars().filter(|c| !matches!(c, ' ' | '-' | '_')).flat_map(char::to_lowercase).collect()
+}
+
+/* "Golden Cove" matches "GoldenCove + Gracemont", "Skylake" matches "Skylake_AVX512" */
+fn name_matches(query: &str, name: &str) -> bool {
+    let query = normalize(query);
+
+    name.split(" + ").any(|name| {
+        normalize(name) == query || name.split('_').next().is_some_and(|base| normalize(base) == query)
+    })
+}
+
+#[test]
+fn test_micro_arch_gen() {
+    use crate::{AmdMicroArch, IntelMicroArch};
+    #[cfg(not(feature = "std"))]
+    use alloc::string::ToString;
+
+    let codenames = |query: &str| -> Vec<String> {
+        CodenameDb::query(query).iter().map(|entry| entry.codename.to_string()).collect()
+    };
+
+    assert_eq!(codenames("Zen 3"), ["Milan", "Chagall", "Vermeer", "Trento", "Cezanne/Barcelo"]);
+    assert_eq!(codenames("Alder Lake"), ["AlderLake_N", "AlderLake_S", "AlderLake_L"]);
+
+    let milan = &CodenameDb::query("Milan")[0];
+
+    assert_eq!(milan.models(), 0x00..=0x01);
+    assert_eq!(milan.steps(), None);
+    assert_eq!(milan.pkg_types(), [AmdPkgType::SP3, AmdPkgType::STRX4]);
+
+    /* Ryzen 5 5600G */
+    let info = ProcInfo::from_fms(&crate::FamModStep::from(0x00A50F00), &crate::CpuVendor::AuthenticAMD);
+
+    assert_eq!(info.is_same_or_newer("Zen 2"), Some(true));
+    assert_eq!(info.is_same_or_newer("zen3"), Some(true));
+    assert_eq!(info.is_same_or_newer("Zen 3+"), Some(false));
+    assert_eq!(info.is_same_or_newer("Alder Lake"), Some(false));
+    assert_eq!(info.is_same_or_newer("Zne 3"), None);
+
+    assert_eq!(info.is_same_or_newer_than(&CpuMicroArch::Amd(AmdMicroArch::Zen2)), Some(true));
+    assert_eq!(info.is_same_or_newer_than(&CpuMicroArch::Amd(AmdMicroArch::Zen4)), Some(false));
+    assert_eq!(info.is_same_or_newer_than(&CpuMicroArch::Intel(IntelMicroArch::Skylake)), Some(false));
+    assert_eq!(info.is_same_or_newer_than(&CpuMicroArch::Amd(AmdMicroArch::_Reserved)), None);
+
+    let codenames: Vec<String> = CodenameDb::query_micro_arch(&CpuMicroArch::Intel(IntelMicroArch::Gracemont))
+        .iter()
+        .map(|entry| entry.codename.to_string())
+        .collect();
+
+    assert_eq!(codenames, ["AlderLake_N", "AlderLake_S", "AlderLake_L", "RaptorLake_S", "RaptorLake_P", "RaptorLake_S_BFH"]);
+
+    /* every microarchitecture of the built-in entries has a generation */
+    for entry in CodenameDb::BUILTIN {
+        assert!(entry.microarch.is_none() || entry.generation().is_some(), "{:?}", entry.microarch);
+    }
+
+    let zen = MicroArchGen::from_microarch(DbVendor::Amd, "Zen").unwrap();
+    let jaguar = MicroArchGen::from_microarch(DbVendor::Amd, "Jaguar").unwrap();
+
+    assert!(zen < info.generation().unwrap());
+    assert_eq!(zen.partial_cmp(&jaguar), None);
+}
@@ -1,3 +1,47 @@
+/*
+    The enum of the names used in codename/codename_db.toml.
+    `name()` is the name in the database (`Display` is the same), `Variant => "name"` if it differs from the variant.
+*/
+macro_rules! db_name_enum {
+    (
+        $(#[$attr: meta])*
+        pub enum $name: ident {
+            $($variant: ident $(=> $str: literal)?,)*
+        }
+    ) => {
+        $(#[$attr])*
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[allow(non_camel_case_types)]
+        pub enum $name {
+            $($variant,)*
+        }
+
+        impl $name {
+            pub const ALL: &'static [Self] = &[$(Self::$variant,)*];
+
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => db_name_enum!(@name $variant $($str)?),)*
+                }
+            }
+
+            pub fn from_name(name: &str) -> Option<Self> {
+                Self::ALL.iter().find(|v| v.name() == name).copied()
+            }
+        }
+
+        #[cfg(feature = "std")]
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str(self.name())
+            }
+        }
+    };
+    (@name $variant: ident $str: literal) => { $str };
+    (@name $variant: ident) => { stringify!($variant) };
+}
+
 mod fms;
 pub use fms::*;
 
@@ -9,3 +53,12 @@ pub use codename_intel::*;
 
 mod codename_zhaoxin;
 pub use codename_zhaoxin::*;
+
+mod codename_hygon;
+pub use codename_hygon::*;
+
+mod codename_db;
+pub use codename_db::*;
+
+mod micro_arch_gen;
+pub use micro_arch_gen::*;
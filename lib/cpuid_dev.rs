@@ -0,0 +1,80 @@
+use crate::{CpuidResult, Error};
+
+use std::fs::File;
+use std::os::unix::fs::FileExt;
+
+/* Linux cpuid driver: https://github.com/torvalds/linux/blob/master/arch/x86/kernel/cpuid.c */
+/*
+    The file offset selects the input, Leaf: offset[31:0], SubLeaf: offset[63:32],
+    and a read of 16 bytes returns EAX, EBX, ECX, EDX.
+    The kernel executes CPUID on the target CPU, so this works without changing the
+    affinity of the calling thread, and for CPUs outside of its affinity mask.
+*/
+#[derive(Debug)]
+pub struct CpuidDev {
+    pub cpu: usize,
+    file: File,
+}
+
+impl CpuidDev {
+    const DEV_DIR: &'static str = "/dev/cpu";
+
+    pub fn open(cpu: usize) -> Result<Self, Error> {
+        let file = File::open(format!("{}/{cpu}/cpuid", Self::DEV_DIR))
+            .map_err(|source| Error::CpuidDev { cpu, source })?;
+
+        Ok(Self { cpu, file })
+    }
+
+    pub fn read(&self, leaf: u32, sub_leaf: u32) -> Result<CpuidResult, Error> {
+        let mut buf = [0u8; 16];
+        let offset = (leaf as u64) | ((sub_leaf as u64) << 32);
+
+        self.file.read_exact_at(&mut buf, offset)
+            .map_err(|source| Error::CpuidDev { cpu: self.cpu, source })?;
+
+        let [eax, ebx, ecx, edx] = [0, 4, 8, 12].map(|pos| {
+            u32::from_le_bytes([buf[pos], buf[pos+1], buf[pos+2], buf[pos+3]])
+        });
+
+        Ok(CpuidResult { eax, ebx, ecx, edx })
+    }
+
+    /// List of CPUs that have `/dev/cpu/<N>/cpuid`, regardless of the affinity mask.
+    pub fn cpu_list() -> Result<Vec<usize>, Error> {
+        let mut cpus: Vec<usize> = Vec::with_capacity(256);
+
+        for entry in std::fs::read_dir(Self::DEV_DIR)? {
+            let path = entry?.path();
+
+            let cpu = match path.file_name().and_then(|name| name.to_str()?.parse::<usize>().ok()) {
+                Some(cpu) => cpu,
+                None => continue,
+            };
+
+            if path.join("cpuid").exists() {
+                cpus.push(cpu);
+            }
+        }
+
+        cpus.sort_unstable();
+
+        Ok(cpus)
+    }
+
+    /// The cpuid driver is loaded and readable by the current user.
+    pub fn available() -> bool {
+        let cpu = match Self::cpu_list() {
+            Ok(list) => match list.first() {
+                Some(cpu) => *cpu,
+                None => return false,
+            },
+            Err(_) => return false,
+        };
+
+        match Self::open(cpu) {
+            Ok(dev) => dev.read(0x0, 0x0).is_ok(),
+            Err(_) => false,
+        }
+    }
+}
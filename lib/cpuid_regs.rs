@@ -0,0 +1,88 @@
+use crate::CpuidResult;
+
+/*
+    `CpuidResult` is defined in `core::arch`, so it can not implement foreign traits.
+    `CpuidRegs` has the same layout and provides them, e.g. the serde traits.
+    Schema: { "eax": u32, "ebx": u32, "ecx": u32, "edx": u32 }
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CpuidRegs {
+    pub eax: u32,
+    pub ebx: u32,
+    pub ecx: u32,
+    pub edx: u32,
+}
+
+impl From<CpuidResult> for CpuidRegs {
+    fn from(cpuid: CpuidResult) -> Self {
+        let CpuidResult { eax, ebx, ecx, edx } = cpuid;
+
+        Self { eax, ebx, ecx, edx }
+    }
+}
+
+impl From<&CpuidResult> for CpuidRegs {
+    fn from(cpuid: &CpuidResult) -> Self {
+        Self::from(*cpuid)
+    }
+}
+
+impl From<CpuidRegs> for CpuidResult {
+    fn from(regs: CpuidRegs) -> Self {
+        let CpuidRegs { eax, ebx, ecx, edx } = regs;
+
+        Self { eax, ebx, ecx, edx }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CpuidReg {
+    EAX,
+    EBX,
+    ECX,
+    EDX,
+}
+
+impl CpuidReg {
+    pub fn get(&self, cpuid: &CpuidResult) -> u32 {
+        match self {
+            Self::EAX => cpuid.eax,
+            Self::EBX => cpuid.ebx,
+            Self::ECX => cpuid.ecx,
+            Self::EDX => cpuid.edx,
+        }
+    }
+}
+
+/// For `#[serde(with = "libcpuid_dump::cpuid_result_serde")]` on a `CpuidResult` field,
+/// serialized in the same schema as `CpuidRegs`.
+#[cfg(feature = "serde")]
+pub mod cpuid_result_serde {
+    use super::CpuidRegs;
+    use crate::CpuidResult;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(cpuid: &CpuidResult, serializer: S) -> Result<S::Ok, S::Error> {
+        CpuidRegs::from(cpuid).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<CpuidResult, D::Error> {
+        CpuidRegs::deserialize(deserializer).map(CpuidResult::from)
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_cpuid_regs_serde() {
+    /* Ryzen 5 5600G, CPUID[Leaf=0x1] */
+    let cpuid = CpuidResult { eax: 0x00A50F00, ebx: 0x0A0C0800, ecx: 0x7EF8320B, edx: 0x178BFBFF };
+    let json = serde_json::to_string(&CpuidRegs::from(cpuid)).unwrap();
+
+    assert_eq!(json, r#"{"eax":10817280,"ebx":168560640,"ecx":2130194955,"edx":395049983}"#);
+
+    let regs: CpuidRegs = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(CpuidResult::from(regs), cpuid);
+}
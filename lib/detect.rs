@@ -0,0 +1,285 @@
+use crate::{CpuidReg, CpuidResult, CpuFeature, FeatureSet, MicroArchLevel};
+use core::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+
+/* The flags for the runtime dispatch, named after `#[target_feature]` where it exists */
+impl CpuFeature {
+    /* 00_01_EDX */
+    pub const SSE: Self = Self::new(0x1, 0x0, CpuidReg::EDX, 25);
+    pub const SSE2: Self = Self::new(0x1, 0x0, CpuidReg::EDX, 26);
+    /* 00_01_ECX */
+    pub const SSE3: Self = Self::new(0x1, 0x0, CpuidReg::ECX, 0);
+    pub const PCLMULQDQ: Self = Self::new(0x1, 0x0, CpuidReg::ECX, 1);
+    pub const SSSE3: Self = Self::new(0x1, 0x0, CpuidReg::ECX, 9);
+    pub const FMA: Self = Self::new(0x1, 0x0, CpuidReg::ECX, 12);
+    pub const CMPXCHG16B: Self = Self::new(0x1, 0x0, CpuidReg::ECX, 13);
+    pub const SSE4_1: Self = Self::new(0x1, 0x0, CpuidReg::ECX, 19);
+    pub const SSE4_2: Self = Self::new(0x1, 0x0, CpuidReg::ECX, 20);
+    pub const MOVBE: Self = Self::new(0x1, 0x0, CpuidReg::ECX, 22);
+    pub const POPCNT: Self = Self::new(0x1, 0x0, CpuidReg::ECX, 23);
+    pub const AES: Self = Self::new(0x1, 0x0, CpuidReg::ECX, 25);
+    pub const XSAVE: Self = Self::new(0x1, 0x0, CpuidReg::ECX, 26);
+    pub const OSXSAVE: Self = Self::new(0x1, 0x0, CpuidReg::ECX, 27);
+    pub const AVX: Self = Self::new(0x1, 0x0, CpuidReg::ECX, 28);
+    pub const F16C: Self = Self::new(0x1, 0x0, CpuidReg::ECX, 29);
+    pub const RDRAND: Self = Self::new(0x1, 0x0, CpuidReg::ECX, 30);
+    /* 00_07_EBX */
+    pub const BMI1: Self = Self::new(0x7, 0x0, CpuidReg::EBX, 3);
+    pub const AVX2: Self = Self::new(0x7, 0x0, CpuidReg::EBX, 5);
+    pub const BMI2: Self = Self::new(0x7, 0x0, CpuidReg::EBX, 8);
+    pub const AVX512F: Self = Self::new(0x7, 0x0, CpuidReg::EBX, 16);
+    pub const AVX512DQ: Self = Self::new(0x7, 0x0, CpuidReg::EBX, 17);
+    pub const RDSEED: Self = Self::new(0x7, 0x0, CpuidReg::EBX, 18);
+    pub const ADX: Self = Self::new(0x7, 0x0, CpuidReg::EBX, 19);
+    pub const AVX512IFMA: Self = Self::new(0x7, 0x0, CpuidReg::EBX, 21);
+    pub const AVX512CD: Self = Self::new(0x7, 0x0, CpuidReg::EBX, 28);
+    pub const SHA: Self = Self::new(0x7, 0x0, CpuidReg::EBX, 29);
+    pub const AVX512BW: Self = Self::new(0x7, 0x0, CpuidReg::EBX, 30);
+    pub const AVX512VL: Self = Self::new(0x7, 0x0, CpuidReg::EBX, 31);
+    /* 00_07_ECX */
+    pub const AVX512VBMI: Self = Self::new(0x7, 0x0, CpuidReg::ECX, 1);
+    pub const AVX512VBMI2: Self = Self::new(0x7, 0x0, CpuidReg::ECX, 6);
+    pub const GFNI: Self = Self::new(0x7, 0x0, CpuidReg::ECX, 8);
+    pub const VAES: Self = Self::new(0x7, 0x0, CpuidReg::ECX, 9);
+    pub const VPCLMULQDQ: Self = Self::new(0x7, 0x0, CpuidReg::ECX, 10);
+    pub const AVX512VNNI: Self = Self::new(0x7, 0x0, CpuidReg::ECX, 11);
+    pub const AVX512BITALG: Self = Self::new(0x7, 0x0, CpuidReg::ECX, 12);
+    pub const AVX512VPOPCNTDQ: Self = Self::new(0x7, 0x0, CpuidReg::ECX, 14);
+    /* 00_07_EDX */
+    pub const AVX512VP2INTERSECT: Self = Self::new(0x7, 0x0, CpuidReg::EDX, 8);
+    pub const AMX_BF16: Self = Self::new(0x7, 0x0, CpuidReg::EDX, 22);
+    pub const AVX512FP16: Self = Self::new(0x7, 0x0, CpuidReg::EDX, 23);
+    pub const AMX_TILE: Self = Self::new(0x7, 0x0, CpuidReg::EDX, 24);
+    pub const AMX_INT8: Self = Self::new(0x7, 0x0, CpuidReg::EDX, 25);
+    /* 00_07_EAX_x1 */
+    pub const AVXVNNI: Self = Self::new(0x7, 0x1, CpuidReg::EAX, 4);
+    pub const AVX512BF16: Self = Self::new(0x7, 0x1, CpuidReg::EAX, 5);
+    /* 80_01_ECX, AMD */
+    pub const LAHF_SAHF: Self = Self::new(0x8000_0001, 0x0, CpuidReg::ECX, 0);
+    pub const LZCNT: Self = Self::new(0x8000_0001, 0x0, CpuidReg::ECX, 5);
+    pub const SSE4A: Self = Self::new(0x8000_0001, 0x0, CpuidReg::ECX, 6);
+    pub const XOP: Self = Self::new(0x8000_0001, 0x0, CpuidReg::ECX, 11);
+    pub const FMA4: Self = Self::new(0x8000_0001, 0x0, CpuidReg::ECX, 16);
+    pub const TBM: Self = Self::new(0x8000_0001, 0x0, CpuidReg::ECX, 21);
+    /* C0_01_EDX, Zhaoxin/VIA PadLock and GMI, "*_EN": enabled by the BIOS */
+    pub const SM2: Self = Self::new(0xC000_0001, 0x0, CpuidReg::EDX, 0);
+    pub const SM2_EN: Self = Self::new(0xC000_0001, 0x0, CpuidReg::EDX, 1);
+    pub const RNG: Self = Self::new(0xC000_0001, 0x0, CpuidReg::EDX, 2);
+    pub const RNG_EN: Self = Self::new(0xC000_0001, 0x0, CpuidReg::EDX, 3);
+    pub const CCS: Self = Self::new(0xC000_0001, 0x0, CpuidReg::EDX, 4);
+    pub const CCS_EN: Self = Self::new(0xC000_0001, 0x0, CpuidReg::EDX, 5);
+    pub const ACE: Self = Self::new(0xC000_0001, 0x0, CpuidReg::EDX, 6);
+    pub const ACE_EN: Self = Self::new(0xC000_0001, 0x0, CpuidReg::EDX, 7);
+    pub const ACE2: Self = Self::new(0xC000_0001, 0x0, CpuidReg::EDX, 8);
+    pub const ACE2_EN: Self = Self::new(0xC000_0001, 0x0, CpuidReg::EDX, 9);
+    pub const PHE: Self = Self::new(0xC000_0001, 0x0, CpuidReg::EDX, 10);
+    pub const PHE_EN: Self = Self::new(0xC000_0001, 0x0, CpuidReg::EDX, 11);
+    pub const PMM: Self = Self::new(0xC000_0001, 0x0, CpuidReg::EDX, 12);
+    pub const PMM_EN: Self = Self::new(0xC000_0001, 0x0, CpuidReg::EDX, 13);
+}
+
+/* XCR0 */
+const XSTATE_SSE: u64 = 1 << 1;
+const XSTATE_AVX: u64 = 1 << 2;
+const XSTATE_AVX512: u64 = (1 << 5) | (1 << 6) | (1 << 7);
+const XSTATE_AMX: u64 = (1 << 17) | (1 << 18);
+
+/// The feature flags of the running processor, and the register states enabled by the OS (XCR0)
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct HostFeatures {
+    pub set: FeatureSet,
+    /// 0 if the OS does not enable XSAVE (OSXSAVE)
+    pub xcr0: u64,
+}
+
+impl HostFeatures {
+    pub fn from_cpuid_with<F: Fn(u32, u32) -> CpuidResult>(cpuid: &F, xcr0: u64) -> Self {
+        Self { set: FeatureSet::from_cpuid_with(cpuid), xcr0 }
+    }
+
+    /// Not cached, see `detect()`
+    pub fn get() -> Self {
+        let set = FeatureSet::get();
+        let xcr0 = if set.has(CpuFeature::OSXSAVE) { unsafe { xgetbv0() } } else { 0 };
+
+        Self { set, xcr0 }
+    }
+
+    /* The states the instructions need to be enabled in XCR0 */
+    fn xstate(ftr: CpuFeature) -> u64 {
+        const AVX: &[CpuFeature] = &[
+            CpuFeature::FMA, CpuFeature::AVX, CpuFeature::F16C, CpuFeature::AVX2,
+            CpuFeature::VAES, CpuFeature::VPCLMULQDQ, CpuFeature::AVXVNNI, CpuFeature::XOP, CpuFeature::FMA4,
+        ];
+        const AVX512: &[CpuFeature] = &[
+            CpuFeature::AVX512F, CpuFeature::AVX512DQ, CpuFeature::AVX512IFMA, CpuFeature::AVX512CD,
+            CpuFeature::AVX512BW, CpuFeature::AVX512VL, CpuFeature::AVX512VBMI, CpuFeature::AVX512VBMI2,
+            CpuFeature::AVX512VNNI, CpuFeature::AVX512BITALG, CpuFeature::AVX512VPOPCNTDQ,
+            CpuFeature::AVX512VP2INTERSECT, CpuFeature::AVX512FP16, CpuFeature::AVX512BF16,
+        ];
+        const AMX: &[CpuFeature] = &[CpuFeature::AMX_BF16, CpuFeature::AMX_TILE, CpuFeature::AMX_INT8];
+
+        if AVX.contains(&ftr) {
+            XSTATE_SSE | XSTATE_AVX
+        } else if AVX512.contains(&ftr) {
+            XSTATE_SSE | XSTATE_AVX | XSTATE_AVX512
+        } else if AMX.contains(&ftr) {
+            XSTATE_AMX
+        } else {
+            0
+        }
+    }
+
+    /// The processor reports the feature flag
+    pub fn has(&self, ftr: CpuFeature) -> bool {
+        self.set.has(ftr)
+    }
+
+    /// The processor reports the feature flag, and the OS saves the registers used by it.
+    /// Linux also requires `arch_prctl(ARCH_REQ_XCOMP_PERM)` for AMX.
+    pub fn is_usable(&self, ftr: CpuFeature) -> bool {
+        let xstate = Self::xstate(ftr);
+
+        self.has(ftr) && (self.xcr0 & xstate) == xstate
+    }
+
+    pub fn is_usable_all(&self, ftrs: &[CpuFeature]) -> bool {
+        ftrs.iter().all(|ftr| self.is_usable(*ftr))
+    }
+
+    pub fn is_usable_level(&self, level: MicroArchLevel) -> bool {
+        level != MicroArchLevel::X86_64_V0 && level.feature_set().iter().all(|ftr| self.is_usable(ftr))
+    }
+}
+
+#[target_feature(enable = "xsave")]
+unsafe fn xgetbv0() -> u64 {
+    core::arch::x86_64::_xgetbv(0)
+}
+
+/* The threads racing on the first call store the same values */
+static DETECTED: AtomicBool = AtomicBool::new(false);
+static WORDS: [AtomicU32; FeatureSet::WORDS.len()] = [const { AtomicU32::new(0) }; FeatureSet::WORDS.len()];
+static XCR0: AtomicU64 = AtomicU64::new(0);
+
+/// `HostFeatures` of the running processor, CPUID and XGETBV are executed only on the first call.
+/// Available without `std`.
+///
+/// ```
+/// use libcpuid_dump::{select, CpuFeature};
+///
+/// fn sum_scalar(v: &[f32]) -> f32 { v.iter().sum() }
+/// fn sum_avx2(v: &[f32]) -> f32 { /* #[target_feature(enable = "avx2,fma")] */ sum_scalar(v) }
+/// fn sum_avx512(v: &[f32]) -> f32 { sum_scalar(v) }
+///
+/// let sum: fn(&[f32]) -> f32 = select! {
+///     [CpuFeature::AVX512F] => sum_avx512,
+///     [CpuFeature::AVX2, CpuFeature::FMA] => sum_avx2,
+///     _ => sum_scalar,
+/// };
+///
+/// assert_eq!(sum(&[1.0, 2.0]), 3.0);
+/// ```
+pub fn detect() -> HostFeatures {
+    if !DETECTED.load(Ordering::Acquire) {
+        let host = HostFeatures::get();
+
+        for (cache, word) in WORDS.iter().zip(host.set.words()) {
+            cache.store(*word, Ordering::Relaxed);
+        }
+        XCR0.store(host.xcr0, Ordering::Relaxed);
+        DETECTED.store(true, Ordering::Release);
+
+        return host;
+    }
+
+    let mut words = [0u32; FeatureSet::WORDS.len()];
+
+    for (word, cache) in words.iter_mut().zip(WORDS.iter()) {
+        *word = cache.load(Ordering::Relaxed);
+    }
+
+    HostFeatures { set: FeatureSet::from_words(words), xcr0: XCR0.load(Ordering::Relaxed) }
+}
+
+/// Choose the first implementation whose features are all usable (`HostFeatures::is_usable_all`)
+/// on the running processor, the last arm `_ => ..` is the fallback.
+/// Resolve it once and keep the function pointer out of the hot loop.
+#[macro_export]
+macro_rules! select {
+    ($([$($ftr: expr),+ $(,)?] => $imp: expr,)+ _ => $default: expr $(,)?) => {{
+        let host = $crate::detect();
+
+        $(if host.is_usable_all(&[$($ftr),+]) { $imp } else)+ { $default }
+    }};
+}
+
+#[test]
+fn test_host_features() {
+    let zero = CpuidResult { eax: 0x0, ebx: 0x0, ecx: 0x0, edx: 0x0 };
+
+    /* "  Shanghai  ", PadLock RNG, ACE, ACE2, PHE, PMM and GMI enabled */
+    let zhaoxin = |leaf, _| match leaf {
+        0x0 => CpuidResult { eax: 0x0000000D, ebx: 0x68532020, ecx: 0x20206961, edx: 0x68676E61 },
+        0xC000_0000 => CpuidResult { eax: 0xC0000001, ebx: 0x0, ecx: 0x0, edx: 0x0 },
+        0xC000_0001 => CpuidResult { eax: 0x0, ebx: 0x0, ecx: 0x0, edx: 0x00003FFF },
+        _ => zero,
+    };
+    let host = HostFeatures::from_cpuid_with(&zhaoxin, 0x7);
+
+    assert!(host.is_usable_all(&[CpuFeature::RNG, CpuFeature::RNG_EN, CpuFeature::CCS, CpuFeature::PHE_EN]));
+
+    assert_eq!(detect(), detect());
+    assert_eq!(detect(), HostFeatures::get());
+
+    let f: fn() -> u8 = select! {
+        [CpuFeature::AVX512F, CpuFeature::AVX512VL] => || 2,
+        [CpuFeature::AVX2] => || 1,
+        _ => || 0,
+    };
+
+    assert_eq!(
+        f(),
+        if detect().is_usable_all(&[CpuFeature::AVX512F, CpuFeature::AVX512VL]) {
+            2
+        } else {
+            detect().is_usable(CpuFeature::AVX2) as u8
+        },
+    );
+}
+
+/* the 5600G fixture and `is_x86_feature_detected!` need std */
+#[cfg(feature = "std")]
+#[test]
+fn test_host_features_std() {
+    /* Ryzen 5 5600G */
+    let snapshot = crate::CpuidSnapshot::ryzen_5_5600g();
+    let zen3 = |leaf, sub_leaf| match leaf {
+        /* the highest basic leaf on Intel, must not be read as Zhaoxin features */
+        0xC000_0000 | 0xC000_0001 => CpuidResult { eax: 0x0, ebx: 0x0, ecx: 0x0, edx: 0xFFFF_FFFF },
+        _ => snapshot.cpuid(leaf, sub_leaf),
+    };
+    /* x87, SSE, AVX, PKRU */
+    let host = HostFeatures::from_cpuid_with(&zen3, 0x207);
+
+    assert!(host.is_usable_all(&[CpuFeature::AVX2, CpuFeature::FMA, CpuFeature::SSE4A]));
+    assert!(!host.has(CpuFeature::FMA4) && !host.has(CpuFeature::XOP));
+    assert!(!host.has(CpuFeature::AVX512F));
+    assert!(!host.has(CpuFeature::RNG));
+    assert!(host.is_usable_level(MicroArchLevel::X86_64_V3));
+    assert!(!host.is_usable_level(MicroArchLevel::X86_64_V4));
+
+    /* the OS does not save the AVX registers */
+    let host = HostFeatures { xcr0: 0x3, ..host };
+
+    assert!(host.has(CpuFeature::AVX2) && !host.is_usable(CpuFeature::AVX2));
+    assert!(host.is_usable(CpuFeature::SSE4_2) && host.is_usable(CpuFeature::BMI2));
+    assert!(!host.is_usable_level(MicroArchLevel::X86_64_V3));
+
+    /* cross-check with the std runtime detection */
+    assert_eq!(detect().is_usable(CpuFeature::AVX2), std::is_x86_feature_detected!("avx2"));
+    assert_eq!(
+        detect().is_usable_all(&[CpuFeature::AVX512F, CpuFeature::AVX512VL]),
+        std::is_x86_feature_detected!("avx512f") && std::is_x86_feature_detected!("avx512vl"),
+    );
+}
@@ -0,0 +1,181 @@
+use crate::{CpuidResult, CpuidReg, CpuVendor, FamModStep, ProcInfo};
+use crate::codename::DbVendor;
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/* ref: https://www.intel.com/content/www/us/en/support/articles/000059422/processors.html */
+/* ref: https://github.com/torvalds/linux/blob/master/arch/x86/kernel/cpu/amd.c */
+/* ref: https://github.com/torvalds/linux/blob/master/arch/x86/kernel/cpu/tsx.c */
+
+/* The erratum is observable only if the CPUID bit is in this state */
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CpuidBit {
+    pub leaf: u32,
+    pub sub_leaf: u32,
+    pub reg: CpuidReg,
+    pub bit: u32,
+    pub set: bool,
+}
+
+impl CpuidBit {
+    fn check<F: Fn(u32, u32) -> CpuidResult>(&self, cpuid: &F) -> bool {
+        let base = self.leaf & 0xF000_0000;
+        let max_leaf = cpuid(base, 0x0).eax;
+
+        if (max_leaf & 0xF000_0000) != base || max_leaf < self.leaf {
+            return false;
+        }
+
+        ((self.reg.get(&cpuid(self.leaf, self.sub_leaf)) >> self.bit) & 1 == 1) == self.set
+    }
+}
+
+/* Known errata matched by the codename and the stepping name of the codename database */
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Erratum {
+    pub id: &'static str,
+    pub vendor: DbVendor,
+    /* empty for any codename of the vendor */
+    pub codenames: &'static [&'static str],
+    /* empty for any stepping */
+    pub steppings: &'static [&'static str],
+    pub cpuid: Option<CpuidBit>,
+    pub desc: &'static str,
+}
+
+const fn bit(leaf: u32, sub_leaf: u32, reg: CpuidReg, bit: u32, set: bool) -> Option<CpuidBit> {
+    Some(CpuidBit { leaf, sub_leaf, reg, bit, set })
+}
+
+impl Erratum {
+    pub const LIST: &'static [Self] = &[
+        Self {
+            id: "AMD-298",
+            vendor: DbVendor::Amd,
+            codenames: &["DR"],
+            steppings: &["B2"],
+            cpuid: None,
+            desc: "Erratum 298: L2 eviction of a TLB entry may hang the system, the BIOS workaround is slow (fixed in B3)",
+        },
+        Self {
+            id: "ZENBLEED",
+            vendor: DbVendor::Amd,
+            codenames: &["Rome", "Renoir", "Lucienne", "Matisse", "VanGogh", "Mendocino"],
+            steppings: &[],
+            cpuid: None,
+            desc: "Zenbleed (CVE-2023-20593): vector registers may leak across contexts without the microcode update",
+        },
+        Self {
+            id: "ZEN2-RDRAND",
+            vendor: DbVendor::Amd,
+            codenames: &["Matisse"],
+            steppings: &[],
+            cpuid: bit(0x1, 0x0, CpuidReg::ECX, 30, true),
+            desc: "RDRAND is enumerated but may always return 0xFFFFFFFF with firmware before AGESA 1.0.0.3 ABBA",
+        },
+        Self {
+            id: "ZEN5-RDSEED",
+            vendor: DbVendor::Amd,
+            codenames: &["Turin", "TurinDense", "StrixPoint", "GraniteRidge", "KrackanPoint", "StrixHalo"],
+            steppings: &[],
+            cpuid: bit(0x7, 0x0, CpuidReg::EBX, 18, true),
+            desc: "RDSEED (16/32-bit forms) may return 0 with CF=1 without the microcode update (AMD-SB-7055)",
+        },
+        Self {
+            id: "HSW-TSX",
+            vendor: DbVendor::Intel,
+            codenames: &["Haswell", "Haswell_X", "Haswell_L", "Haswell_G", "Broadwell", "Broadwell_G"],
+            steppings: &[],
+            cpuid: bit(0x7, 0x0, CpuidReg::EBX, 11, false),
+            desc: "TSX (HLE, RTM) is not enumerated: disabled by microcode for erratum HSW136, or not on this SKU",
+        },
+        Self {
+            id: "RTM-ALWAYS-ABORT",
+            vendor: DbVendor::Intel,
+            codenames: &[],
+            steppings: &[],
+            cpuid: bit(0x7, 0x0, CpuidReg::EDX, 11, true),
+            desc: "TSX is disabled by microcode: XBEGIN always aborts even if RTM is enumerated",
+        },
+        Self {
+            id: "TSX-FORCE-ABORT",
+            vendor: DbVendor::Intel,
+            codenames: &[],
+            steppings: &[],
+            cpuid: bit(0x7, 0x0, CpuidReg::EDX, 13, true),
+            desc: "TSX_FORCE_ABORT MSR: microcode for the PMC3 erratum, RTM may be forced to abort",
+        },
+        Self {
+            id: "ADL-AVX512",
+            vendor: DbVendor::Intel,
+            codenames: &["AlderLake_S", "AlderLake_L"],
+            steppings: &[],
+            cpuid: bit(0x7, 0x0, CpuidReg::EBX, 16, true),
+            desc: "AVX-512 is enumerated with the E-cores disabled by early firmware, removed by later microcode",
+        },
+    ];
+
+    fn matches(&self, vendor: DbVendor, codename: &str, step: Option<&str>) -> bool {
+        self.vendor == vendor
+            && (self.codenames.is_empty() || self.codenames.contains(&codename))
+            && (self.steppings.is_empty() || step.is_some_and(|step| self.steppings.contains(&step)))
+    }
+
+    pub fn find_with<F: Fn(u32, u32) -> CpuidResult>(cpuid: &F) -> Vec<&'static Self> {
+        let vendor = CpuVendor::from(&cpuid(0x0, 0x0));
+        let db_vendor = match DbVendor::from_cpu_vendor(&vendor) {
+            Some(v) => v,
+            None => return Vec::new(),
+        };
+        let info = ProcInfo::from_fms(&FamModStep::from(&cpuid(0x1, 0x0)), &vendor);
+        let codename = info.codename.name().unwrap_or("");
+        let step = info.step_info.name();
+
+        Self::LIST.iter()
+            .filter(|erratum| erratum.matches(db_vendor, codename, step))
+            .filter(|erratum| erratum.cpuid.is_none_or(|bit| bit.check(cpuid)))
+            .collect()
+    }
+
+    pub fn get() -> Vec<&'static Self> {
+        Self::find_with(&|leaf, sub_leaf| cpuid!(leaf, sub_leaf))
+    }
+}
+
+#[test]
+fn test_errata() {
+    let ids = |cpuid: &dyn Fn(u32, u32) -> CpuidResult| -> Vec<&str> {
+        Erratum::find_with(&cpuid).iter().map(|erratum| erratum.id).collect()
+    };
+    let zero = CpuidResult { eax: 0x0, ebx: 0x0, ecx: 0x0, edx: 0x0 };
+
+    /* Ryzen 5 5600G */
+    #[cfg(feature = "std")]
+    {
+        let snapshot = crate::CpuidSnapshot::ryzen_5_5600g();
+        assert!(ids(&|leaf, sub_leaf| snapshot.cpuid(leaf, sub_leaf)).is_empty());
+    }
+
+    /* Ryzen 7 3700X */
+    let cpuid = |leaf: u32, _| match leaf {
+        0x0 => CpuidResult { eax: 0x00000010, ebx: 0x68747541, ecx: 0x444D4163, edx: 0x69746E65 },
+        0x1 => CpuidResult { eax: 0x00870F10, ebx: 0x00100800, ecx: 0x7ED8320B, edx: 0x178BFBFF },
+        0x7 => CpuidResult { eax: 0x00000000, ebx: 0x219C91A9, ecx: 0x00400004, edx: 0x00000000 },
+        _ => zero,
+    };
+    assert_eq!(ids(&cpuid), ["ZENBLEED", "ZEN2-RDRAND"]);
+
+    /* Core i7-6700K with the microcode of June 2021 */
+    let cpuid = |leaf: u32, _| match leaf {
+        0x0 => CpuidResult { eax: 0x00000016, ebx: 0x756E6547, ecx: 0x6C65746E, edx: 0x49656E69 },
+        0x1 => CpuidResult { eax: 0x000506E3, ebx: 0x00100800, ecx: 0x7FFAFBFF, edx: 0xBFEBFBFF },
+        0x7 => CpuidResult { eax: 0x00000000, ebx: 0x029C6FBF, ecx: 0x00000000, edx: 0xBC002E00 },
+        _ => zero,
+    };
+    assert_eq!(ids(&cpuid), ["RTM-ALWAYS-ABORT", "TSX-FORCE-ABORT"]);
+}
@@ -0,0 +1,52 @@
+use std::{fmt, io};
+
+#[derive(Debug)]
+pub enum Error {
+    /// Failed to pin the current thread to the CPU (`sched_setaffinity`, `SetThreadAffinityMask`)
+    PinThread { cpu: usize, source: io::Error },
+    /// Failed to get the affinity mask of the current thread (`sched_getaffinity`)
+    CpuSetList(io::Error),
+    /// Leaf is greater than the largest standard/extended function, or not in the snapshot
+    UnsupportedLeaf { leaf: u32, sub_leaf: u32 },
+    /// Invalid value or format, e.g. command line argument, dump file
+    Parse(String),
+    /// Failed to read `/dev/cpu/<N>/cpuid`
+    CpuidDev { cpu: usize, source: io::Error },
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::PinThread { cpu, source } =>
+                write!(f, "failed to pin the thread to CPU {cpu}: {source}"),
+            Self::CpuSetList(source) =>
+                write!(f, "failed to get the affinity mask: {source}"),
+            Self::UnsupportedLeaf { leaf, sub_leaf } =>
+                write!(f, "unsupported leaf: {leaf:#010X}, sub-leaf: {sub_leaf:#X}"),
+            Self::Parse(msg) => write!(f, "{msg}"),
+            Self::CpuidDev { cpu, source } =>
+                write!(f, "failed to read \"/dev/cpu/{cpu}/cpuid\": {source}"),
+            Self::Io(source) => write!(f, "{source}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::PinThread { source, .. } |
+            Self::CpuSetList(source) |
+            Self::CpuidDev { source, .. } |
+            Self::Io(source) => Some(source),
+            Self::UnsupportedLeaf { .. } |
+            Self::Parse(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
@@ -0,0 +1,204 @@
+use crate::{CpuidReg, CpuidResult};
+use core::iter::FromIterator;
+
+/* A feature flag, CPUID[leaf, sub_leaf].reg[bit] */
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CpuFeature {
+    pub leaf: u32,
+    pub sub_leaf: u32,
+    pub reg: CpuidReg,
+    pub bit: u32,
+}
+
+impl CpuFeature {
+    pub const fn new(leaf: u32, sub_leaf: u32, reg: CpuidReg, bit: u32) -> Self {
+        Self { leaf, sub_leaf, reg, bit }
+    }
+}
+
+/* The registers of the feature flags, (Leaf, SubLeaf, Register) */
+const FEATURE_WORDS: [(u32, u32, CpuidReg); 21] = {
+    use CpuidReg::*;
+
+    [
+        (0x1, 0x0, EDX),
+        (0x1, 0x0, ECX),
+        (0x6, 0x0, EAX),
+        (0x7, 0x0, EBX),
+        (0x7, 0x0, ECX),
+        (0x7, 0x0, EDX),
+        (0x7, 0x1, EAX),
+        (0x7, 0x1, EDX),
+        (0x7, 0x2, EDX),
+        (0xD, 0x1, EAX),
+        (0x8000_0001, 0x0, ECX),
+        (0x8000_0001, 0x0, EDX),
+        (0x8000_0007, 0x0, EDX),
+        (0x8000_0008, 0x0, EBX),
+        (0x8000_000A, 0x0, EDX),
+        (0x8000_001A, 0x0, EAX),
+        (0x8000_001B, 0x0, EAX),
+        (0x8000_001F, 0x0, EAX),
+        (0x8000_0021, 0x0, EAX),
+        (0x8000_0021, 0x0, ECX),
+        (0xC000_0001, 0x0, EDX),
+    ]
+};
+
+/* The feature flags of a processor, bitwise operations are for comparing processors */
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct FeatureSet {
+    words: [u32; FEATURE_WORDS.len()],
+}
+
+impl FeatureSet {
+    pub const WORDS: &'static [(u32, u32, CpuidReg)] = &FEATURE_WORDS;
+
+    /* The leaves and sub-leaves above the maximum are zero */
+    pub fn from_cpuid_with<F: Fn(u32, u32) -> CpuidResult>(cpuid: &F) -> Self {
+        let max_leaf = cpuid(0x0, 0x0).eax;
+        let max_ext_leaf = cpuid(0x8000_0000, 0x0).eax;
+        /* Centaur/Zhaoxin leaves, Intel CPUs return the data of the highest basic leaf */
+        let max_centaur_leaf = cpuid(0xC000_0000, 0x0).eax;
+        let max_sub_leaf_07h = if 0x7 <= max_leaf { cpuid(0x7, 0x0).eax } else { 0x0 };
+        let mut words = [0u32; FEATURE_WORDS.len()];
+
+        for (word, (leaf, sub_leaf, reg)) in words.iter_mut().zip(FEATURE_WORDS.iter()) {
+            let supported = if (leaf & 0xF000_0000) == 0xC000_0000 {
+                (max_centaur_leaf & 0xF000_0000) == 0xC000_0000 && *leaf <= max_centaur_leaf
+            } else if (leaf & 0x8000_0000) != 0 {
+                (max_ext_leaf & 0x8000_0000) != 0 && *leaf <= max_ext_leaf
+            } else {
+                *leaf <= max_leaf && (*leaf != 0x7 || *sub_leaf <= max_sub_leaf_07h)
+            };
+
+            if supported {
+                *word = reg.get(&cpuid(*leaf, *sub_leaf));
+            }
+        }
+
+        Self { words }
+    }
+
+    pub fn get() -> Self {
+        Self::from_cpuid_with(&|leaf, sub_leaf| cpuid!(leaf, sub_leaf))
+    }
+
+    pub(crate) fn from_words(words: [u32; FEATURE_WORDS.len()]) -> Self {
+        Self { words }
+    }
+
+    pub(crate) fn words(&self) -> &[u32; FEATURE_WORDS.len()] {
+        &self.words
+    }
+
+    fn index(leaf: u32, sub_leaf: u32, reg: CpuidReg) -> Option<usize> {
+        FEATURE_WORDS.iter().position(|w| *w == (leaf, sub_leaf, reg))
+    }
+
+    /* `None` if the register is not in `FeatureSet::WORDS` */
+    pub fn word(&self, leaf: u32, sub_leaf: u32, reg: CpuidReg) -> Option<u32> {
+        Self::index(leaf, sub_leaf, reg).map(|i| self.words[i])
+    }
+
+    pub fn has(&self, ftr: CpuFeature) -> bool {
+        self.word(ftr.leaf, ftr.sub_leaf, ftr.reg).is_some_and(|word| (word >> ftr.bit) & 1 == 1)
+    }
+
+    /* The features not in `FeatureSet::WORDS` are ignored */
+    pub fn insert(&mut self, ftr: CpuFeature) {
+        if let Some(i) = Self::index(ftr.leaf, ftr.sub_leaf, ftr.reg) {
+            self.words[i] |= 1 << ftr.bit;
+        }
+    }
+
+    pub fn remove(&mut self, ftr: CpuFeature) {
+        if let Some(i) = Self::index(ftr.leaf, ftr.sub_leaf, ftr.reg) {
+            self.words[i] &= !(1 << ftr.bit);
+        }
+    }
+
+    fn zip_with(&self, other: &Self, f: fn(u32, u32) -> u32) -> Self {
+        let mut words = self.words;
+
+        for (word, other) in words.iter_mut().zip(other.words.iter()) {
+            *word = f(*word, *other);
+        }
+
+        Self { words }
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a & b)
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a | b)
+    }
+
+    /* The features in `self` but not in `other` */
+    pub fn difference(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a & !b)
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.difference(other).is_empty()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = CpuFeature> + '_ {
+        FEATURE_WORDS.iter().zip(self.words.iter()).flat_map(|((leaf, sub_leaf, reg), word)| {
+            (0..32).filter(move |bit| (word >> bit) & 1 == 1).map(move |bit| {
+                CpuFeature::new(*leaf, *sub_leaf, *reg, bit)
+            })
+        })
+    }
+}
+
+impl FromIterator<CpuFeature> for FeatureSet {
+    fn from_iter<I: IntoIterator<Item = CpuFeature>>(iter: I) -> Self {
+        let mut set = Self::default();
+
+        for ftr in iter {
+            set.insert(ftr);
+        }
+
+        set
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_feature_set() {
+    let zero = CpuidResult { eax: 0x0, ebx: 0x0, ecx: 0x0, edx: 0x0 };
+
+    /* Ryzen 5 5600G */
+    let snapshot = crate::CpuidSnapshot::ryzen_5_5600g();
+    let zen3 = FeatureSet::from_cpuid_with(&|leaf, sub_leaf| snapshot.cpuid(leaf, sub_leaf));
+    /* Ryzen 5 2600 */
+    let zen_plus = FeatureSet::from_cpuid_with(&|leaf, sub_leaf| match (leaf, sub_leaf) {
+        (0x0, _) => CpuidResult { eax: 0x0000000D, ebx: 0x68747541, ecx: 0x444D4163, edx: 0x69746E65 },
+        (0x1, _) => CpuidResult { eax: 0x00800F82, ebx: 0x000C0800, ecx: 0x7ED8320B, edx: 0x178BFBFF },
+        (0x7, 0x0) => CpuidResult { eax: 0x00000000, ebx: 0x209C01A9, ecx: 0x00000000, edx: 0x00000000 },
+        (0x8000_0000, _) => CpuidResult { eax: 0x8000001F, ebx: 0x68747541, ecx: 0x444D4163, edx: 0x69746E65 },
+        (0x8000_0001, _) => CpuidResult { eax: 0x00800F82, ebx: 0x20000000, ecx: 0x35C233FF, edx: 0x2FD3FBFF },
+        _ => zero,
+    });
+
+    const AVX2: CpuFeature = CpuFeature::new(0x7, 0x0, CpuidReg::EBX, 5);
+    const VAES: CpuFeature = CpuFeature::new(0x7, 0x0, CpuidReg::ECX, 9);
+
+    let common = zen3.intersection(&zen_plus);
+
+    assert!(common.has(AVX2));
+    assert!(!common.has(VAES));
+    assert!(common.is_subset(&zen3));
+    assert_eq!(common, zen_plus);
+    assert!(zen3.difference(&zen_plus).iter().any(|ftr| ftr == VAES));
+    assert_eq!(common.iter().collect::<FeatureSet>(), common);
+}
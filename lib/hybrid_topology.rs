@@ -1,10 +1,11 @@
 #[cfg(feature = "std")]
-use crate::{cpuid, CacheType, CacheProp, HybridCoreType, HybridInfo};
+use crate::{cpuid, CacheType, CacheProp, Error, HybridCoreType, HybridInfo};
 use crate::util::*;
 
 use std::sync::Arc;
 use std::thread;
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug)]
 pub struct CachePropCount {
     pub prop: CacheProp,
@@ -12,6 +13,7 @@ pub struct CachePropCount {
     pub shared_between_topology: bool, // shared_all_threads?
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug)]
 pub struct TopoCacheInfo {
     pub l1d: Option<CachePropCount>,
@@ -26,11 +28,14 @@ impl TopoCacheInfo {
         prop.share_thread == max_apic_id
     }
 
-    pub fn get_topology_cache_info(type_only_list: &[usize]) -> Option<Self> {
-        let cache_leaf = Arc::new(CacheProp::get_cache_prop_leaf()?);
+    pub fn get_topology_cache_info(type_only_list: &[usize]) -> Result<Option<Self>, Error> {
+        let cache_leaf = match CacheProp::get_cache_prop_leaf() {
+            Some(leaf) => Arc::new(leaf),
+            None => return Ok(None),
+        };
 
         if *cache_leaf == 0x8000_001D {
-            return Self::from_amd_80_1dh(*cache_leaf);
+            return Ok(Self::from_amd_80_1dh(*cache_leaf));
         }
 
         let len = type_only_list.len();
@@ -45,8 +50,8 @@ impl TopoCacheInfo {
         ];
 
         /* fill cache prop */
-        thread::scope(|s| s.spawn(|| {
-            self::pin_thread(type_only_list[0]).unwrap();
+        thread::scope(|s| s.spawn(|| -> Result<(), Error> {
+            self::pin_thread(type_only_list[0])?;
             let eax = cpuid!(0x1, 0x0).eax;
             let apicid = initial_apic_id!(eax);
             let max_apic_id = max_apic_id!(eax);
@@ -105,7 +110,9 @@ impl TopoCacheInfo {
                     _ => {},
                 }
             }
-        }).join().unwrap());
+
+            Ok(())
+        }).join().unwrap())?;
 
         let update_cache_ids = |ids: &mut Vec<u32>, cache_id: u32| {
             if !ids.contains(&cache_id) {
@@ -119,8 +126,8 @@ impl TopoCacheInfo {
             let cpu = *cpu;
             let cache_leaf = Arc::clone(&cache_leaf);
 
-            handles.push(thread::spawn(move || -> Vec<Option<(CacheProp, u32)>> {
-                self::pin_thread(cpu).unwrap();
+            handles.push(thread::spawn(move || -> Result<Vec<Option<(CacheProp, u32)>>, Error> {
+                self::pin_thread(cpu)?;
                 let apicid = initial_apic_id!();
                 let mut props: Vec<Option<(CacheProp, u32)>> = Vec::with_capacity(6);
 
@@ -139,12 +146,12 @@ impl TopoCacheInfo {
                     props.push(Some((prop, cache_id)));
                 }
 
-                props
+                Ok(props)
             }));
         }
 
         for h in handles {
-            for (prop, cache_id) in h.join().unwrap().into_iter().flatten() {
+            for (prop, cache_id) in h.join().unwrap()?.into_iter().flatten() {
                 match prop {
                     CacheProp { cache_type: CacheType::Data, level: 1, .. } => {
                         update_cache_ids(&mut l1d_ids, cache_id);
@@ -175,13 +182,13 @@ impl TopoCacheInfo {
             }
         }
 
-        Some(Self {
+        Ok(Some(Self {
             l1d,
             l1i,
             l2,
             l3,
             l4,
-        })
+        }))
     }
 
     fn from_amd_80_1dh(cache_leaf: u32) -> Option<Self> {
@@ -253,14 +260,16 @@ impl TopoCacheInfo {
         https://www.intel.com/content/dam/develop/external/us/en/documents/intel-64-architecture-processor-topology-enumeration.pdf)
     */
     /* Linux Kernel: arch/x86/kernel/cpu/cacheinfo.c */
-    fn get_cache_id(apicid: u32, num_sharing_thread: u32) -> u32 {
-        /* find last set bit */
-        let index_msb = u32::BITS - num_sharing_thread.leading_zeros();
+    /// The APIC ID of the first thread sharing the cache, unique to each instance of the cache.
+    pub fn get_cache_id(apicid: u32, num_sharing_thread: u32) -> u32 {
+        /* get_count_order, ceil(log2(num_sharing_thread)) */
+        let index_msb = u32::BITS - num_sharing_thread.saturating_sub(1).leading_zeros();
 
         apicid & !((1 << index_msb) - 1)
     }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TopoPartInfo {
    pub core_type: HybridCoreType,
    pub num_logical_proc: u32,
@@ -275,60 +284,69 @@ impl TopoPartInfo {
         cpuid == 0b1
     }
 
-    fn get_core_type_only_list(core_type: HybridCoreType) -> Vec<usize> {
+    fn get_core_type_only_list(core_type: HybridCoreType) -> Result<Vec<usize>, Error> {
         let core_type = Arc::new(core_type);
-        let cpu_list = cpu_set_list().unwrap();
+        let cpu_list = cpu_set_list()?;
         let mut type_only_list: Vec<usize> = Vec::with_capacity(cpu_list.len());
         let mut handles: Vec<thread::JoinHandle<_>> = Vec::with_capacity(cpu_list.len());
 
         for cpu in cpu_list {
             let core_type = Arc::clone(&core_type);
 
-            handles.push(thread::spawn(move || -> Option<usize> {
-                self::pin_thread(cpu).unwrap();
+            handles.push(thread::spawn(move || -> Result<Option<usize>, Error> {
+                self::pin_thread(cpu)?;
                 let leaf_1ah = cpuid!(0x1A, 0x0);
 
                 if let Some(cur_core_type) = HybridInfo::get_core_type(&leaf_1ah) {
                     if cur_core_type == *core_type {
-                        return Some(cpu);
+                        return Ok(Some(cpu));
                     }
                 };
 
-                None
+                Ok(None)
             }));
         }
 
         for h in handles {
-            if let Some(cpu) = h.join().unwrap() {
+            if let Some(cpu) = h.join().unwrap()? {
                 type_only_list.push(cpu)
             }
         }
 
-        type_only_list
+        Ok(type_only_list)
     }
 
-    pub fn get(core_type: HybridCoreType) -> Self {
-        let cpu_list = Self::get_core_type_only_list(core_type.clone());
+    pub fn get(core_type: HybridCoreType) -> Result<Self, Error> {
+        let cpu_list = Self::get_core_type_only_list(core_type.clone())?;
         /* core type only */
         let num_logical_proc = cpu_list.len() as u32;
 
         /* To confine the effects of pin_thread */
-        let (num_physical_proc, cache) = thread::scope(|s| s.spawn(move || {
-            self::pin_thread(cpu_list[0]).unwrap();
+        let (num_physical_proc, cache) = thread::scope(|s| s.spawn(move || -> Result<_, Error> {
+            self::pin_thread(cpu_list[0])?;
 
             let threads_per_core = get_threads_per_core().unwrap_or(1);
 
-            (
+            Ok((
                 num_logical_proc / threads_per_core,
-                TopoCacheInfo::get_topology_cache_info(&cpu_list),
-            )
-        }).join().unwrap());
+                TopoCacheInfo::get_topology_cache_info(&cpu_list)?,
+            ))
+        }).join().unwrap())?;
 
-        Self {
+        Ok(Self {
             core_type,
             num_logical_proc,
             num_physical_proc,
             cache,
-        }
+        })
     }
 }
+
+#[test]
+fn test_get_cache_id() {
+    /* L1/L2 shared by 2 threads, L3 shared by 12 threads (Ryzen 5 5600G) */
+    assert_eq!(TopoCacheInfo::get_cache_id(0x5, 2), 0x4);
+    assert_eq!(TopoCacheInfo::get_cache_id(0x5, 1), 0x5);
+    assert_eq!(TopoCacheInfo::get_cache_id(0xB, 12), 0x0);
+    assert_eq!(TopoCacheInfo::get_cache_id(0x11, 12), 0x10);
+}
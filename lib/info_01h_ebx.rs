@@ -1,5 +1,6 @@
 use crate::{cpuid, CpuidResult};
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Info01h {
     pub local_apic_id: u8,
     pub max_apic_id: u8,
@@ -1,5 +1,6 @@
 use crate::CpuidResult;
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, PartialEq, Eq)]
 #[repr(u8)]
 pub enum TopoLevelType {
@@ -32,6 +33,7 @@ impl std::fmt::Display for TopoLevelType {
     }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct IntelExtTopo {
     pub next_level: u32,
     pub x2apic_id: u32,
@@ -0,0 +1,152 @@
+use crate::{CpuidResult, SubLeafRule, TlbAssoc, TlbInfo};
+#[cfg(feature = "std")]
+use std::fmt;
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/* Leaf: 0x18, Deterministic Address Translation Parameters, Intel CPU only */
+/* ref: Intel SDM Vol. 2A, CPUID—CPU Identification, Table 3-8 */
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntelTlbType {
+    Data,
+    Instruction,
+    Unified,
+    LoadOnly,
+    StoreOnly,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone)]
+pub struct IntelTlb {
+    pub level: u8,
+    pub type_: IntelTlbType,
+    pub page_4k: bool,
+    pub page_2m: bool,
+    pub page_4m: bool,
+    pub page_1g: bool,
+    /* entries (ways * sets) and associativity, shared by the supported page sizes */
+    pub info: TlbInfo,
+    pub share_thread: u16,
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for IntelTlb {
+    /* "L1dTLB", "L2TLB", "L1dTLB (load)" */
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (ty, suffix) = match self.type_ {
+            IntelTlbType::Data => ("d", ""),
+            IntelTlbType::Instruction => ("i", ""),
+            IntelTlbType::Unified => ("", ""),
+            IntelTlbType::LoadOnly => ("d", " (load)"),
+            IntelTlbType::StoreOnly => ("d", " (store)"),
+        };
+
+        write!(f, "L{}{}TLB{}", self.level, ty, suffix)
+    }
+}
+
+impl IntelTlb {
+    /* `None` for the invalid sub-leaf, Translation cache type field (EDX[4:0]) is 0 */
+    pub fn from_cpuid(cpuid: &CpuidResult) -> Option<Self> {
+        let type_ = match cpuid.edx & 0x1F {
+            0x1 => IntelTlbType::Data,
+            0x2 => IntelTlbType::Instruction,
+            0x3 => IntelTlbType::Unified,
+            0x4 => IntelTlbType::LoadOnly,
+            0x5 => IntelTlbType::StoreOnly,
+            _ => return None,
+        };
+        let ways = cpuid.ebx >> 16;
+        let assoc = if (cpuid.edx >> 8) & 0b1 == 1 {
+            TlbAssoc::Full
+        } else {
+            TlbAssoc::Way(ways.min(0xFF) as u8)
+        };
+
+        Some(Self {
+            level: ((cpuid.edx >> 5) & 0b111) as u8,
+            type_,
+            page_4k: cpuid.ebx & 0b0001 != 0,
+            page_2m: cpuid.ebx & 0b0010 != 0,
+            page_4m: cpuid.ebx & 0b0100 != 0,
+            page_1g: cpuid.ebx & 0b1000 != 0,
+            info: TlbInfo { size: ways.saturating_mul(cpuid.ecx).min(0xFFFF) as u16, assoc },
+            share_thread: (((cpuid.edx >> 14) & 0xFFF) + 1) as u16,
+        })
+    }
+
+    pub fn get_all_with<F: Fn(u32, u32) -> CpuidResult>(cpuid: &F) -> Vec<Self> {
+        let mut tlbs = Vec::new();
+
+        if cpuid(0x0, 0x0).eax < 0x18 {
+            return tlbs;
+        }
+
+        SubLeafRule::for_leaf(0x18).sub_leaves_with(0x18, cpuid, &mut |sub_leaf| {
+            tlbs.extend(Self::from_cpuid(&cpuid(0x18, sub_leaf)));
+        });
+
+        tlbs
+    }
+
+    pub fn get_all() -> Vec<Self> {
+        Self::get_all_with(&|leaf, sub_leaf| cpuid!(leaf, sub_leaf))
+    }
+}
+
+#[test]
+fn test_intel_tlb_18h() {
+    /* constructed leaf 0x18 layout: L1i, L1d (load), L1d (store, fully associative), L2 */
+    let cpuid = |leaf: u32, sub_leaf: u32| {
+        let [eax, ebx, ecx, edx] = match (leaf, sub_leaf) {
+            (0x0, _) => [0x00000020, 0x756E6547, 0x6C65746E, 0x49656E69],
+            (0x18, 0x0) => [0x00000004, 0x00000000, 0x00000000, 0x00000000],
+            (0x18, 0x1) => [0x00000000, 0x00080001, 0x00000010, 0x00004022],
+            (0x18, 0x2) => [0x00000000, 0x00040001, 0x00000010, 0x00004024],
+            (0x18, 0x3) => [0x00000000, 0x0010000F, 0x00000001, 0x00004125],
+            (0x18, 0x4) => [0x00000000, 0x00080003, 0x00000100, 0x00004043],
+            _ => [0x0; 4],
+        };
+
+        CpuidResult { eax, ebx, ecx, edx }
+    };
+    let tlbs = IntelTlb::get_all_with(&cpuid);
+
+    assert_eq!(tlbs.len(), 4);
+
+    assert_eq!(tlbs[0].type_, IntelTlbType::Instruction);
+    assert_eq!(tlbs[0].info.size, 128);
+    assert!(matches!(tlbs[0].info.assoc, TlbAssoc::Way(8)));
+    assert!(tlbs[0].page_4k && !tlbs[0].page_2m);
+
+    assert_eq!(tlbs[2].type_, IntelTlbType::StoreOnly);
+    assert_eq!(tlbs[2].info.size, 16);
+    assert!(matches!(tlbs[2].info.assoc, TlbAssoc::Full));
+    assert!(tlbs[2].page_1g);
+
+    assert_eq!(tlbs[3].level, 2);
+    assert_eq!(tlbs[3].info.size, 2048);
+    assert_eq!(tlbs[3].share_thread, 2);
+
+    #[cfg(feature = "std")]
+    {
+        assert_eq!(tlbs[0].to_string(), "L1iTLB");
+        assert_eq!(tlbs[1].to_string(), "L1dTLB (load)");
+        assert_eq!(tlbs[3].to_string(), "L2TLB");
+    }
+
+    /* ways * sets overflowing u32 */
+    let tlb = IntelTlb::from_cpuid(&CpuidResult { eax: 0x0, ebx: 0xFFFF0001, ecx: 0xFFFFFFFF, edx: 0x00000021 }).unwrap();
+    assert_eq!(tlb.info.size, 0xFFFF);
+
+    /* max standard leaf below 0x18 */
+    assert!(IntelTlb::get_all_with(&|leaf, sub_leaf| match leaf {
+        0x0 => CpuidResult { eax: 0x16, ..cpuid(leaf, sub_leaf) },
+        _ => cpuid(leaf, sub_leaf),
+    }).is_empty());
+}
@@ -16,8 +16,19 @@ macro_rules! cpuid {
 
 #[macro_use]
 pub mod util;
+
+mod cpuid_regs;
+pub use cpuid_regs::*;
+
+mod snapshot;
+pub use snapshot::*;
 // pub use util::*;
 
+#[cfg(feature = "std")]
+mod error;
+#[cfg(feature = "std")]
+pub use error::*;
+
 mod codename;
 pub use codename::*;
 
@@ -27,6 +38,15 @@ pub use vendor::*;
 mod micro_arch_level;
 pub use micro_arch_level::*;
 
+mod feature_set;
+pub use feature_set::*;
+
+mod detect;
+pub use detect::*;
+
+mod errata;
+pub use errata::*;
+
 #[cfg(feature = "std")]
 mod proc_name;
 #[cfg(feature = "std")]
@@ -44,6 +64,9 @@ pub use intel_ext_topo_0bh_1fh::*;
 mod amd_tlb_info;
 pub use amd_tlb_info::*;
 
+mod intel_tlb_info_18h;
+pub use intel_tlb_info_18h::*;
+
 mod hybrid_info_00_1ah;
 pub use hybrid_info_00_1ah::*;
 
@@ -66,3 +89,8 @@ pub use amd_pkg_type_80_01h::*;
 
 mod amd_proc_topo_80_1eh;
 pub use amd_proc_topo_80_1eh::*;
+
+#[cfg(all(feature = "std", target_os = "linux"))]
+mod cpuid_dev;
+#[cfg(all(feature = "std", target_os = "linux"))]
+pub use cpuid_dev::*;
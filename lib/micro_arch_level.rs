@@ -1,8 +1,9 @@
-use crate::{cpuid, CpuidResult};
+use crate::{cpuid, CpuidReg, CpuidResult, CpuFeature, FeatureSet};
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(non_camel_case_types)]
 #[repr(u8)]
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum MicroArchLevel {
     X86_64_V0,
     X86_64_V1, // baseline
@@ -128,6 +129,44 @@ impl MicroArchLevel {
 
         Self::from_cpuid_array(cpuid_array)
     }
+
+    /// The feature flags required by the level
+    pub fn feature_set(&self) -> FeatureSet {
+        let mut set = FeatureSet::default();
+        let mut insert = |leaf: u32, reg: CpuidReg, mask: u32| {
+            for bit in (0..32).filter(|bit| (mask >> bit) & 1 == 1) {
+                set.insert(CpuFeature::new(leaf, 0x0, reg, bit));
+            }
+        };
+
+        if Self::X86_64_V1 <= *self {
+            insert(0x1, CpuidReg::EDX, Self::MASK_BASE_LINE);
+        }
+        if Self::X86_64_V2 <= *self {
+            insert(0x1, CpuidReg::ECX, Self::MASK_X86_64_V2[0]);
+            insert(0x8000_0001, CpuidReg::ECX, Self::MASK_X86_64_V2[1]);
+        }
+        if Self::X86_64_V3 <= *self {
+            insert(0x1, CpuidReg::ECX, Self::MASK_X86_64_V3[0]);
+            insert(0x7, CpuidReg::EBX, Self::MASK_X86_64_V3[1]);
+            insert(0x8000_0001, CpuidReg::ECX, Self::MASK_X86_64_V3[2]);
+        }
+        if Self::X86_64_V4 <= *self {
+            insert(0x7, CpuidReg::EBX, Self::MASK_X86_64_V4);
+        }
+
+        set
+    }
+
+    /// Same as `check`, but CPUID results are provided by `cpuid(leaf, sub_leaf)`,
+    /// e.g. read from a saved dump.
+    pub fn check_with<F: Fn(u32, u32) -> CpuidResult>(cpuid: &F) -> Self {
+        Self::from_cpuid_array([
+            cpuid(0x1, 0x0),
+            cpuid(0x7, 0x0),
+            cpuid(0x8000_0001, 0x0),
+        ])
+    }
 }
 
 #[test]
@@ -145,4 +184,12 @@ fn test_micro_arch_level() {
     let level = MicroArchLevel::from_cpuid_array(cpuid_array);
 
     assert_eq!(MicroArchLevel::X86_64_V3, level);
+
+    let v3 = MicroArchLevel::X86_64_V3.feature_set();
+
+    /* AVX2, LAHF/SAHF */
+    assert!(v3.has(CpuFeature::new(0x7, 0x0, CpuidReg::EBX, 5)));
+    assert!(v3.has(CpuFeature::new(0x8000_0001, 0x0, CpuidReg::ECX, 0)));
+    assert!(MicroArchLevel::X86_64_V2.feature_set().is_subset(&v3));
+    assert!(!MicroArchLevel::X86_64_V4.feature_set().is_subset(&v3));
 }
@@ -40,7 +40,7 @@ impl ProcName {
         /* 4 (0x8000_0002 .. 0x8000_0004) * u32 ([u8; 4]) * 4 (E{A,B,C,D}X) */
         let name: Vec<u8> = array.iter().flat_map(Self::dec_cpuid).collect();
 
-        String::from_utf8(name).unwrap()
+        String::from_utf8_lossy(&name).to_string()
     }
     
     pub fn get_name() -> String {
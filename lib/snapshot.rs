@@ -0,0 +1,496 @@
+use crate::{CpuidReg, CpuidResult};
+#[cfg(feature = "std")]
+use crate::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RawCpuid {
+    pub leaf: u32,
+    pub sub_leaf: u32,
+    #[cfg_attr(feature = "serde", serde(with = "crate::cpuid_result_serde"))]
+    pub result: CpuidResult,
+}
+
+impl RawCpuid {
+    pub fn exe(leaf: u32, sub_leaf: u32) -> Self {
+        Self {
+            leaf,
+            sub_leaf,
+            result: cpuid!(leaf, sub_leaf),
+        }
+    }
+
+    pub fn check_result_zero(&self) -> bool {
+        self.result == CpuidResult { eax: 0x0, ebx: 0x0, ecx: 0x0, edx: 0x0 }
+    }
+}
+
+fn parse_hex(s: &str) -> Option<u32> {
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    let s = s.strip_suffix('h').unwrap_or(s);
+
+    u32::from_str_radix(s, 16).ok()
+}
+
+impl RawCpuid {
+    /// Parse a line of the text dump, `None` for the other lines.
+    ///
+    /// Supported formats:
+    /// * Raw, Parse: `"  0x00000001 0x0:  0x00A50F00 0x000C0800 0x7EF8320B 0x178BFBFF  [..]"`
+    /// * CompatCpuid: `"   0x00000001 0x00: eax=0x00a50f00 ebx=0x000c0800 ecx=0x7ef8320b edx=0x178bfbff"`
+    /// * Old cpuid_dump: `" 00000001h_x0: eax=00800F82h ebx=000C0800h ecx=7ED8320Bh edx=178BFBFFh [..]"`
+    pub fn parse_line(ln: &str) -> Option<Self> {
+        let mut tokens = ln.split_whitespace();
+        let first = tokens.next()?;
+
+        let (leaf, sub_leaf) = if let Some((leaf, sub_leaf)) = first.split_once("h_x") {
+            /* old format: "0000001Fh_x0:" */
+            (parse_hex(leaf)?, parse_hex(sub_leaf.strip_suffix(':')?)?)
+        } else {
+            (parse_hex(first)?, parse_hex(tokens.next()?.strip_suffix(':')?)?)
+        };
+
+        let mut regs = [0u32; 4];
+
+        for (reg, name) in regs.iter_mut().zip(["eax=", "ebx=", "ecx=", "edx="]) {
+            let token = tokens.next()?;
+            *reg = parse_hex(token.strip_prefix(name).unwrap_or(token))?;
+        }
+
+        let [eax, ebx, ecx, edx] = regs;
+
+        Some(Self { leaf, sub_leaf, result: CpuidResult { eax, ebx, ecx, edx } })
+    }
+}
+
+/// CPUID results of a thread in a text dump.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct LoadedThread {
+    /// Like `"[Pkg: 000, Core: 000, SMT: 000, x2APIC: 000, Thread: 000]"`, `"CPU 0:"`
+    pub head: String,
+    pub pool: Vec<RawCpuid>,
+}
+
+#[cfg(feature = "std")]
+impl LoadedThread {
+    /// The logical processor number of the head, `"Thread: <N>"` or `"CPU <N>:"`
+    pub fn cpu(&self) -> Option<usize> {
+        let num = match self.head.split_once("Thread: ") {
+            Some((_, s)) => s,
+            None => self.head.strip_prefix("CPU ")?,
+        };
+
+        num.split(|c: char| !c.is_ascii_digit()).next()?.parse().ok()
+    }
+
+    /// Split the text dump into the threads, see `RawCpuid::parse_line` for the line formats.
+    /// Binary format is not supported.
+    pub fn parse_dump(s: &str) -> Vec<Self> {
+        let mut threads: Vec<Self> = Vec::new();
+        let mut cur = Self { head: "".to_string(), pool: Vec::with_capacity(64) };
+
+        for ln in s.lines() {
+            if let Some(raw) = RawCpuid::parse_line(ln) {
+                cur.pool.push(raw);
+                continue;
+            }
+
+            let trim = ln.trim();
+            let is_head = trim.starts_with("[Pkg:") || trim.starts_with("[Thread:")
+                || (trim.starts_with("CPU ") && trim.ends_with(':'));
+
+            if !is_head {
+                continue;
+            }
+
+            if !cur.pool.is_empty() || !cur.head.is_empty() {
+                threads.push(cur);
+            }
+
+            cur = Self { head: trim.to_string(), pool: Vec::with_capacity(64) };
+        }
+
+        if !cur.pool.is_empty() || !cur.head.is_empty() {
+            threads.push(cur);
+        }
+
+        threads
+    }
+}
+
+/// How to enumerate the valid sub-leaves of a leaf.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SubLeafRule {
+    /// Only sub-leaf 0
+    Single,
+    /// Sub-leaves are valid until the field is zero, e.g. cache type, level type.
+    /// The first `first` sub-leaves are always valid.
+    ZeroField { reg: CpuidReg, mask: u32, first: u32 },
+    /// EAX of sub-leaf 0 is the largest sub-leaf
+    MaxInEax,
+    /// The register of sub-leaf 0 is a bitmap of valid sub-leaves, sub-leaf 0 is always valid
+    Bitmap { reg: CpuidReg },
+    /// Leaf 0xD: sub-leaf 0, 1, and the XSAVE state components supported by
+    /// XCR0 (sub-leaf 0 EDX:EAX) and IA32_XSS (sub-leaf 1 EDX:ECX)
+    XStateBitmap,
+}
+
+/// Rules for the leaves with multiple sub-leaves, the other leaves are `SubLeafRule::Single`
+pub const SUB_LEAF_RULES: &[(u32, SubLeafRule)] = {
+    use CpuidReg::*;
+    use SubLeafRule::*;
+
+    &[
+        /* Deterministic Cache Parameters, Intel, Cache Type: EAX[4:0] */
+        (0x4, ZeroField { reg: EAX, mask: 0x1F, first: 1 }),
+        (0x7, MaxInEax),
+        /* Extended Topology Enumeration, Level Type: ECX[15:8] */
+        (0xB, ZeroField { reg: ECX, mask: 0xFF00, first: 1 }),
+        (0xD, XStateBitmap),
+        /* Intel RDT Monitoring, Resource Type: EDX */
+        (0xF, Bitmap { reg: EDX }),
+        /* Intel RDT Allocation, Resource ID: EBX */
+        (0x10, Bitmap { reg: EBX }),
+        /* Intel SGX, EPC Section: EAX[3:0] from sub-leaf 2 */
+        (0x12, ZeroField { reg: EAX, mask: 0xF, first: 2 }),
+        /* Intel Processor Trace */
+        (0x14, MaxInEax),
+        /* SoC Vendor Attribute */
+        (0x17, MaxInEax),
+        /* Deterministic Address Translation Parameters */
+        (0x18, MaxInEax),
+        /* PCONFIG, Sub-leaf Type: EAX[11:0] */
+        (0x1B, ZeroField { reg: EAX, mask: 0xFFF, first: 1 }),
+        /* Tile Information */
+        (0x1D, MaxInEax),
+        /* V2 Extended Topology Enumeration, Level Type: ECX[15:8] */
+        (0x1F, ZeroField { reg: ECX, mask: 0xFF00, first: 1 }),
+        /* Processor History Reset */
+        (0x20, MaxInEax),
+        /* Architectural Performance Monitoring Extended */
+        (0x23, Bitmap { reg: EAX }),
+        /* AVX10 */
+        (0x24, MaxInEax),
+        /* Cache Properties, AMD, same format as Intel Leaf 0x4 */
+        (0x8000_001D, ZeroField { reg: EAX, mask: 0x1F, first: 1 }),
+        /* AMD Platform QoS Enforcement */
+        (0x8000_0020, Bitmap { reg: EBX }),
+        /* AMD Extended CPU Topology, Level Type: ECX[15:8] */
+        (0x8000_0026, ZeroField { reg: ECX, mask: 0xFF00, first: 1 }),
+    ]
+};
+
+impl SubLeafRule {
+    /* some hypervisors return the same values for any sub-leaf */
+    const MAX_SUB_LEAF: u32 = 0x3F;
+
+    pub fn for_leaf(leaf: u32) -> Self {
+        SUB_LEAF_RULES.iter()
+            .find(|(l, _)| *l == leaf)
+            .map_or(Self::Single, |(_, rule)| *rule)
+    }
+
+    fn bitmap(bitmap: u64, first: u32) -> impl Iterator<Item = u32> {
+        (0..first).chain((first..64).filter(move |i| (bitmap >> i) & 1 == 1))
+    }
+
+    /// Call `f` with each valid sub-leaf
+    pub fn sub_leaves_with<F: Fn(u32, u32) -> CpuidResult>(&self, leaf: u32, cpuid: &F, f: &mut dyn FnMut(u32)) {
+        match *self {
+            Self::Single => f(0x0),
+            Self::ZeroField { reg, mask, first } => {
+                for sub_leaf in 0x0..=Self::MAX_SUB_LEAF {
+                    if first <= sub_leaf && (reg.get(&cpuid(leaf, sub_leaf)) & mask) == 0 {
+                        break;
+                    }
+
+                    f(sub_leaf)
+                }
+            },
+            Self::MaxInEax => {
+                let max = cpuid(leaf, 0x0).eax.min(Self::MAX_SUB_LEAF);
+
+                (0x0..=max).for_each(f)
+            },
+            Self::Bitmap { reg } => {
+                let bitmap = reg.get(&cpuid(leaf, 0x0)) as u64;
+
+                Self::bitmap(bitmap, 1).for_each(f)
+            },
+            Self::XStateBitmap => {
+                let [sub0, sub1] = [cpuid(leaf, 0x0), cpuid(leaf, 0x1)];
+                let xcr0 = ((sub0.edx as u64) << 32) | (sub0.eax as u64);
+                let xss = ((sub1.edx as u64) << 32) | (sub1.ecx as u64);
+
+                Self::bitmap(xcr0 | xss, 2).for_each(f)
+            },
+        }
+    }
+}
+
+/// CPUID results of all valid leaves and sub-leaves for a logical processor.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CpuidSnapshot {
+    /// Logical processor number, `None` if taken without pinning the thread.
+    pub cpu: Option<usize>,
+    pub pool: Vec<RawCpuid>,
+}
+
+#[cfg(feature = "std")]
+impl CpuidSnapshot {
+    /// List of (Leaf, SubLeaf) to be executed, based on the largest standard/extended function
+    /// and the number of sub-leaves of each leaf.
+    pub fn leaf_pool_with<F: Fn(u32, u32) -> CpuidResult>(cpuid: &F) -> Vec<(u32, u32)> {
+        let mut leaf_pool: Vec<(u32, u32)> = Vec::with_capacity(64);
+
+        /* LFuncStd: largest standard function */
+        let max_std_leaf = cpuid(0x0, 0x0).eax;
+        /* LFuncExt: largest extended function */
+        let max_ext_leaf = cpuid(0x8000_0000, 0x0).eax;
+
+        let mut push = |leaf: u32| {
+            SubLeafRule::for_leaf(leaf).sub_leaves_with(leaf, cpuid, &mut |sub_leaf| {
+                leaf_pool.push((leaf, sub_leaf))
+            });
+        };
+
+        (0x0..=max_std_leaf).for_each(&mut push);
+
+        /* the extended functions are supported */
+        if (max_ext_leaf & 0xF000_0000) == 0x8000_0000 {
+            (0x8000_0000..=max_ext_leaf).for_each(&mut push);
+        }
+
+        leaf_pool
+    }
+
+    /// In addition to `leaf_pool_with`, scan sub-leaves up to `max_sub_leaf` of every leaf
+    /// to discover undocumented ones.
+    /// A sub-leaf is added if the result is not empty and differs from sub-leaf 0,
+    /// because the leaves without sub-leaves ignore ECX.
+    pub fn probe_leaf_pool_with<F: Fn(u32, u32) -> CpuidResult>(cpuid: &F, max_sub_leaf: u32) -> Vec<(u32, u32)> {
+        /* the Extended Topology leaves return the input ECX[7:0] even for an invalid level */
+        let is_empty = |r: &CpuidResult, sub_leaf: u32| {
+            r.eax == 0x0 && r.ebx == 0x0 && r.edx == 0x0 && (r.ecx == 0x0 || r.ecx == (sub_leaf & 0xFF))
+        };
+        let mut leaf_pool = Self::leaf_pool_with(cpuid);
+        let mut leaves: Vec<u32> = leaf_pool.iter().map(|(leaf, _)| *leaf).collect();
+        leaves.dedup();
+
+        for leaf in leaves {
+            let sub0 = cpuid(leaf, 0x0);
+
+            for sub_leaf in 0x1..=max_sub_leaf {
+                let result = cpuid(leaf, sub_leaf);
+
+                if !is_empty(&result, sub_leaf) && result != sub0 && !leaf_pool.contains(&(leaf, sub_leaf)) {
+                    leaf_pool.push((leaf, sub_leaf));
+                }
+            }
+        }
+
+        leaf_pool.sort_unstable();
+
+        leaf_pool
+    }
+
+    pub fn leaf_pool() -> Vec<(u32, u32)> {
+        Self::leaf_pool_with(&|leaf, sub_leaf| cpuid!(leaf, sub_leaf))
+    }
+
+    pub fn probe_leaf_pool(max_sub_leaf: u32) -> Vec<(u32, u32)> {
+        Self::probe_leaf_pool_with(&|leaf, sub_leaf| cpuid!(leaf, sub_leaf), max_sub_leaf)
+    }
+
+    /// Same as `take`, but the CPUID results are provided by the closure,
+    /// e.g. `/dev/cpu/<N>/cpuid` or a saved dump.
+    pub fn take_with<F: Fn(u32, u32) -> CpuidResult>(cpu: Option<usize>, cpuid: &F) -> Self {
+        let pool = Self::leaf_pool_with(cpuid).into_iter().map(|(leaf, sub_leaf)| {
+            RawCpuid { leaf, sub_leaf, result: cpuid(leaf, sub_leaf) }
+        }).collect();
+
+        Self { cpu, pool }
+    }
+
+    /// Take a snapshot on the current thread.
+    pub fn take() -> Self {
+        Self::take_with(None, &|leaf, sub_leaf| cpuid!(leaf, sub_leaf))
+    }
+
+    /// Take a snapshot on the logical processor, the current thread is not affected.
+    pub fn take_cpu(cpu: usize) -> Result<Self, Error> {
+        std::thread::scope(|s| s.spawn(|| {
+            crate::util::pin_thread(cpu)?;
+
+            Ok(Self::take_with(Some(cpu), &|leaf, sub_leaf| cpuid!(leaf, sub_leaf)))
+        }).join().unwrap())
+    }
+
+    /// Take snapshots on all logical processors in the affinity mask.
+    pub fn take_all() -> Result<Vec<Self>, Error> {
+        let cpu_list = crate::util::cpu_set_list()?;
+
+        std::thread::scope(|s| {
+            let handles: Vec<_> = cpu_list.iter().map(|&cpu| s.spawn(move || {
+                crate::util::pin_thread(cpu)?;
+
+                Ok(Self::take_with(Some(cpu), &|leaf, sub_leaf| cpuid!(leaf, sub_leaf)))
+            })).collect();
+
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        })
+    }
+
+    pub fn get(&self, leaf: u32, sub_leaf: u32) -> Option<&CpuidResult> {
+        self.pool.iter()
+            .find(|raw| raw.leaf == leaf && raw.sub_leaf == sub_leaf)
+            .map(|raw| &raw.result)
+    }
+
+    /// Same as `get`, but returns `Error::UnsupportedLeaf` for the leaves not in the snapshot.
+    pub fn try_get(&self, leaf: u32, sub_leaf: u32) -> Result<&CpuidResult, Error> {
+        self.get(leaf, sub_leaf).ok_or(Error::UnsupportedLeaf { leaf, sub_leaf })
+    }
+
+    /// Returns zero for the leaves not in the snapshot, like CPUID with an unsupported leaf on AMD.
+    pub fn cpuid(&self, leaf: u32, sub_leaf: u32) -> CpuidResult {
+        self.get(leaf, sub_leaf)
+            .copied()
+            .unwrap_or(CpuidResult { eax: 0x0, ebx: 0x0, ecx: 0x0, edx: 0x0 })
+    }
+
+    pub fn iter(&self) -> core::slice::Iter<'_, RawCpuid> {
+        self.pool.iter()
+    }
+}
+
+/* Ryzen 5 5600G, the first thread of dump_result/AMD_Ryzen_5_5600G_with_Radeon_Graphics_00A50F00.txt,
+   the tests replace only the leaves they vary */
+#[cfg(all(test, feature = "std"))]
+impl CpuidSnapshot {
+    pub(crate) fn ryzen_5_5600g() -> Self {
+        let threads = LoadedThread::parse_dump(
+            include_str!("../dump_result/AMD_Ryzen_5_5600G_with_Radeon_Graphics_00A50F00.txt"),
+        );
+
+        Self { cpu: Some(0), pool: threads[0].pool.clone() }
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_snapshot() {
+    /* Ryzen 5 5600G, a part of leaves */
+    let cpuid = |leaf: u32, sub_leaf: u32| -> CpuidResult {
+        let [eax, ebx, ecx, edx] = match (leaf, sub_leaf) {
+            (0x0, _) => [0x00000010, 0x68747541, 0x444D4163, 0x69746E65],
+            (0x1, _) => [0x00A50F00, 0x000C0800, 0x7EF8320B, 0x178BFBFF],
+            (0x7, 0x0) => [0x00000000, 0x219C97A9, 0x0040068C, 0x00000010],
+            (0xD, 0x0) => [0x00000207, 0x00000988, 0x00000988, 0x00000000],
+            (0xD, 0x1) => [0x0000000F, 0x00000348, 0x00001800, 0x00000000],
+            (0xD, 0x2) => [0x00000100, 0x00000240, 0x00000000, 0x00000000],
+            (0xD, 0x9) => [0x00000008, 0x00000980, 0x00000000, 0x00000000],
+            (0xD, 0xB) => [0x00000010, 0x00000000, 0x00000001, 0x00000000],
+            (0xD, 0xC) => [0x00000018, 0x00000000, 0x00000001, 0x00000000],
+            (0x8000_0000, _) => [0x80000023, 0x68747541, 0x444D4163, 0x69746E65],
+            (0x8000_0008, _) => [0x00003030, 0x111EF657, 0x0000500B, 0x00000000],
+            (0x8000_001D, 0x0) => [0x00004121, 0x01C0003F, 0x0000003F, 0x00000000],
+            (0x8000_001D, 0x1) => [0x00004122, 0x01C0003F, 0x0000003F, 0x00000000],
+            (0x8000_001D, 0x2) => [0x00004143, 0x01C0003F, 0x000003FF, 0x00000002],
+            (0x8000_001D, 0x3) => [0x0001C163, 0x03C0003F, 0x00003FFF, 0x00000001],
+            (0x8000_0020, 0x0) => [0x00000000, 0x00000002, 0x00000000, 0x00000000],
+            (0x8000_0020, 0x1) => [0x0000000B, 0x00000000, 0x00000000, 0x0000000F],
+            _ => [0x0; 4],
+        };
+
+        CpuidResult { eax, ebx, ecx, edx }
+    };
+
+    let snapshot = CpuidSnapshot::take_with(Some(0), &cpuid);
+    let leaf_pool: Vec<(u32, u32)> = snapshot.iter().map(|raw| (raw.leaf, raw.sub_leaf)).collect();
+
+    assert!(leaf_pool.contains(&(0x10, 0x0)));
+    assert!(!leaf_pool.contains(&(0x11, 0x0)));
+    assert!(leaf_pool.contains(&(0x7, 0x0)));
+    assert!(!leaf_pool.contains(&(0x7, 0x1)));
+    /* XCR0: X87, SSE, AVX, PKRU, IA32_XSS: CET_U, CET_S */
+    for sub_leaf in [0x0, 0x1, 0x2, 0x9, 0xB, 0xC] {
+        assert!(leaf_pool.contains(&(0xD, sub_leaf)));
+    }
+    assert!(!leaf_pool.contains(&(0xD, 0x3)));
+    /* L1d, L1i, L2, L3 */
+    assert!(leaf_pool.contains(&(0x8000_001D, 0x3)));
+    assert!(!leaf_pool.contains(&(0x8000_001D, 0x4)));
+    assert!(leaf_pool.contains(&(0x8000_0020, 0x1)));
+    assert!(!leaf_pool.contains(&(0x8000_0020, 0x2)));
+    assert!(leaf_pool.contains(&(0x8000_0023, 0x0)));
+    assert!(!leaf_pool.contains(&(0x8000_0024, 0x0)));
+
+    assert_eq!(snapshot.get(0x8000_0008, 0x0), Some(&cpuid(0x8000_0008, 0x0)));
+    assert_eq!(snapshot.get(0x8000_0024, 0x0), None);
+    assert!(matches!(
+        snapshot.try_get(0x8000_0024, 0x0),
+        Err(Error::UnsupportedLeaf { leaf: 0x8000_0024, sub_leaf: 0x0 }),
+    ));
+}
+
+#[test]
+fn test_parse_line() {
+    let expected = RawCpuid {
+        leaf: 0x8000_001D,
+        sub_leaf: 0x3,
+        result: CpuidResult { eax: 0x00014163, ebx: 0x03C0003F, ecx: 0x00003FFF, edx: 0x00000001 },
+    };
+
+    for ln in [
+        "  0x8000001D 0x3:  0x00014163 0x03C0003F 0x00003FFF 0x00000001  [L3, 16MiB]",
+        "   0x8000001d 0x03: eax=0x00014163 ebx=0x03c0003f ecx=0x00003fff edx=0x00000001",
+        " 8000001Dh_x3: eax=00014163h ebx=03C0003Fh ecx=00003FFFh edx=00000001h [L3, 16MiB]",
+    ] {
+        assert_eq!(RawCpuid::parse_line(ln), Some(expected.clone()));
+    }
+
+    assert_eq!(RawCpuid::parse_line("       [Leaf.Sub]    [EAX]     [EBX]     [ECX]     [EDX]"), None);
+    assert_eq!(RawCpuid::parse_line("                                        [SSE3] [PCLMULQDQ]"), None);
+}
+
+#[cfg(all(feature = "std", feature = "serde"))]
+#[test]
+fn test_snapshot_serde() {
+    let snapshot = CpuidSnapshot::ryzen_5_5600g();
+    let json = serde_json::to_string(&snapshot).unwrap();
+
+    /* the schema persisted by the downstream tools */
+    assert!(json.starts_with(concat!(
+        r#"{"cpu":0,"pool":["#,
+        r#"{"leaf":0,"sub_leaf":0,"result":{"eax":16,"ebx":1752462657,"ecx":1145913699,"edx":1769238117}},"#,
+        r#"{"leaf":1,"sub_leaf":0,"result":{"eax":10817280,"ebx":788480,"ecx":2130194955,"edx":395049983}},"#,
+    )));
+
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let pool = value["pool"].as_array().unwrap();
+
+    assert_eq!(pool.len(), snapshot.pool.len());
+
+    for raw in pool {
+        let keys: Vec<&str> = raw.as_object().unwrap().keys().map(|k| k.as_str()).collect();
+        let regs: Vec<&str> = raw["result"].as_object().unwrap().keys().map(|k| k.as_str()).collect();
+
+        assert_eq!(keys, ["leaf", "result", "sub_leaf"]);
+        assert_eq!(regs, ["eax", "ebx", "ecx", "edx"]);
+    }
+
+    assert_eq!(serde_json::from_str::<CpuidSnapshot>(&json).unwrap(), snapshot);
+
+    /* taken without pinning the thread */
+    let json = r#"{"cpu":null,"pool":[{"leaf":1,"sub_leaf":0,"result":{"eax":10817280,"ebx":788480,"ecx":2130194955,"edx":395049983}}]}"#;
+    let snapshot: CpuidSnapshot = serde_json::from_str(json).unwrap();
+
+    assert_eq!(snapshot.cpu, None);
+    assert_eq!(snapshot.cpuid(0x1, 0x0), CpuidResult { eax: 0x00A50F00, ebx: 0x000C0800, ecx: 0x7EF8320B, edx: 0x178BFBFF });
+    assert_eq!(serde_json::to_string(&snapshot).unwrap(), json);
+}
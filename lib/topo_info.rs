@@ -1,5 +1,6 @@
 use crate::{cpuid, CpuidResult, TopoLevelType};
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TopoId {
     pub smt_id: u32,
     pub core_id: u32,
@@ -8,9 +9,9 @@ pub struct TopoId {
 }
 
 impl TopoId {
-    fn check_topology_leaf(leaf: u32) -> bool {
+    fn check_topology_leaf<F: Fn(u32, u32) -> CpuidResult>(leaf: u32, cpuid: &F) -> bool {
         let sub_leaf = 0x1;
-        let cpuid = cpuid!(leaf, sub_leaf);
+        let cpuid = cpuid(leaf, sub_leaf);
 
         /* ECX[07-00]: Level number. Same value in ECX input (Sub_Leaf) */
         if (cpuid.ecx & 0xFF) != sub_leaf {
@@ -21,9 +22,13 @@ impl TopoId {
     }
 
     pub(crate) fn get_topology_leaf() -> Option<u32> {
-        let topo_leaf = if Self::check_topology_leaf(0x1F) {
+        Self::get_topology_leaf_with(&|leaf, sub_leaf| cpuid!(leaf, sub_leaf))
+    }
+
+    fn get_topology_leaf_with<F: Fn(u32, u32) -> CpuidResult>(cpuid: &F) -> Option<u32> {
+        let topo_leaf = if Self::check_topology_leaf(0x1F, cpuid) {
             0x1F
-        } else if Self::check_topology_leaf(0xB) {
+        } else if Self::check_topology_leaf(0xB, cpuid) {
             0xB
         } else {
             return None;
@@ -32,12 +37,13 @@ impl TopoId {
         Some(topo_leaf)
     }
 
-    pub(crate) fn get_cpuid_by_level_type(
+    pub(crate) fn get_cpuid_by_level_type<F: Fn(u32, u32) -> CpuidResult>(
         topo_leaf: u32,
-        target_level_type: TopoLevelType
+        target_level_type: TopoLevelType,
+        cpuid: &F,
     ) -> Option<CpuidResult> {
         for sub_leaf in 0..(TopoLevelType::Die as u32) {
-            let cpuid = cpuid!(topo_leaf, sub_leaf);
+            let cpuid = cpuid(topo_leaf, sub_leaf);
             let level_type = {
                 let reg = (cpuid.ecx >> 8) & 0xFF;
 
@@ -57,10 +63,16 @@ impl TopoId {
         https://www.intel.com/content/dam/develop/external/us/en/documents/kuo-cputopology-rc1-rh1-final-256920.pdf
     */
     pub fn get_topo_info() -> Option<Self> {
-        let topo_leaf = Self::get_topology_leaf()?;
+        Self::get_topo_info_with(&|leaf, sub_leaf| cpuid!(leaf, sub_leaf))
+    }
+
+    /// Same as `get_topo_info`, but CPUID results are provided by `cpuid(leaf, sub_leaf)`,
+    /// e.g. read from `/dev/cpu/<N>/cpuid` or a saved dump.
+    pub fn get_topo_info_with<F: Fn(u32, u32) -> CpuidResult>(cpuid: &F) -> Option<Self> {
+        let topo_leaf = Self::get_topology_leaf_with(cpuid)?;
 
-        let smt_cpuid = Self::get_cpuid_by_level_type(topo_leaf, TopoLevelType::SMT)?;
-        let core_cpuid = Self::get_cpuid_by_level_type(topo_leaf, TopoLevelType::Core)?;
+        let smt_cpuid = Self::get_cpuid_by_level_type(topo_leaf, TopoLevelType::SMT, cpuid)?;
+        let core_cpuid = Self::get_cpuid_by_level_type(topo_leaf, TopoLevelType::Core, cpuid)?;
 
         let x2apic_id = smt_cpuid.edx;
 
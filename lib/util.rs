@@ -1,7 +1,9 @@
 use crate::{cpuid, CacheProp, TopoId, TopoLevelType};
+#[cfg(feature = "std")]
+use crate::Error;
 
 #[cfg(feature = "std")]
-pub fn pin_thread(cpu: usize) -> Result<(), i32> {
+pub fn pin_thread(cpu: usize) -> Result<(), Error> {
     #[cfg(unix)]
     unsafe {
         use libc::{
@@ -19,8 +21,7 @@ pub fn pin_thread(cpu: usize) -> Result<(), i32> {
 
         let status = sched_setaffinity(0, std::mem::size_of::<cpu_set_t>(), &set);
         if status == -1 {
-            eprintln!("sched_setaffinity failed.");
-            return Err(status);
+            return Err(Error::PinThread { cpu, source: std::io::Error::last_os_error() });
         }
     }
 
@@ -30,14 +31,18 @@ pub fn pin_thread(cpu: usize) -> Result<(), i32> {
             GetCurrentThread,
             SetThreadAffinityMask,
         };
-        SetThreadAffinityMask(GetCurrentThread(), 1 << cpu);
+
+        /* returns 0 on failure */
+        if SetThreadAffinityMask(GetCurrentThread(), 1 << cpu) == 0 {
+            return Err(Error::PinThread { cpu, source: std::io::Error::last_os_error() });
+        }
     }
 
     Ok(())
 }
 
 #[cfg(feature = "std")]
-pub fn cpu_set_list() -> Result<Vec<usize>, i32> {
+pub fn cpu_set_list() -> Result<Vec<usize>, Error> {
     let mut cpus: Vec<usize> = Vec::with_capacity(256);
 
     #[cfg(unix)]
@@ -56,8 +61,7 @@ pub fn cpu_set_list() -> Result<Vec<usize>, i32> {
 
         let status = sched_getaffinity(0, mem::size_of::<cpu_set_t>(), &mut set);
         if status == -1 {
-            eprintln!("sched_getaffinity failed");
-            return Err(status);
+            return Err(Error::CpuSetList(std::io::Error::last_os_error()));
         }
 
         for i in 0..CPU_SETSIZE as usize {
@@ -82,6 +86,26 @@ pub fn cpu_set_list() -> Result<Vec<usize>, i32> {
     Ok(cpus)
 }
 
+/// Check the leaf is within the largest function of its range
+/// (Standard: 0x0, Hypervisor: 0x4000_0000, Extended: 0x8000_0000, Centaur: 0xC000_0000).
+/// Intel CPUs return the data of the highest basic leaf for an unsupported leaf.
+pub fn is_leaf_supported(leaf: u32) -> bool {
+    let base = leaf & 0xF000_0000;
+    let max_leaf = cpuid!(base, 0x0).eax;
+
+    (max_leaf & 0xF000_0000) == base && leaf <= max_leaf
+}
+
+/// Same as `is_leaf_supported`, but returns `Error::UnsupportedLeaf` for the unsupported leaf.
+#[cfg(feature = "std")]
+pub fn check_leaf_supported(leaf: u32, sub_leaf: u32) -> Result<(), Error> {
+    if is_leaf_supported(leaf) {
+        Ok(())
+    } else {
+        Err(Error::UnsupportedLeaf { leaf, sub_leaf })
+    }
+}
+
 pub fn get_total_logical_processor() -> Option<u32> {
     let topo_leaf = match TopoId::get_topology_leaf() {
         Some(v) => v,
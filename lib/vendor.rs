@@ -1,5 +1,6 @@
 use crate::{cpuid, CpuidResult};
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Vendor {
     pub ebx: u32,
@@ -26,6 +27,12 @@ impl Vendor {
         edx: 0x4872_7561,
     };
 
+    const REG_HYGON: Self = Self {
+        ebx: 0x6F67_7948,
+        ecx: 0x656E_6975,
+        edx: 0x6E65_476E,
+    };
+
     const REG_SHANGHAI: Self = Self {
         ebx: 0x6853_2020,
         ecx: 0x2020_6961,
@@ -69,12 +76,14 @@ impl From<&CpuidResult> for Vendor {
     }
 }
 
-#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CpuVendor {
     AuthenticAMD,
     GenuineIntel,
     CentaurHauls,
     Shanghai,
+    HygonGenuine,
     Unknown(Vendor),
 }
 
@@ -85,6 +94,7 @@ impl From<&Vendor> for CpuVendor {
             Vendor::REG_INTEL => Self::GenuineIntel,
             Vendor::REG_CENTAUR => Self::CentaurHauls,
             Vendor::REG_SHANGHAI => Self::Shanghai,
+            Vendor::REG_HYGON => Self::HygonGenuine,
             _ => Self::Unknown(vendor.clone()),
         }
     }
@@ -100,6 +110,11 @@ impl CpuVendor {
     pub fn get() -> Self {
         Self::from(&cpuid!(0x0, 0x0))
     }
+
+    /* Hygon Dhyana is derived from AMD Zen, and has the same extended leaves */
+    pub fn is_amd_compatible(&self) -> bool {
+        matches!(self, Self::AuthenticAMD | Self::HygonGenuine)
+    }
 }
 
 #[cfg(feature = "std")]
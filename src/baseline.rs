@@ -0,0 +1,171 @@
+use crate::*;
+use libcpuid_dump::{AddressSize, CpuFeature, FamModStep, FeatureSet, MicroArchLevel, ProcInfo};
+
+/* A host of the fleet, loaded from a dump file */
+pub(crate) struct Host {
+    pub name: String,
+    pub snapshot: CpuidSnapshot,
+}
+
+/* "#0, #2" */
+fn host_list(hosts: impl Iterator<Item = usize>) -> String {
+    hosts.map(|i| format!("#{i}")).collect::<Vec<String>>().join(", ")
+}
+
+/* "(limited by #1)", empty if all hosts are the same */
+fn limited_by<T: PartialEq>(values: &[T], min: &T) -> String {
+    if values.iter().all(|v| v == min) {
+        return "".to_string();
+    }
+
+    let list = host_list(values.iter().enumerate().filter(|(_, v)| *v == min).map(|(i, _)| i));
+
+    format!(" (limited by {list})")
+}
+
+/* XSAVE state components, (XCR0, IA32_XSS) */
+fn xsave_masks(cpuid: &dyn Fn(u32, u32) -> CpuidResult) -> (u64, u64) {
+    let [sub_0, sub_1] = [cpuid(0xD, 0x0), cpuid(0xD, 0x1)];
+
+    (
+        ((sub_0.edx as u64) << 32) | sub_0.eax as u64,
+        ((sub_1.edx as u64) << 32) | sub_1.ecx as u64,
+    )
+}
+
+fn xsave_name(bit: u32, xss: bool) -> String {
+    let names = if xss { xsave_00_0d_ecx_x1() } else { xfeature_mask_00_0d_eax_x0() };
+
+    match names.get(bit as usize) {
+        Some(name) if !name.is_empty() => name.to_string(),
+        _ => format!("bit {bit}"),
+    }
+}
+
+/* The common features, x86-64 level, address sizes and XSAVE state components of the hosts */
+pub(crate) fn baseline_report(hosts: &[Host]) -> String {
+    let cpuid_fns: Vec<_> = hosts.iter()
+        .map(|host| move |leaf: u32, sub_leaf: u32| host.snapshot.cpuid(leaf, sub_leaf))
+        .collect();
+    let mut out = String::from("[Hosts]\n");
+
+    for (i, (host, cpuid)) in hosts.iter().zip(cpuid_fns.iter()).enumerate() {
+        let vendor = CpuVendor::from(&cpuid(0x0, 0x0));
+        let info = ProcInfo::from_fms(&FamModStep::from(&cpuid(0x1, 0x0)), &vendor);
+
+        out.push_str(&format!("  #{i} {} [{}, {}]\n", host.name, info.codename, info.archname));
+    }
+
+    /* x86-64 micro-architecture level */
+    let levels: Vec<MicroArchLevel> = cpuid_fns.iter().map(MicroArchLevel::check_with).collect();
+    let min_level = *levels.iter().min().unwrap();
+
+    out.push_str(&format!(
+        "[x86-64 micro-architecture level]\n  {min_level:?}{}\n",
+        limited_by(&levels, &min_level),
+    ));
+
+    /* Address size, leaf 0x8000_0008 is not reported by very old processors */
+    let addr: Vec<AddressSize> = cpuid_fns.iter().map(|cpuid| AddressSize::from(&cpuid(0x8000_0008, 0x0))).collect();
+    let phys: Vec<u8> = addr.iter().map(|a| a.physical).collect();
+    let virt: Vec<u8> = addr.iter().map(|a| a.virtual_).collect();
+
+    out.push_str("[Address size]\n");
+
+    for (name, sizes) in [("physical", phys), ("virtual", virt)] {
+        let min = *sizes.iter().filter(|size| **size != 0).min().unwrap_or(&0);
+
+        out.push_str(&format!("  {name:<9} {min}-bits{}\n", limited_by(&sizes, &min)));
+    }
+
+    /* XSAVE state components */
+    let masks: Vec<(u64, u64)> = cpuid_fns.iter().map(|cpuid| xsave_masks(cpuid)).collect();
+
+    out.push_str("[XSAVE features]\n");
+
+    for (name, xss) in [("XCR0", false), ("IA32_XSS", true)] {
+        let masks: Vec<u64> = masks.iter().map(|(xcr0, ss)| if xss { *ss } else { *xcr0 }).collect();
+        let common = masks.iter().fold(u64::MAX, |acc, m| acc & m);
+        let all = masks.iter().fold(0, |acc, m| acc | m);
+        let names: Vec<String> = (0..64).filter(|bit| (common >> bit) & 1 == 1).map(|bit| format!("[{}]", xsave_name(bit, xss))).collect();
+
+        out.push_str(&format!("  {name:<9} {common:#018X} {}\n", names.join(" ")));
+
+        for bit in (0..64).filter(|bit| ((all & !common) >> bit) & 1 == 1) {
+            let missing = host_list(masks.iter().enumerate().filter(|(_, m)| (*m >> bit) & 1 == 0).map(|(i, _)| i));
+
+            out.push_str(&format!("    (-) {:<24} missing on {missing}\n", xsave_name(bit, xss)));
+        }
+    }
+
+    /* Feature flags */
+    let sets: Vec<FeatureSet> = cpuid_fns.iter().map(FeatureSet::from_cpuid_with).collect();
+    let common = sets.iter().fold(sets[0], |acc, set| acc.intersection(set));
+    let dropped = sets.iter().fold(FeatureSet::default(), |acc, set| acc.union(set)).difference(&common);
+
+    let ftr_fmt = |ftr: &CpuFeature| -> String {
+        let CpuFeature { leaf, sub_leaf, reg, bit } = ftr;
+
+        format!("{:<24} {leaf:#010X} {sub_leaf:#X} {reg:?}[{bit:02}]", ftr_name(ftr).unwrap_or(""))
+    };
+
+    /* the unnamed bits, e.g. AMD copies of CPUID[0x1].EDX in CPUID[0x8000_0001].EDX, are omitted */
+    let names: Vec<&str> = common.iter().filter_map(|ftr| ftr_name(&ftr)).collect();
+    let mut ln = String::from(" ");
+
+    out.push_str(&format!("[Common features: {}]\n", names.len()));
+
+    for name in names {
+        let name = format!(" [{name}]");
+
+        if total_width() < ln.len() + name.len() {
+            out.push_str(&format!("{ln}\n"));
+            ln = String::from(" ");
+        }
+        ln.push_str(&name);
+    }
+
+    out.push_str(&format!("{ln}\n"));
+
+    out.push_str(&format!("[Dropped features: {}]\n", dropped.iter().count()));
+
+    for ftr in dropped.iter() {
+        let missing = host_list(sets.iter().enumerate().filter(|(_, set)| !set.has(ftr)).map(|(i, _)| i));
+
+        out.push_str(&format!("  {}  missing on {missing}\n", ftr_fmt(&ftr)));
+    }
+
+    out
+}
+
+#[test]
+fn test_baseline_report() {
+    let hosts = [
+        Host {
+            name: "5600G".to_string(),
+            snapshot: ryzen_5_5600g(),
+        },
+        Host {
+            name: "2600".to_string(),
+            snapshot: snapshot_from_regs(&[
+                (0x0, 0x0, [0x0000000D, 0x68747541, 0x444D4163, 0x69746E65]),
+                (0x1, 0x0, [0x00800F82, 0x000C0800, 0x7ED8320B, 0x178BFBFF]),
+                (0x7, 0x0, [0x00000000, 0x209C01A9, 0x00000000, 0x00000000]),
+                (0xD, 0x0, [0x00000007, 0x00000340, 0x00000340, 0x00000000]),
+                (0xD, 0x1, [0x0000000F, 0x00000340, 0x00000000, 0x00000000]),
+                (0x8000_0000, 0x0, [0x8000001F, 0x68747541, 0x444D4163, 0x69746E65]),
+                (0x8000_0001, 0x0, [0x00800F82, 0x20000000, 0x35C233FF, 0x2FD3FBFF]),
+                (0x8000_0008, 0x0, [0x00003030, 0x00001007, 0x0000400B, 0x00000000]),
+            ]),
+        },
+    ];
+    let s = baseline_report(&hosts);
+
+    assert!(s.contains("[x86-64 micro-architecture level]\n  X86_64_V3\n"));
+    assert!(s.contains("  physical  48-bits\n"));
+    assert!(s.contains("  XCR0      0x0000000000000007 [X87] [SSE] [AVX256]\n"));
+    assert!(s.contains("    (-) Protection Key User      missing on #1\n"));
+    assert!(s.contains("  VAES                     0x00000007 0x0 ECX[09]  missing on #1\n"));
+    assert!(s.contains(" [AVX2] "));
+    assert!(!s.contains("AVX2                     0x00000007 0x0 EBX[05]  missing"));
+}
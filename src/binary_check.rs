@@ -0,0 +1,380 @@
+use crate::*;
+use libcpuid_dump::{CpuFeature, FeatureSet, MicroArchLevel};
+use std::convert::TryInto;
+
+/*
+    GNU property note of x86 ELF binaries, written by binutils 2.36 or later
+    ("-Wa,-mx86-used-note=yes", "-Wl,-z,x86-64-v3"), same as `readelf -n`.
+    ref: https://gitlab.com/x86-psABIs/x86-64-ABI, "Program Property"
+*/
+const NT_GNU_PROPERTY_TYPE_0: u32 = 5;
+const GNU_PROPERTY_X86_FEATURE_2_NEEDED: u32 = 0xC000_8001;
+const GNU_PROPERTY_X86_ISA_1_NEEDED: u32 = 0xC000_8002;
+const GNU_PROPERTY_X86_FEATURE_2_USED: u32 = 0xC001_0001;
+const GNU_PROPERTY_X86_ISA_1_USED: u32 = 0xC001_0002;
+
+const PT_NOTE: u32 = 4;
+const SHT_NOTE: u32 = 7;
+const EM_386: u16 = 3;
+const EM_X86_64: u16 = 62;
+
+/* GNU_PROPERTY_X86_FEATURE_2_*, the bits without the CPUID feature flag are `None` */
+const X86_FEATURE_2: [(&str, Option<CpuFeature>); 12] = {
+    use CpuidReg::*;
+
+    [
+        ("x86", None),
+        ("x87", Some(CpuFeature::new(0x1, 0x0, EDX, 0))),
+        ("MMX", Some(CpuFeature::new(0x1, 0x0, EDX, 23))),
+        ("XMM", Some(CpuFeature::new(0x1, 0x0, EDX, 25))),
+        ("YMM", Some(CpuFeature::new(0x1, 0x0, ECX, 28))),
+        ("ZMM", Some(CpuFeature::new(0x7, 0x0, EBX, 16))),
+        ("FXSR", Some(CpuFeature::new(0x1, 0x0, EDX, 24))),
+        ("XSAVE", Some(CpuFeature::new(0x1, 0x0, ECX, 26))),
+        ("XSAVEOPT", Some(CpuFeature::new(0xD, 0x1, EAX, 0))),
+        ("XSAVEC", Some(CpuFeature::new(0xD, 0x1, EAX, 1))),
+        ("TMM", Some(CpuFeature::new(0x7, 0x0, EDX, 24))),
+        ("MASK", Some(CpuFeature::new(0x7, 0x0, EBX, 16))),
+    ]
+};
+
+/* GNU_PROPERTY_X86_ISA_1_*, the bits of the x86-64 micro-architecture levels */
+const X86_ISA_1: [(&str, MicroArchLevel); 4] = [
+    ("x86-64-baseline", MicroArchLevel::X86_64_V1),
+    ("x86-64-v2", MicroArchLevel::X86_64_V2),
+    ("x86-64-v3", MicroArchLevel::X86_64_V3),
+    ("x86-64-v4", MicroArchLevel::X86_64_V4),
+];
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct X86IsaNote {
+    pub elf64: bool,
+    pub machine: u16,
+    /* the GNU property note is found */
+    pub found: bool,
+    pub isa_needed: u32,
+    pub isa_used: u32,
+    pub feature_needed: u32,
+    pub feature_used: u32,
+}
+
+fn read_u16(b: &[u8], off: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(b.get(off..off.checked_add(2)?)?.try_into().ok()?))
+}
+
+fn read_u32(b: &[u8], off: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(b.get(off..off.checked_add(4)?)?.try_into().ok()?))
+}
+
+fn read_u64(b: &[u8], off: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(b.get(off..off.checked_add(8)?)?.try_into().ok()?))
+}
+
+fn align_up(v: usize, align: usize) -> usize {
+    (v + align - 1) & !(align - 1)
+}
+
+impl X86IsaNote {
+    pub fn parse(b: &[u8]) -> Result<Self, String> {
+        if b.get(..4) != Some(b"\x7FELF") {
+            return Err("not an ELF file".to_string());
+        }
+
+        let elf64 = match b.get(4) {
+            Some(1) => false,
+            Some(2) => true,
+            _ => return Err("invalid ELF class".to_string()),
+        };
+
+        if b.get(5) != Some(&1) {
+            return Err("big-endian ELF is not supported".to_string());
+        }
+
+        let machine = read_u16(b, 18).ok_or("truncated ELF header")?;
+
+        if machine != EM_386 && machine != EM_X86_64 {
+            return Err(format!("not an x86 ELF file (e_machine: {machine})"));
+        }
+
+        let word = |b: &[u8], off: usize| -> Option<usize> {
+            if elf64 { read_u64(b, off)?.try_into().ok() } else { Some(read_u32(b, off)? as usize) }
+        };
+        let hdr = || -> Option<[usize; 6]> {
+            Some(if elf64 {
+                [word(b, 32)?, read_u16(b, 54)? as usize, read_u16(b, 56)? as usize,
+                 word(b, 40)?, read_u16(b, 58)? as usize, read_u16(b, 60)? as usize]
+            } else {
+                [word(b, 28)?, read_u16(b, 42)? as usize, read_u16(b, 44)? as usize,
+                 word(b, 32)?, read_u16(b, 46)? as usize, read_u16(b, 48)? as usize]
+            })
+        };
+        let [phoff, phentsize, phnum, shoff, shentsize, shnum] = hdr().ok_or("truncated ELF header")?;
+        /* the i-th entry of the header table, the offsets are read from the file */
+        let entry = |off: usize, entsize: usize, i: usize| -> Result<&[u8], String> {
+            i.checked_mul(entsize)
+                .and_then(|v| v.checked_add(off))
+                .and_then(|start| b.get(start..))
+                .ok_or_else(|| "truncated ELF header".to_string())
+        };
+
+        /* (offset, size, align) of the notes, the sections are for the relocatable files */
+        let mut notes: Vec<(usize, usize, usize)> = Vec::new();
+
+        for i in 0..phnum {
+            let ph = entry(phoff, phentsize, i)?;
+            let note = if elf64 {
+                (read_u32(ph, 0), word(ph, 8), word(ph, 32), word(ph, 48))
+            } else {
+                (read_u32(ph, 0), word(ph, 4), word(ph, 16), word(ph, 28))
+            };
+
+            if let (Some(PT_NOTE), Some(off), Some(size), Some(align)) = note {
+                notes.push((off, size, align));
+            }
+        }
+
+        if notes.is_empty() {
+            for i in 0..shnum {
+                let sh = entry(shoff, shentsize, i)?;
+                let note = if elf64 {
+                    (read_u32(sh, 4), word(sh, 24), word(sh, 32), word(sh, 48))
+                } else {
+                    (read_u32(sh, 4), word(sh, 16), word(sh, 20), word(sh, 32))
+                };
+
+                if let (Some(SHT_NOTE), Some(off), Some(size), Some(align)) = note {
+                    notes.push((off, size, align));
+                }
+            }
+        }
+
+        let mut isa = Self { elf64, machine, ..Default::default() };
+
+        for (off, size, align) in notes {
+            let align = if align == 8 { 8 } else { 4 };
+            let seg = b.get(off..off.saturating_add(size)).ok_or("truncated note")?;
+
+            isa.parse_notes(seg, align);
+        }
+
+        Ok(isa)
+    }
+
+    fn parse_notes(&mut self, seg: &[u8], align: usize) {
+        let mut pos = 0;
+
+        while let (Some(namesz), Some(descsz), Some(n_type)) = (read_u32(seg, pos), read_u32(seg, pos + 4), read_u32(seg, pos + 8)) {
+            let [namesz, descsz] = [namesz as usize, descsz as usize];
+            let name = seg.get(pos + 12..pos + 12 + namesz);
+            let desc_off = align_up(pos + 12 + namesz, align);
+            let desc = match seg.get(desc_off..desc_off + descsz) {
+                Some(desc) => desc,
+                None => return,
+            };
+
+            if n_type == NT_GNU_PROPERTY_TYPE_0 && name == Some(b"GNU\0") {
+                self.found = true;
+                self.parse_properties(desc);
+            }
+
+            pos = align_up(desc_off + descsz, align);
+        }
+    }
+
+    fn parse_properties(&mut self, desc: &[u8]) {
+        let align = if self.elf64 { 8 } else { 4 };
+        let mut pos = 0;
+
+        while let (Some(pr_type), Some(pr_datasz)) = (read_u32(desc, pos), read_u32(desc, pos + 4)) {
+            let data = read_u32(desc, pos + 8).unwrap_or(0);
+
+            match pr_type {
+                GNU_PROPERTY_X86_ISA_1_NEEDED => self.isa_needed |= data,
+                GNU_PROPERTY_X86_ISA_1_USED => self.isa_used |= data,
+                GNU_PROPERTY_X86_FEATURE_2_NEEDED => self.feature_needed |= data,
+                GNU_PROPERTY_X86_FEATURE_2_USED => self.feature_used |= data,
+                _ => {},
+            }
+
+            pos = align_up(pos + 8 + pr_datasz as usize, align);
+        }
+    }
+
+    /* x86-64 baseline is assumed for the x86-64 binaries without the note */
+    fn needed_level(&self) -> Option<MicroArchLevel> {
+        let level = X86_ISA_1.iter().rev().find(|(_, level)| (self.isa_needed >> (*level as u32 - 1)) & 1 == 1);
+
+        match level {
+            Some((_, level)) => Some(*level),
+            None if self.machine == EM_X86_64 => Some(MicroArchLevel::X86_64_V1),
+            None => None,
+        }
+    }
+
+    fn features(isa: u32, ftr_2: u32) -> FeatureSet {
+        let mut set = X86_ISA_1.iter()
+            .filter(|(_, level)| (isa >> (*level as u32 - 1)) & 1 == 1)
+            .fold(FeatureSet::default(), |acc, (_, level)| acc.union(&level.feature_set()));
+
+        for (bit, (_, ftr)) in X86_FEATURE_2.iter().enumerate() {
+            if let (true, Some(ftr)) = ((ftr_2 >> bit) & 1 == 1, ftr) {
+                set.insert(*ftr);
+            }
+        }
+
+        set
+    }
+
+    pub fn needed(&self) -> FeatureSet {
+        let level = self.needed_level().map_or(FeatureSet::default(), |level| level.feature_set());
+
+        level.union(&Self::features(self.isa_needed, self.feature_needed))
+    }
+
+    pub fn used(&self) -> FeatureSet {
+        Self::features(self.isa_used, self.feature_used)
+    }
+}
+
+/* "x86-64-baseline, x86-64-v3" */
+fn isa_names(isa: u32) -> String {
+    let names: Vec<&str> = X86_ISA_1.iter()
+        .filter(|(_, level)| (isa >> (*level as u32 - 1)) & 1 == 1)
+        .map(|(name, _)| *name)
+        .collect();
+
+    if names.is_empty() { "-".to_string() } else { names.join(", ") }
+}
+
+fn feature_names(ftr_2: u32) -> String {
+    let names: Vec<&str> = X86_FEATURE_2.iter()
+        .enumerate()
+        .filter(|(bit, _)| (ftr_2 >> bit) & 1 == 1)
+        .map(|(_, (name, _))| *name)
+        .collect();
+
+    if names.is_empty() { "-".to_string() } else { names.join(", ") }
+}
+
+/* The report and whether all needed features are supported by `target` */
+pub(crate) fn binary_check_report(isa: &X86IsaNote, target: &FeatureSet) -> (String, bool) {
+    let mut out = format!(
+        "[ELF{}, {}]\n",
+        if isa.elf64 { "64" } else { "32" },
+        if isa.machine == EM_X86_64 { "x86-64" } else { "i386" },
+    );
+
+    if isa.found {
+        out.push_str(&format!("  x86 ISA needed:     {}\n", isa_names(isa.isa_needed)));
+        out.push_str(&format!("  x86 ISA used:       {}\n", isa_names(isa.isa_used)));
+        out.push_str(&format!("  x86 feature needed: {}\n", feature_names(isa.feature_needed)));
+        out.push_str(&format!("  x86 feature used:   {}\n", feature_names(isa.feature_used)));
+    } else {
+        out.push_str("  the GNU property note (x86 ISA needed) is not found\n");
+    }
+
+    if let Some(level) = isa.needed_level() {
+        let assumed = if isa.isa_needed == 0 { " (assumed)" } else { "" };
+
+        out.push_str(&format!("  x86-64 micro-architecture level: {level:?}{assumed}\n"));
+    }
+
+    let needed = isa.needed();
+    let used = isa.used().difference(&needed);
+    let mut supported = true;
+
+    for (head, set) in [("[Needed features]", needed), ("[Used features, not needed]", used)] {
+        if set.is_empty() {
+            continue;
+        }
+
+        out.push_str(&format!("{head}\n"));
+
+        for ftr in set.iter() {
+            let CpuFeature { leaf, sub_leaf, reg, bit } = ftr;
+            let has = target.has(ftr);
+
+            out.push_str(&format!(
+                "  [{}] {:<24} {leaf:#010X} {sub_leaf:#X} {reg:?}[{bit:02}]\n",
+                if has { "Y" } else { "N" },
+                ftr_name(&ftr).unwrap_or(""),
+            ));
+        }
+    }
+
+    for ftr in needed.difference(target).iter() {
+        supported = false;
+        out.push_str(&format!("(!) {} is not supported, the binary may raise SIGILL\n", ftr_name(&ftr).unwrap_or("")));
+    }
+
+    /* the instructions are not disassembled, only the note tells the ISA */
+    if isa.isa_needed == 0 {
+        out.push_str("(?) The binary does not record the x86 ISA needed, no ISA check was possible\n");
+        out.push_str("    (built without \"-z x86-64-vN\"), it may still raise SIGILL\n");
+    }
+
+    (out, supported)
+}
+
+#[test]
+fn test_binary_check() {
+    /* ELF64 header + PT_NOTE + NT_GNU_PROPERTY_TYPE_0 of "-Wl,-z,x86-64-v4" */
+    let mut elf = vec![0u8; 0x40 + 0x38];
+
+    elf[..8].copy_from_slice(b"\x7FELF\x02\x01\x01\x00");
+    elf[18..20].copy_from_slice(&EM_X86_64.to_le_bytes());
+    elf[32..40].copy_from_slice(&0x40u64.to_le_bytes());
+    elf[54..56].copy_from_slice(&0x38u16.to_le_bytes());
+    elf[56..58].copy_from_slice(&1u16.to_le_bytes());
+    /* p_type, p_offset, p_filesz, p_align */
+    elf[0x40..0x44].copy_from_slice(&PT_NOTE.to_le_bytes());
+    elf[0x48..0x50].copy_from_slice(&0x78u64.to_le_bytes());
+    elf[0x60..0x68].copy_from_slice(&0x20u64.to_le_bytes());
+    elf[0x70..0x78].copy_from_slice(&0x8u64.to_le_bytes());
+
+    for v in [4u32, 0x10, NT_GNU_PROPERTY_TYPE_0] {
+        elf.extend(v.to_le_bytes());
+    }
+    elf.extend(b"GNU\0");
+    for v in [GNU_PROPERTY_X86_ISA_1_NEEDED, 4, 0b1001, 0] {
+        elf.extend(v.to_le_bytes());
+    }
+
+    let isa = X86IsaNote::parse(&elf).unwrap();
+
+    assert!(isa.found);
+    assert_eq!(isa.needed_level(), Some(MicroArchLevel::X86_64_V4));
+    assert_eq!(isa_names(isa.isa_needed), "x86-64-baseline, x86-64-v4");
+
+    /* Ryzen 5 5600G, x86-64-v3 */
+    let snapshot = ryzen_5_5600g();
+    let zen3 = FeatureSet::from_cpuid_with(&|leaf, sub_leaf| snapshot.cpuid(leaf, sub_leaf));
+    let (s, supported) = binary_check_report(&isa, &zen3);
+
+    assert!(!supported);
+    assert!(s.contains("  [Y] AVX2                     0x00000007 0x0 EBX[05]\n"));
+    assert!(s.contains("  [N] AVX512F                  0x00000007 0x0 EBX[16]\n"));
+    assert!(s.contains("(!) AVX512F is not supported"));
+
+    assert!(!s.contains("(?) "));
+    assert!(X86IsaNote::parse(b"#!/bin/sh\n").is_err());
+
+    /* without the note, only the assumed baseline */
+    let plain = X86IsaNote { elf64: true, machine: EM_X86_64, ..Default::default() };
+    let (s, supported) = binary_check_report(&plain, &zen3);
+
+    assert!(supported);
+    assert!(s.contains("  x86-64 micro-architecture level: X86_64_V1 (assumed)\n"));
+    assert!(s.contains("(?) The binary does not record the x86 ISA needed, no ISA check was possible\n"));
+
+    /* e_phoff near the end of the address space */
+    let mut elf = vec![0u8; 0x40];
+
+    elf[..8].copy_from_slice(b"\x7FELF\x02\x01\x01\x00");
+    elf[18..20].copy_from_slice(&EM_X86_64.to_le_bytes());
+    elf[32..40].copy_from_slice(&0xFFFF_FFFF_FFFF_FFF0u64.to_le_bytes());
+    elf[54..56].copy_from_slice(&0x38u16.to_le_bytes());
+    elf[56..58].copy_from_slice(&2u16.to_le_bytes());
+
+    assert_eq!(X86IsaNote::parse(&elf), Err("truncated ELF header".to_string()));
+}
@@ -0,0 +1,928 @@
+use crate::*;
+use std::ops::RangeInclusive;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Command {
+    Dump,
+    Leaf,
+    Check,
+    Diff,
+    Topo,
+    Load,
+    Export,
+    Explain,
+    Security,
+    Baseline,
+    BinaryCheck,
+    Watch,
+    Tui,
+}
+
+/* <LEAF>[:<SUB_LEAF>], e.g. "0x7:0-2", "0x8000_0000..0x8000_0008" */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LeafSpec {
+    pub leaf: RangeInclusive<u32>,
+    pub sub_leaf: RangeInclusive<u32>,
+}
+
+impl LeafSpec {
+    /* to avoid executing CPUID 2^32 times by a typo */
+    const MAX_RANGE_LEN: u32 = 0x100;
+
+    pub fn from_str(s: &str) -> Result<Self, Error> {
+        let (leaf, sub_leaf) = match s.split_once(':') {
+            Some((leaf, sub_leaf)) => (parse_range(leaf)?, parse_range(sub_leaf)?),
+            None => (parse_range(s)?, 0x0..=0x0),
+        };
+
+        for range in [&leaf, &sub_leaf] {
+            if Self::MAX_RANGE_LEN <= (range.end() - range.start()) {
+                return Err(Error::Parse(format!("range is too large: \"{s}\"")));
+            }
+        }
+
+        Ok(Self { leaf, sub_leaf })
+    }
+
+    pub fn to_pool(&self) -> Vec<(u32, u32)> {
+        self.leaf.clone().flat_map(|leaf| {
+            self.sub_leaf.clone().map(move |sub_leaf| (leaf, sub_leaf))
+        }).collect()
+    }
+
+    /* the range does not cross the standard/extended function boundary */
+    pub fn check_supported(&self) -> Result<(), Error> {
+        use libcpuid_dump::util::check_leaf_supported;
+
+        let [start, end] = [*self.leaf.start(), *self.leaf.end()];
+        let sub_leaf = *self.sub_leaf.start();
+
+        for leaf in [start, end] {
+            check_leaf_supported(leaf, sub_leaf)?;
+
+            if (start & 0xF000_0000) != (leaf & 0xF000_0000) {
+                return Err(Error::UnsupportedLeaf { leaf, sub_leaf });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub(crate) fn parse_value(raw_value: &str) -> Result<u32, Error> {
+    /* for like "0x8000_0000" */
+    let value = raw_value.replace('_', "");
+
+    let parsed = if let Some(stripped) = value.strip_prefix("0x") {
+        u32::from_str_radix(stripped, 16)
+    } else {
+        value.parse::<u32>()
+    };
+
+    parsed.map_err(|err| Error::Parse(format!("invalid value \"{raw_value}\": {err}")))
+}
+
+/* "1", "0-2", "0x0..0x3", "0x0..=0x3", the end is inclusive */
+fn parse_range(s: &str) -> Result<RangeInclusive<u32>, Error> {
+    let split = s.split_once("..=")
+        .or_else(|| s.split_once(".."))
+        .or_else(|| s.split_once('-'));
+
+    let (start, end) = match split {
+        Some((start, end)) => (parse_value(start)?, parse_value(end)?),
+        None => {
+            let v = parse_value(s)?;
+            (v, v)
+        },
+    };
+
+    if end < start {
+        return Err(Error::Parse(format!("invalid range \"{s}\": start is greater than end")));
+    }
+
+    Ok(start..=end)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Opt {
+    All,
+    Summary,
+    Raw,
+    Bin,
+    Compat,
+    Debug,
+    Full,
+    DispZero,
+    NoDiff,
+    Width,
+    Dev,
+    Affinity,
+    ProbeSubLeaves,
+    Leaf,
+    SubLeaf,
+    Reg,
+    Save,
+    CodenameDb,
+    Qemu,
+    Libvirt,
+    Prometheus,
+    Hwloc,
+    Markdown,
+    Html,
+    Rust,
+    CHeader,
+    Input,
+    Help,
+}
+
+enum OptValue {
+    None,
+    Required(&'static str),
+    Optional(&'static str),
+}
+
+struct OptSpec {
+    opt: Opt,
+    names: &'static [&'static str],
+    value: OptValue,
+    help: &'static [&'static str],
+}
+
+/* The leading '-' are trimmed before matching, "-all", "--all" and "all" are the same,
+   but an argument without '-' is parsed as a command or a positional argument */
+const OPTS: &[OptSpec] = &[
+    OptSpec {
+        opt: Opt::All,
+        names: &["-a", "-all"],
+        value: OptValue::None,
+        help: &["Display result for all threads."],
+    },
+    OptSpec {
+        opt: Opt::Summary,
+        names: &["-summary"],
+        value: OptValue::None,
+        help: &[
+            "Group all threads into classes by CPUID results, ignoring the APIC IDs,",
+            "and display the differences between the classes and unexpected asymmetry.",
+        ],
+    },
+    OptSpec {
+        opt: Opt::Raw,
+        names: &["-r", "-raw"],
+        value: OptValue::None,
+        help: &["Display raw/hex result."],
+    },
+    OptSpec {
+        opt: Opt::Bin,
+        names: &["-bin"],
+        value: OptValue::None,
+        help: &["Display binary result."],
+    },
+    OptSpec {
+        opt: Opt::Compat,
+        names: &["-c", "-compat"],
+        value: OptValue::None,
+        help: &["Display the same format as `cpuid -r` (cpuid by Todd Allen)"],
+    },
+    OptSpec {
+        opt: Opt::Debug,
+        names: &["-debug"],
+        value: OptValue::None,
+        help: &["Display the Debug format of RawCpuid."],
+    },
+    OptSpec {
+        opt: Opt::Full,
+        names: &["-full"],
+        value: OptValue::None,
+        help: &["Combine \"-disp-zero\" and \"-no-diff\""],
+    },
+    OptSpec {
+        opt: Opt::DispZero,
+        names: &["-disp-zero"],
+        value: OptValue::None,
+        help: &["Display result even if E[ABCD]X are zero."],
+    },
+    OptSpec {
+        opt: Opt::NoDiff,
+        names: &["-no-diff"],
+        value: OptValue::None,
+        help: &["Do not omit diff when all threads execution"],
+    },
+    OptSpec {
+        opt: Opt::Width,
+        names: &["--width"],
+        value: OptValue::Required("<COLUMNS>"),
+        help: &[
+            "Wrap the decoded fields at <COLUMNS> instead of the terminal width.",
+            "The colors are disabled if stdout is not a terminal or NO_COLOR is set.",
+        ],
+    },
+    OptSpec {
+        opt: Opt::Dev,
+        names: &["-dev"],
+        value: OptValue::None,
+        help: &[
+            "Read CPUID of each thread through \"/dev/cpu/<N>/cpuid\" (Linux cpuid driver)",
+            "instead of changing the thread affinity, with \"-all\".",
+            "This also reports the threads outside of the current affinity mask.",
+        ],
+    },
+    OptSpec {
+        opt: Opt::Affinity,
+        names: &["-affinity"],
+        value: OptValue::None,
+        help: &[
+            "Pin a thread to each CPU to execute CPUID, with \"-all\".",
+            "By default, \"-dev\" is selected if the affinity can not be changed",
+            "or the cpuid driver provides more threads than the affinity mask.",
+        ],
+    },
+    OptSpec {
+        opt: Opt::ProbeSubLeaves,
+        names: &["-probe-subleaves"],
+        value: OptValue::None,
+        help: &[
+            "Scan sub-leaves 0x0..=0xFF of every leaf to discover undocumented sub-leaves,",
+            "in addition to the sub-leaf enumeration rules of each leaf.",
+        ],
+    },
+    OptSpec {
+        opt: Opt::Leaf,
+        names: &["--l", "--leaf"],
+        value: OptValue::Required("<LEAF>"),
+        help: &[
+            "Display result only for the specified leaves, can be used multiple times.",
+            "e.g. --leaf 1, --leaf 0x8000_0008, --leaf 0x7:0-2",
+        ],
+    },
+    OptSpec {
+        opt: Opt::SubLeaf,
+        names: &["--sub_leaf", "--subleaf", "--sub-leaf", "--sub"],
+        value: OptValue::Required("<u32>"),
+        help: &["Set Sub_Leaf/InputECX <u32> of the preceding \"--leaf\"."],
+    },
+    OptSpec {
+        opt: Opt::Reg,
+        names: &["--reg"],
+        value: OptValue::Required("<eax|ebx|ecx|edx>"),
+        help: &["Explain only the specified register."],
+    },
+    OptSpec {
+        opt: Opt::Save,
+        names: &["--s", "--save"],
+        value: OptValue::Optional("<path/filename>"),
+        help: &[
+            "Save dump result to text file.",
+            "If there is no path/filename argument, will be used \"./<processor_name>\".",
+        ],
+    },
+    OptSpec {
+        opt: Opt::CodenameDb,
+        names: &["--codename-db"],
+        value: OptValue::Required("<FILE>"),
+        help: &[
+            "Load codename entries from a TOML or JSON (\"*.json\") file,",
+            "which add or override the built-in codename database.",
+            "The file can also be set by the CPUID_DUMP_CODENAME_DB environment variable.",
+        ],
+    },
+    OptSpec {
+        opt: Opt::Qemu,
+        names: &["-qemu"],
+        value: OptValue::None,
+        help: &[
+            "Export the QEMU \"-cpu\" option reproducing the features of the processor,",
+            "based on the closest named CPU model. Written to stdout if there is no FILE.",
+        ],
+    },
+    OptSpec {
+        opt: Opt::Libvirt,
+        names: &["-libvirt"],
+        value: OptValue::None,
+        help: &["Export the same as \"-qemu\" as <cpu mode='custom'> of the libvirt domain XML."],
+    },
+    OptSpec {
+        opt: Opt::Prometheus,
+        names: &["-prometheus"],
+        value: OptValue::None,
+        help: &[
+            "Export the feature flags, x86-64 level, cache sizes and processor information",
+            "as Prometheus metrics for the node_exporter textfile collector.",
+        ],
+    },
+    OptSpec {
+        opt: Opt::Hwloc,
+        names: &["-hwloc"],
+        value: OptValue::None,
+        help: &[
+            "Export the topology of all threads (Package, Die, Caches, Core, PU) as hwloc v2 XML,",
+            "e.g. \"lstopo --input <FILE>\", \"HWLOC_XMLFILE=<FILE>\".",
+        ],
+    },
+    OptSpec {
+        opt: Opt::Markdown,
+        names: &["-markdown"],
+        value: OptValue::None,
+        help: &[
+            "Export a report for the hardware issues: processor summary, ISA level, features by category,",
+            "caches/TLBs, topology of all threads and the raw CPUID, as Markdown.",
+        ],
+    },
+    OptSpec {
+        opt: Opt::Html,
+        names: &["-html"],
+        value: OptValue::None,
+        help: &["Export the same report as \"-markdown\" as HTML."],
+    },
+    OptSpec {
+        opt: Opt::Rust,
+        names: &["-rust"],
+        value: OptValue::None,
+        help: &[
+            "Export the features, cache sizes, XSAVE size and address widths of the processor",
+            "as Rust constants, e.g. \"pub const HAS_AVX2: bool = true;\".",
+        ],
+    },
+    OptSpec {
+        opt: Opt::CHeader,
+        names: &["-c-header"],
+        value: OptValue::None,
+        help: &["Export the same as \"-rust\" as a C header, e.g. \"#define CPU_L1D_LINE_SIZE 64\"."],
+    },
+    OptSpec {
+        opt: Opt::Input,
+        names: &["--input"],
+        value: OptValue::Required("<FILE>"),
+        help: &[
+            "Read CPUID results of the first thread (all threads with \"-hwloc\", \"-markdown\" and \"-html\")",
+            "from a dump file instead of the processor, with the export formats other than text",
+            "and \"binary-check\".",
+        ],
+    },
+    OptSpec {
+        opt: Opt::Help,
+        names: &["-h", "-help"],
+        value: OptValue::None,
+        help: &["Display this message."],
+    },
+];
+
+struct CmdSpec {
+    cmd: Command,
+    name: &'static str,
+    args: &'static str,
+    about: &'static str,
+    opts: &'static [Opt],
+}
+
+/* output formats, only one of them can be used */
+const FMT_OPTS: &[Opt] = &[
+    Opt::Raw, Opt::Bin, Opt::Compat, Opt::Debug, Opt::Qemu, Opt::Libvirt, Opt::Prometheus, Opt::Hwloc,
+    Opt::Markdown, Opt::Html, Opt::Rust, Opt::CHeader,
+];
+
+const CMDS: &[CmdSpec] = &[
+    CmdSpec {
+        cmd: Command::Dump,
+        name: "dump",
+        args: "",
+        about: "Dump CPUID results (default)",
+        opts: &[
+            Opt::All, Opt::Summary, Opt::Raw, Opt::Bin, Opt::Compat, Opt::Debug, Opt::Full,
+            Opt::DispZero, Opt::NoDiff, Opt::Width, Opt::Dev, Opt::Affinity, Opt::ProbeSubLeaves,
+            Opt::Save, Opt::CodenameDb, Opt::Help,
+        ],
+    },
+    CmdSpec {
+        cmd: Command::Leaf,
+        name: "leaf",
+        args: "<LEAF>..",
+        about: "Dump CPUID results only for the specified leaves",
+        opts: &[
+            Opt::All, Opt::Raw, Opt::Bin, Opt::Compat, Opt::Debug, Opt::NoDiff, Opt::Width,
+            Opt::Dev, Opt::Affinity, Opt::Leaf, Opt::SubLeaf, Opt::Save, Opt::CodenameDb, Opt::Help,
+        ],
+    },
+    CmdSpec {
+        cmd: Command::Check,
+        name: "check",
+        args: "[FEATURE]..",
+        about: "Display x86-64 micro-architecture level and check the features are supported",
+        opts: &[Opt::Help],
+    },
+    CmdSpec {
+        cmd: Command::Diff,
+        name: "diff",
+        args: "<FILE> [FILE]",
+        about: "Compare dump files, or a dump file with the current processor",
+        opts: &[Opt::DispZero, Opt::Help],
+    },
+    CmdSpec {
+        cmd: Command::Topo,
+        name: "topo",
+        args: "",
+        about: "Display topology IDs of all threads",
+        opts: &[Opt::Dev, Opt::Affinity, Opt::Help],
+    },
+    CmdSpec {
+        cmd: Command::Load,
+        name: "load",
+        args: "<FILE>",
+        about: "Load a dump file and display it",
+        opts: &[
+            Opt::Summary, Opt::Raw, Opt::Bin, Opt::Compat, Opt::Debug, Opt::Width, Opt::CodenameDb,
+            Opt::Help,
+        ],
+    },
+    CmdSpec {
+        cmd: Command::Export,
+        name: "export",
+        args: "[FILE]",
+        about: "Save dump result of all threads to text file",
+        opts: &[
+            Opt::Raw, Opt::Bin, Opt::Compat, Opt::Debug, Opt::Full, Opt::DispZero, Opt::NoDiff,
+            Opt::Dev, Opt::Affinity, Opt::ProbeSubLeaves, Opt::Qemu, Opt::Libvirt, Opt::Prometheus, Opt::Hwloc,
+            Opt::Markdown, Opt::Html, Opt::Rust, Opt::CHeader, Opt::Input, Opt::CodenameDb, Opt::Help,
+        ],
+    },
+    CmdSpec {
+        cmd: Command::Explain,
+        name: "explain",
+        args: "<LEAF>..",
+        about: "Explain each field and bit of the registers",
+        opts: &[Opt::Leaf, Opt::SubLeaf, Opt::Reg, Opt::Help],
+    },
+    CmdSpec {
+        cmd: Command::Security,
+        name: "security",
+        args: "[FILE]",
+        about: "Display mitigation capabilities by vulnerability, with the Linux status",
+        opts: &[Opt::Help],
+    },
+    CmdSpec {
+        cmd: Command::Baseline,
+        name: "baseline",
+        args: "<FILE>..",
+        about: "Display the features common to dump files and the hosts limiting them",
+        opts: &[Opt::Help],
+    },
+    CmdSpec {
+        cmd: Command::BinaryCheck,
+        name: "binary-check",
+        args: "<ELF>",
+        about: "Check the x86 ISA needed by an ELF binary (GNU property note) is supported",
+        opts: &[Opt::Input, Opt::Help],
+    },
+    CmdSpec {
+        cmd: Command::Watch,
+        name: "watch",
+        args: "[SECONDS]",
+        about: "Re-sample all threads every SECONDS (default: 1) and display the changes",
+        opts: &[Opt::Dev, Opt::Affinity, Opt::Leaf, Opt::SubLeaf, Opt::Help],
+    },
+    CmdSpec {
+        cmd: Command::Tui,
+        name: "tui",
+        args: "[FILE]",
+        about: "Browse the leaves of all threads interactively, from the processor or a dump file",
+        opts: &[Opt::DispZero, Opt::Dev, Opt::Affinity, Opt::ProbeSubLeaves, Opt::CodenameDb, Opt::Help],
+    },
+];
+
+impl Command {
+    fn spec(&self) -> &'static CmdSpec {
+        CMDS.iter().find(|spec| spec.cmd == *self).unwrap()
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        CMDS.iter().find(|spec| spec.name == name).map(|spec| spec.cmd)
+    }
+}
+
+fn find_opt(arg: &str) -> Option<&'static OptSpec> {
+    let arg = arg.trim_start_matches('-');
+
+    OPTS.iter().find(|spec| spec.names.iter().any(|name| name.trim_start_matches('-') == arg))
+}
+
+fn opt_spec(opt: Opt) -> &'static OptSpec {
+    OPTS.iter().find(|spec| spec.opt == opt).unwrap()
+}
+
+fn opts_msg(opts: &[Opt]) -> String {
+    let mut flags = String::new();
+    let mut options = String::new();
+
+    for spec in opts.iter().map(|opt| opt_spec(*opt)) {
+        let (buf, value) = match spec.value {
+            OptValue::None => (&mut flags, "".to_string()),
+            OptValue::Required(v) => (&mut options, format!(" {v}")),
+            OptValue::Optional(v) => (&mut options, format!(" [{v}]")),
+        };
+        let names: Vec<String> = spec.names.iter().map(|name| format!("{name}{value}")).collect();
+
+        buf.push_str(&format!("    {}\n", names.join(", ")));
+
+        for ln in spec.help {
+            buf.push_str(&format!("        {ln}\n"));
+        }
+    }
+
+    let mut msg = String::new();
+
+    if !flags.is_empty() {
+        msg.push_str(&format!("\nFLAGS:\n{flags}"));
+    }
+    if !options.is_empty() {
+        msg.push_str(&format!("\nOPTIONS:\n{options}"));
+    }
+
+    msg
+}
+
+const HELP_HEAD: &str = concat!(
+    "CPUID Dump ", env!("CARGO_PKG_VERSION"), "\n",
+    "https://github.com/Umio-Yasuno/cpuid_dump_rs\n",
+);
+
+const HELP_LEAF: &str = "\
+LEAF:
+    <LEAF>[:<SUB_LEAF>], each value is <u32> or an inclusive range of <u32>.
+    e.g. 1, 0x8000_0008, 0x7:0-2, 0x8000_0000..0x8000_0008, 0xD:0..=0x3
+";
+
+const HELP_EXIT: &str = "\
+EXIT STATUS:
+    0  Success
+    1  \"check\", \"binary-check\": one or more features are not supported
+    2  Invalid command line argument or dump file
+    3  Failed to change the thread affinity (sched_setaffinity, SetThreadAffinityMask)
+    4  The specified leaf is not supported by the processor
+    5  I/O error (load/save file, \"/dev/cpu/<N>/cpuid\")";
+
+pub(crate) fn help_msg() -> String {
+    let mut cmds = String::new();
+
+    for spec in CMDS {
+        let usage = format!("{} {}", spec.name, spec.args);
+        cmds.push_str(&format!("    {usage:<24}{}\n", spec.about));
+    }
+
+    let all_opts: Vec<Opt> = OPTS.iter().map(|spec| spec.opt).collect();
+
+    format!("\
+        {HELP_HEAD}\n\
+        USAGE:\n    \
+            cargo run -- [COMMAND] [options ..] or <cpuid_dump> [COMMAND] [options ..]\n    \
+            Run \"<cpuid_dump> <COMMAND> -help\" for the options of each command.\n\
+        \n\
+        COMMANDS:\n\
+        {cmds}\
+        {}\n\
+        {HELP_LEAF}\n\
+        {HELP_EXIT}",
+        opts_msg(&all_opts),
+    )
+}
+
+fn cmd_help_msg(cmd: Command) -> String {
+    let spec = cmd.spec();
+    let leaf = if spec.opts.contains(&Opt::Leaf) { format!("\n{HELP_LEAF}") } else { "".to_string() };
+
+    format!("\
+        {HELP_HEAD}\n\
+        {}\n\
+        \n\
+        USAGE:\n    \
+            <cpuid_dump> {} [options ..] {}\n\
+        {}\
+        {leaf}",
+        spec.about,
+        spec.name,
+        spec.args,
+        opts_msg(spec.opts),
+    )
+}
+
+impl MainOpt {
+    pub(crate) fn main_parse() -> Result<Self, Error> {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        let mut opt = Self::parse_args(&args)?;
+
+        opt.color = use_color();
+
+        Ok(opt)
+    }
+
+    fn parse_args(args: &[String]) -> Result<Self, Error> {
+        let mut opt = MainOpt::default();
+        let mut cmd: Option<Command> = None;
+        let mut given: Vec<(Opt, &str)> = Vec::with_capacity(args.len());
+        let mut fmt_opt: Option<&str> = None;
+        let mut backend_opt: Option<&str> = None;
+        let mut args = args.iter().peekable();
+
+        while let Some(arg) = args.next() {
+            if !arg.starts_with('-') {
+                match Command::from_name(arg) {
+                    Some(c) if cmd.is_none() && opt.args.is_empty() => cmd = Some(c),
+                    _ => opt.args.push(arg.to_string()),
+                }
+                continue;
+            }
+
+            let spec = find_opt(arg)
+                .ok_or_else(|| Error::Parse(format!("unknown option: \"{arg}\"")))?;
+
+            given.push((spec.opt, arg));
+
+            let value = match spec.value {
+                OptValue::None => None,
+                OptValue::Required(_) => Some(args.next().ok_or_else(|| {
+                    Error::Parse(format!("missing argument {} to \"{arg}\"", spec.names[0]))
+                })?),
+                OptValue::Optional(_) => args.next_if(|v| !v.starts_with('-')),
+            };
+
+            if FMT_OPTS.contains(&spec.opt) {
+                if let Some(prev) = fmt_opt {
+                    return Err(Error::Parse(format!("\"{prev}\" and \"{arg}\" cannot be used together")));
+                }
+                fmt_opt = Some(arg);
+            }
+
+            match spec.opt {
+                Opt::All => opt.dump_all = true,
+                Opt::Summary => opt.summary = true,
+                Opt::Raw => opt.fmt = DumpFormat::Raw,
+                Opt::Bin => opt.fmt = DumpFormat::Binary,
+                Opt::Compat => {
+                    opt.fmt = DumpFormat::CompatCpuid;
+                    opt.skip_zero = false;
+                    opt.diff = false;
+                },
+                Opt::Debug => opt.fmt = DumpFormat::Debug,
+                Opt::Full => {
+                    opt.skip_zero = false;
+                    opt.diff = false;
+                },
+                Opt::DispZero => opt.skip_zero = false,
+                Opt::NoDiff => opt.diff = false,
+                Opt::Width => {
+                    let v = value.unwrap();
+
+                    opt.width = match parse_value(v)? {
+                        0 => return Err(Error::Parse(format!("invalid width: \"{v}\""))),
+                        width => Some(width as usize),
+                    };
+                },
+                Opt::Dev | Opt::Affinity => {
+                    if let Some(prev) = backend_opt {
+                        return Err(Error::Parse(format!("\"{prev}\" and \"{arg}\" cannot be used together")));
+                    }
+                    backend_opt = Some(arg);
+                    opt.backend = if spec.opt == Opt::Dev {
+                        CpuidBackend::DevCpuid
+                    } else {
+                        CpuidBackend::Affinity
+                    };
+                },
+                Opt::ProbeSubLeaves => opt.probe_sub_leaves = true,
+                Opt::Leaf => opt.leaves.push(LeafSpec::from_str(value.unwrap())?),
+                Opt::SubLeaf => {
+                    let sub_leaf = parse_range(value.unwrap())?;
+                    let last = opt.leaves.last_mut().ok_or_else(|| {
+                        Error::Parse(format!("\"{arg}\" requires \"--leaf <LEAF>\" before it"))
+                    })?;
+                    last.sub_leaf = sub_leaf;
+                },
+                Opt::Reg => {
+                    let v = value.unwrap();
+
+                    opt.reg = Some(match v.to_ascii_lowercase().as_str() {
+                        "eax" => CpuidReg::EAX,
+                        "ebx" => CpuidReg::EBX,
+                        "ecx" => CpuidReg::ECX,
+                        "edx" => CpuidReg::EDX,
+                        _ => return Err(Error::Parse(format!("invalid register: \"{v}\""))),
+                    });
+                },
+                Opt::Save => {
+                    use std::path::Path;
+                    let path = default_name();
+
+                    opt.save_path = Some(match value {
+                        Some(v) if Path::new(v).is_dir() => format!("{v}{path}"),
+                        Some(v) => v.to_string(),
+                        None => path,
+                    });
+                },
+                Opt::CodenameDb => opt.codename_db = Some(value.unwrap().to_string()),
+                Opt::Qemu => opt.export_fmt = ExportFormat::Qemu,
+                Opt::Libvirt => opt.export_fmt = ExportFormat::Libvirt,
+                Opt::Prometheus => opt.export_fmt = ExportFormat::Prometheus,
+                Opt::Hwloc => opt.export_fmt = ExportFormat::Hwloc,
+                Opt::Markdown => opt.export_fmt = ExportFormat::Markdown,
+                Opt::Html => opt.export_fmt = ExportFormat::Html,
+                Opt::Rust => opt.export_fmt = ExportFormat::Rust,
+                Opt::CHeader => opt.export_fmt = ExportFormat::CHeader,
+                Opt::Input => opt.input = Some(value.unwrap().to_string()),
+                Opt::Help => opt.help = true,
+            }
+        }
+
+        /* compatible with "cpuid_dump --leaf <u32>" */
+        opt.cmd = match cmd {
+            Some(cmd) => cmd,
+            None if !opt.leaves.is_empty() => Command::Leaf,
+            None => Command::Dump,
+        };
+
+        let spec = opt.cmd.spec();
+
+        for (o, arg) in &given {
+            if !spec.opts.contains(o) {
+                return Err(Error::Parse(format!("\"{arg}\" is not available for \"{}\"", spec.name)));
+            }
+        }
+
+        if opt.help {
+            return Ok(opt);
+        }
+
+        opt.check_args()?;
+
+        Ok(opt)
+    }
+
+    fn check_args(&mut self) -> Result<(), Error> {
+        let name = self.cmd.spec().name;
+        let (min, max) = match self.cmd {
+            Command::Dump |
+            Command::Topo => (0, 0),
+            Command::Leaf |
+            Command::Explain => (0, usize::MAX),
+            Command::Check => (0, usize::MAX),
+            Command::Diff => (1, 2),
+            Command::Load |
+            Command::BinaryCheck => (1, 1),
+            Command::Baseline => (1, usize::MAX),
+            Command::Export |
+            Command::Security |
+            Command::Watch |
+            Command::Tui => (0, 1),
+        };
+
+        if let Command::Leaf | Command::Explain = self.cmd {
+            for arg in &self.args {
+                self.leaves.push(LeafSpec::from_str(arg)?);
+            }
+            self.args.clear();
+
+            if self.leaves.is_empty() {
+                return Err(Error::Parse(format!("\"{name}\" requires <LEAF>")));
+            }
+
+            /* display the specified leaves even if E[ABCD]X are zero */
+            self.skip_zero = false;
+        }
+
+        if self.args.len() < min {
+            return Err(Error::Parse(format!("\"{name}\" requires {}", self.cmd.spec().args)));
+        }
+        if max < self.args.len() {
+            return Err(Error::Parse(format!("unexpected argument: \"{}\"", self.args[max])));
+        }
+
+        if self.cmd == Command::Watch {
+            if let Some(s) = self.args.first() {
+                self.interval = s.parse::<f64>()
+                    .ok()
+                    .filter(|sec| 0.0 < *sec)
+                    .and_then(|sec| std::time::Duration::try_from_secs_f64(sec).ok())
+                    .ok_or_else(|| Error::Parse(format!("invalid interval: \"{s}\"")))?;
+            }
+        }
+
+        /* same as `cpuid -r`, which displays all threads */
+        if self.cmd == Command::Dump && self.fmt == DumpFormat::CompatCpuid {
+            self.dump_all = true;
+        }
+
+        if self.cmd == Command::Export {
+            if self.export_fmt != ExportFormat::Text {
+                self.save_path = self.args.pop();
+            } else if self.input.is_some() {
+                return Err(Error::Parse("\"--input\" requires an export format option".to_string()));
+            } else {
+                self.save_path = Some(self.args.pop().unwrap_or_else(default_name));
+                self.dump_all = true;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn help(&self) -> String {
+        if self.cmd == Command::Dump {
+            help_msg()
+        } else {
+            cmd_help_msg(self.cmd)
+        }
+    }
+}
+
+#[test]
+fn test_parse_args() {
+    let parse = |args: &[&str]| {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+
+        MainOpt::parse_args(&args)
+    };
+
+    assert_eq!(parse(&["watch"]).unwrap().interval, std::time::Duration::from_secs(1));
+    assert_eq!(parse(&["watch", "0.5"]).unwrap().interval, std::time::Duration::from_millis(500));
+
+    for sec in ["0", "-1", "NaN", "inf", "1e30", "one"] {
+        assert!(matches!(parse(&["watch", sec]), Err(Error::Parse(_))), "{}", sec);
+    }
+
+    assert!(matches!(parse(&["--width", "0"]), Err(Error::Parse(_))));
+}
+
+#[test]
+fn test_leaf_spec() {
+    let spec = |s: &str| LeafSpec::from_str(s).map(|spec| (spec.leaf, spec.sub_leaf));
+
+    assert_eq!(spec("0x1").unwrap(), (0x1..=0x1, 0x0..=0x0));
+    assert_eq!(spec("13").unwrap(), (0xD..=0xD, 0x0..=0x0));
+    assert_eq!(spec("0x7:0-2").unwrap(), (0x7..=0x7, 0x0..=0x2));
+    assert_eq!(spec("0xD:0x1..0x2").unwrap(), (0xD..=0xD, 0x1..=0x2));
+    assert_eq!(spec("0x8000_0000..0x8000_0008").unwrap(), (0x8000_0000..=0x8000_0008, 0x0..=0x0));
+    assert_eq!(spec("0x8000_0000..=0x8000_0008").unwrap(), (0x8000_0000..=0x8000_0008, 0x0..=0x0));
+    assert_eq!(spec("0x8000_001D:0-3").unwrap(), (0x8000_001D..=0x8000_001D, 0x0..=0x3));
+
+    /* reversed, malformed, too large */
+    for s in ["0x8..0x1", "0x7:2-0", "0xZZ", "0x1:", "0x1-", "", "0x0..0x1000", "0x4:0..0xFFFF_FFFF"] {
+        assert!(matches!(spec(s), Err(Error::Parse(_))), "{}", s);
+    }
+
+    let spec = LeafSpec::from_str("0x7:0-1").unwrap();
+
+    assert_eq!(spec.to_pool(), vec![(0x7, 0x0), (0x7, 0x1)]);
+}
+
+#[test]
+fn test_opt_alias() {
+    let parse = |args: &[&str]| {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+
+        MainOpt::parse_args(&args)
+    };
+
+    for arg in ["-a", "--a", "-all", "--all", "all"] {
+        assert_eq!(find_opt(arg).map(|spec| spec.opt), Some(Opt::All), "{}", arg);
+    }
+    for arg in ["-a", "-all", "--all"] {
+        assert!(parse(&[arg]).unwrap().dump_all, "{}", arg);
+    }
+    /* "all" without '-' is a positional argument, "dump" takes none */
+    assert!(matches!(parse(&["all"]), Err(Error::Parse(_))));
+
+    /* single-dash aliases of the options with a value */
+    for args in [["--leaf", "0x1"], ["-leaf", "0x1"], ["-l", "0x1"]] {
+        let opt = parse(&args).unwrap();
+
+        assert_eq!(opt.cmd, Command::Leaf, "{:?}", args);
+        assert_eq!(opt.leaves, vec![LeafSpec::from_str("0x1").unwrap()], "{:?}", args);
+    }
+
+    /* multiple leaves, "--sub_leaf" applies to the preceding "--leaf" */
+    let opt = parse(&["leaf", "0x1", "0x7:0-1", "--leaf", "0xD", "-sub-leaf", "0x1"]).unwrap();
+
+    assert_eq!(opt.leaves.iter().map(|spec| spec.to_pool().len()).sum::<usize>(), 4);
+    assert!(matches!(parse(&["--sub_leaf", "0x1"]), Err(Error::Parse(_))));
+    assert!(matches!(parse(&["--unknown"]), Err(Error::Parse(_))));
+}
+
+#[test]
+fn test_exclusive_opts() {
+    let parse = |args: &[&str]| {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+
+        MainOpt::parse_args(&args)
+    };
+    let err_msg = |args: &[&str]| match parse(args) {
+        Err(Error::Parse(msg)) => msg,
+        other => panic!("{:?}: {:?}", args, other.map(|opt| opt.cmd)),
+    };
+
+    assert_eq!(err_msg(&["-raw", "-bin"]), "\"-raw\" and \"-bin\" cannot be used together");
+    assert_eq!(err_msg(&["-dev", "--affinity"]), "\"-dev\" and \"--affinity\" cannot be used together");
+    assert_eq!(err_msg(&["export", "-qemu", "-libvirt"]), "\"-qemu\" and \"-libvirt\" cannot be used together");
+    assert_eq!(err_msg(&["topo", "-summary"]), "\"-summary\" is not available for \"topo\"");
+    assert_eq!(err_msg(&["leaf"]), "\"leaf\" requires <LEAF>");
+
+    assert_eq!(parse(&["-dev"]).unwrap().backend, CpuidBackend::DevCpuid);
+    assert_eq!(parse(&["-raw"]).unwrap().fmt, DumpFormat::Raw);
+}
@@ -0,0 +1,137 @@
+use crate::*;
+use libcpuid_dump::{AddressSize, CacheType, FamModStep, Info01h, MicroArchLevel};
+
+enum Value {
+    Bool(bool),
+    Int(u32),
+    Str(String),
+}
+
+/* "SSE4.1" -> "SSE4_1", "LAHF/SAHF" -> "LAHF_SAHF", "SEV-ES" -> "SEV_ES" */
+fn ident(name: &str) -> String {
+    name.chars().map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' }).collect()
+}
+
+/* the capability profile of the processor, shared by the Rust and C outputs */
+fn profile(cpuid: &dyn Fn(u32, u32) -> CpuidResult) -> Vec<(String, Value)> {
+    let vendor = CpuVendor::from(&cpuid(0x0, 0x0));
+    let fms = FamModStep::from(&cpuid(0x1, 0x0));
+    let info = Info01h::from(&cpuid(0x1, 0x0));
+    let addr = AddressSize::from(&cpuid(0x8000_0008, 0x0));
+    /* XSAVE area size for the features enabled in XCR0, and for all supported features */
+    let xsave = cpuid(0xD, 0x0);
+    let mut consts: Vec<(String, Value)> = vec![
+        ("CPU_VENDOR".to_string(), Value::Str(vendor_string(&cpuid(0x0, 0x0)))),
+        ("CPU_MODEL_NAME".to_string(), Value::Str(model_name(cpuid))),
+        ("CPU_FAMILY".to_string(), Value::Int(fms.syn_fam)),
+        ("CPU_MODEL".to_string(), Value::Int(fms.syn_mod)),
+        ("CPU_STEPPING".to_string(), Value::Int(fms.step)),
+        ("CPU_X86_64_LEVEL".to_string(), Value::Int(MicroArchLevel::check_with(&cpuid) as u32)),
+        ("CPU_CLFLUSH_SIZE".to_string(), Value::Int(info.clflush_size as u32)),
+        ("CPU_PHYS_ADDR_BITS".to_string(), Value::Int(addr.physical as u32)),
+        ("CPU_VIRT_ADDR_BITS".to_string(), Value::Int(addr.virtual_ as u32)),
+        ("CPU_XSAVE_SIZE".to_string(), Value::Int(xsave.ebx)),
+        ("CPU_XSAVE_MAX_SIZE".to_string(), Value::Int(xsave.ecx)),
+    ];
+
+    for cache in cache_props(cpuid, &vendor) {
+        let suffix = match cache.cache_type {
+            CacheType::Data => "D",
+            CacheType::Instruction => "I",
+            CacheType::Unified => "",
+            CacheType::Unknown => continue,
+        };
+        let prefix = format!("CPU_L{}{suffix}", cache.level);
+
+        consts.extend([
+            (format!("{prefix}_SIZE"), Value::Int(cache.size)),
+            (format!("{prefix}_LINE_SIZE"), Value::Int(cache.line_size)),
+            (format!("{prefix}_WAYS"), Value::Int(cache.way)),
+            (format!("{prefix}_SETS"), Value::Int(cache.set)),
+            (format!("{prefix}_SHARED_THREADS"), Value::Int(cache.share_thread)),
+        ]);
+    }
+
+    for (name, has) in feature_flags(cpuid) {
+        let name = format!("HAS_{}", ident(name));
+
+        /* "SSE4.1" and "SSE4_1" are the same constant */
+        match consts.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, Value::Bool(b))) => *b |= has,
+            Some(_) => {},
+            None => consts.push((name, Value::Bool(has))),
+        }
+    }
+
+    consts
+}
+
+/* for `include!` in the build script output, or a module of the crate */
+pub(crate) fn rust_consts(cpuid: &dyn Fn(u32, u32) -> CpuidResult) -> String {
+    let mut out = format!(
+        "// CPU capability profile of \"{}\", generated by cpuid_dump\n\n",
+        model_name(cpuid),
+    );
+
+    for (name, value) in profile(cpuid) {
+        out.push_str(&match value {
+            Value::Bool(b) => format!("pub const {name}: bool = {b};\n"),
+            Value::Int(v) => format!("pub const {name}: u32 = {v};\n"),
+            Value::Str(s) => format!("pub const {name}: &str = {s:?};\n"),
+        });
+    }
+
+    out
+}
+
+/* the features are 0 or 1 for "#if CPU_HAS_AVX2" */
+pub(crate) fn c_header(cpuid: &dyn Fn(u32, u32) -> CpuidResult) -> String {
+    let mut out = format!(
+        "/* CPU capability profile of \"{}\", generated by cpuid_dump */\n\n\
+        #ifndef CPU_PROFILE_H\n\
+        #define CPU_PROFILE_H\n\n",
+        model_name(cpuid).replace("*/", "* /"),
+    );
+
+    for (name, value) in profile(cpuid) {
+        out.push_str(&match value {
+            Value::Bool(b) => format!("#define CPU_{name} {}\n", b as u8),
+            Value::Int(v) => format!("#define {name} {v}\n"),
+            Value::Str(s) => format!("#define {name} \"{}\"\n", s.replace('\\', "\\\\").replace('"', "\\\"")),
+        });
+    }
+
+    out.push_str("\n#endif /* CPU_PROFILE_H */\n");
+
+    out
+}
+
+#[test]
+fn test_codegen() {
+    /* Ryzen 5 5600G */
+    let snapshot = ryzen_5_5600g();
+    let cpuid = |leaf, sub_leaf| snapshot.cpuid(leaf, sub_leaf);
+    let rs = rust_consts(&cpuid);
+
+    assert!(rs.contains("pub const CPU_MODEL_NAME: &str = \"AMD Ryzen 5 5600G with Radeon Graphics\";\n"));
+    assert!(rs.contains("pub const CPU_X86_64_LEVEL: u32 = 3;\n"));
+    assert!(rs.contains("pub const CPU_CLFLUSH_SIZE: u32 = 64;\n"));
+    assert!(rs.contains("pub const CPU_PHYS_ADDR_BITS: u32 = 48;\n"));
+    assert!(rs.contains("pub const CPU_XSAVE_SIZE: u32 = 2440;\n"));
+    assert!(rs.contains("pub const CPU_L1D_LINE_SIZE: u32 = 64;\n"));
+    assert!(rs.contains("pub const CPU_L3_SIZE: u32 = 16777216;\n"));
+    assert!(rs.contains("pub const HAS_AVX2: bool = true;\n"));
+    assert!(rs.contains("pub const HAS_AVX512F: bool = false;\n"));
+    assert!(rs.contains("pub const HAS_SSE4_1: bool = true;\n"));
+    assert!(rs.contains("pub const HAS_LAHF_SAHF: bool = true;\n"));
+
+    let h = c_header(&cpuid);
+
+    assert!(h.contains("\n#ifndef CPU_PROFILE_H\n#define CPU_PROFILE_H\n"));
+    assert!(h.contains("#define CPU_VENDOR \"AuthenticAMD\"\n"));
+    assert!(h.contains("#define CPU_L1D_LINE_SIZE 64\n"));
+    assert!(h.contains("#define CPU_L2_WAYS 8\n"));
+    assert!(h.contains("#define CPU_HAS_AVX2 1\n"));
+    assert!(h.contains("#define CPU_HAS_AVX512F 0\n"));
+    assert!(h.ends_with("#endif /* CPU_PROFILE_H */\n"));
+}
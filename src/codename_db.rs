@@ -0,0 +1,18 @@
+use crate::*;
+use libcpuid_dump::CodenameDb;
+
+pub(crate) const CODENAME_DB_ENV: &str = "CPUID_DUMP_CODENAME_DB";
+
+/* The layout is the same as "lib/codename/codename_db.toml", JSON if the file name ends with ".json" */
+pub(crate) fn load_codename_db(path: &str) -> Result<CodenameDb, Error> {
+    let s = std::fs::read_to_string(path).map_err(|err| {
+        Error::Io(io::Error::new(err.kind(), format!("\"{path}\": {err}")))
+    })?;
+    let db = if path.ends_with(".json") {
+        serde_json::from_str(&s).map_err(|err| err.to_string())
+    } else {
+        toml::from_str(&s).map_err(|err| err.to_string())
+    };
+
+    db.map_err(|err| Error::Parse(format!("\"{path}\": {}", err.trim_end())))
+}
@@ -0,0 +1,169 @@
+use crate::*;
+
+/* Multi-bit field: (Low bit, High bit, Field name, Meaning) */
+type Field = (u32, u32, &'static str, String);
+
+fn bits(reg: u32, lo: u32, hi: u32) -> u32 {
+    let len = hi - lo + 1;
+
+    if len == 32 { reg } else { (reg >> lo) & ((1 << len) - 1) }
+}
+
+fn vendor_str(reg: u32) -> String {
+    let bytes: Vec<u8> = reg.to_le_bytes().iter().map(|&b| {
+        if b.is_ascii_graphic() || b == b' ' { b } else { b'.' }
+    }).collect();
+
+    format!("\"{}\"", String::from_utf8_lossy(&bytes))
+}
+
+fn multi_bit_fields(leaf: u32, sub_leaf: u32, reg: CpuidReg, cpuid: &CpuidResult) -> Vec<Field> {
+    use libcpuid_dump::{AddressSize, FamModStep, Info01h};
+
+    match (leaf, sub_leaf, reg) {
+        (0x0, _, CpuidReg::EAX) => vec![
+            (0, 31, "LFuncStd", format!("Largest standard function: {:#X}", cpuid.eax)),
+        ],
+        (0x0, _, _) => vec![
+            (0, 31, "Vendor", vendor_str(reg.get(cpuid))),
+        ],
+        (0x1, _, CpuidReg::EAX) => {
+            let fms = FamModStep::from(cpuid);
+            let proc_type = match bits(cpuid.eax, 12, 13) {
+                0b00 => "Original OEM processor",
+                0b01 => "OverDrive processor",
+                0b10 => "Dual processor",
+                _ => "Reserved",
+            };
+
+            /* the synthesized Family/Model are noted once, on the Ext* rows */
+            vec![
+                (0, 3, "Stepping", format!("{:#X}", fms.step)),
+                (4, 7, "BaseModel", "".to_string()),
+                (8, 11, "BaseFamily", "".to_string()),
+                (12, 13, "ProcessorType", proc_type.to_string()),
+                (16, 19, "ExtModel", format!("Model: {:#X} (synthesized with BaseModel)", fms.syn_mod)),
+                (20, 27, "ExtFamily", format!("Family: {:#X} (synthesized with BaseFamily)", fms.syn_fam)),
+            ]
+        },
+        (0x1, _, CpuidReg::EBX) => {
+            let info = Info01h::from(cpuid);
+
+            vec![
+                (0, 7, "BrandId", format!("{}", info.brand_id)),
+                (8, 15, "CLFlush", format!("{} B", info.clflush_size)),
+                (16, 23, "LogicalProcessorCount", format!("Max APIC ID: {}", info.max_apic_id)),
+                (24, 31, "LocalApicId", format!("{}", info.local_apic_id)),
+            ]
+        },
+        (0x7, 0x0, CpuidReg::EAX) => vec![
+            (0, 31, "StructExtFeatIdMax", format!("Largest sub-leaf: {:#X}", cpuid.eax)),
+        ],
+        (0x8000_0000, _, CpuidReg::EAX) => vec![
+            (0, 31, "LFuncExt", format!("Largest extended function: {:#X}", cpuid.eax)),
+        ],
+        (0x8000_0008, _, CpuidReg::EAX) => {
+            let size = AddressSize::from(cpuid);
+            let guest = bits(cpuid.eax, 16, 23);
+
+            vec![
+                (0, 7, "PhysAddrSize", format!("{}-bit", size.physical)),
+                (8, 15, "LinAddrSize", format!("{}-bit", size.virtual_)),
+                (16, 23, "GuestPhysAddrSize", if guest == 0 {
+                    "same as PhysAddrSize".to_string()
+                } else {
+                    format!("{guest}-bit")
+                }),
+            ]
+        },
+        _ => Vec::new(),
+    }
+}
+
+fn flag_fields(leaf: u32, sub_leaf: u32, reg: CpuidReg, cpuid: &CpuidResult) -> Vec<Field> {
+    let val = reg.get(cpuid);
+    let mut fields = Vec::new();
+
+    for (_, _, _, ftr_str) in FTR_TABLE.iter().filter(|(l, s, r, _)| (*l, *s, *r) == (leaf, sub_leaf, reg)) {
+        for (bit, ftr) in ftr_str.iter().enumerate() {
+            if ftr.is_empty() {
+                continue;
+            }
+
+            let bit = bit as u32;
+            let meaning = if (val >> bit) & 1 == 1 { "supported" } else { "-" };
+
+            fields.push((bit, bit, *ftr, meaning.to_string()));
+        }
+    }
+
+    fields
+}
+
+/* Describe every known field of the register, and the reserved/unknown bits that are set */
+pub(crate) fn explain_reg(leaf: u32, sub_leaf: u32, reg: CpuidReg, cpuid: &CpuidResult) -> String {
+    let val = reg.get(cpuid);
+    let mut fields = multi_bit_fields(leaf, sub_leaf, reg, cpuid);
+    fields.extend(flag_fields(leaf, sub_leaf, reg, cpuid));
+
+    let mut known = 0u64;
+
+    for (lo, hi, _, _) in &fields {
+        known |= ((1u64 << (hi - lo + 1)) - 1) << lo;
+    }
+
+    for bit in 0..32 {
+        if (known >> bit) & 1 == 0 && (val >> bit) & 1 == 1 {
+            fields.push((bit, bit, "* reserved/unknown", "bit is set".to_string()));
+        }
+    }
+
+    fields.sort_by_key(|(lo, _, _, _)| *lo);
+
+    let mut s = format!(
+        "Leaf: {leaf:#010X}, Sub-leaf: {sub_leaf:#X}, {reg:?}: {val:#010X}\n  \
+        [Bits]  [Value]     [Field]                   [Meaning]\n"
+    );
+
+    if fields.is_empty() {
+        s.push_str("  No known fields, and no bits are set.\n");
+    }
+
+    for (lo, hi, name, meaning) in fields {
+        let range = if lo == hi { format!("{lo:02}") } else { format!("{hi:02}:{lo:02}") };
+        let value = bits(val, lo, hi);
+
+        s.push_str(&format!("  {range:<7} {value:<#11X} {name:<25} {meaning}\n"));
+    }
+
+    s
+}
+
+#[test]
+fn test_explain_reg() {
+    /* Ryzen 5 5600G, CPUID[Leaf=0x8000_0008] */
+    let cpuid = CpuidResult { eax: 0x00003030, ebx: 0x111EF657, ecx: 0x0000500B, edx: 0x00000000 };
+    let s = explain_reg(0x8000_0008, 0x0, CpuidReg::EAX, &cpuid);
+
+    assert!(s.contains("PhysAddrSize              48-bit"));
+    assert!(s.contains("LinAddrSize               48-bit"));
+
+    /* bit 31 is not defined in the table */
+    let cpuid = CpuidResult { eax: 0x0, ebx: 0x0, ecx: 0x8000_0001, edx: 0x0 };
+    let s = explain_reg(0x1, 0x0, CpuidReg::ECX, &cpuid);
+
+    assert!(s.contains("00      0x1         SSE3                      supported"));
+    assert!(s.contains("31      0x1         * reserved/unknown        bit is set"));
+
+    /* Ryzen 5 5600G, CPUID[Leaf=0x1], Family 0x19, Model 0x50 */
+    let cpuid = CpuidResult { eax: 0x00A50F00, ebx: 0x000C0800, ecx: 0x7EF8320B, edx: 0x178BFBFF };
+    let s = explain_reg(0x1, 0x0, CpuidReg::EAX, &cpuid);
+
+    assert!(s.contains("07:04   0x0         BaseModel                 \n"));
+    assert!(s.contains("11:08   0xF         BaseFamily                \n"));
+    assert!(s.contains("13:12   0x0         ProcessorType             Original OEM processor\n"));
+    assert!(s.contains("19:16   0x5         ExtModel                  Model: 0x50 (synthesized with BaseModel)\n"));
+    assert!(s.contains("27:20   0xA         ExtFamily                 Family: 0x19 (synthesized with BaseFamily)\n"));
+    assert_eq!(s.matches("Model: ").count(), 1);
+    assert_eq!(s.matches("Family: ").count(), 1);
+}
@@ -0,0 +1,209 @@
+use crate::*;
+use libcpuid_dump::{CpuFeature, FeatureSet};
+
+/* The per-thread topology IDs are cleared to compare the threads */
+fn mask_thread_ids(raw: &RawCpuid) -> RawCpuid {
+    let mut raw = raw.clone();
+    let r = &mut raw.result;
+
+    match raw.leaf {
+        /* EBX[31:24]: Initial APIC ID */
+        0x1 => r.ebx &= 0x00FF_FFFF,
+        /* EDX: x2APIC ID */
+        0xB | 0x1F | 0x8000_0026 => r.edx = 0x0,
+        /* EAX: Extended APIC ID, EBX[7:0]: Core ID, ECX[7:0]: Node ID */
+        0x8000_001E => {
+            r.eax = 0x0;
+            r.ebx &= !0xFF;
+            r.ecx &= !0xFF;
+        },
+        _ => {},
+    }
+
+    raw
+}
+
+/* "0-5,12-17" */
+fn cpu_list(cpus: &[usize]) -> String {
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+
+    for &cpu in cpus {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == cpu => *end = cpu,
+            _ => ranges.push((cpu, cpu)),
+        }
+    }
+
+    let ranges: Vec<String> = ranges.iter()
+        .map(|(start, end)| if start == end { format!("{start}") } else { format!("{start}-{end}") })
+        .collect();
+
+    ranges.join(",")
+}
+
+/* The threads with the same CPUID results except the topology IDs */
+struct Class {
+    cpus: Vec<usize>,
+    pool: Vec<RawCpuid>,
+}
+
+impl Class {
+    fn cpuid(&self, leaf: u32, sub_leaf: u32) -> CpuidResult {
+        self.pool.iter()
+            .find(|raw| (raw.leaf, raw.sub_leaf) == (leaf, sub_leaf))
+            .map_or(ZERO, |raw| raw.result)
+    }
+
+    /* CPUID[0x1A].EAX[31:24], Intel Hybrid */
+    fn core_type(&self) -> u32 {
+        self.cpuid(0x1A, 0x0).eax >> 24
+    }
+}
+
+/* Group the threads into the classes and display the differences between them */
+pub(crate) fn hetero_report(threads: &[(usize, Vec<RawCpuid>)], vendor: &CpuVendor) -> String {
+    let mut classes: Vec<Class> = Vec::new();
+
+    for (cpu, pool) in threads {
+        let mut pool: Vec<RawCpuid> = pool.iter().map(mask_thread_ids).collect();
+        pool.sort_by_key(|raw| (raw.leaf, raw.sub_leaf));
+
+        match classes.iter_mut().find(|class| class.pool == pool) {
+            Some(class) => class.cpus.push(*cpu),
+            None => classes.push(Class { cpus: vec![*cpu], pool }),
+        }
+    }
+
+    let mut out = format!("[Classes: {}] (the APIC IDs and topology IDs are ignored)\n", classes.len());
+
+    for (i, class) in classes.iter().enumerate() {
+        let core_type = match class.core_type() {
+            0x20 => " [Atom]",
+            0x40 => " [Core]",
+            _ => "",
+        };
+
+        out.push_str(&format!(
+            "  #{i} {:>4} threads{core_type}  CPU {}\n",
+            class.cpus.len(),
+            cpu_list(&class.cpus),
+        ));
+    }
+
+    if classes.len() < 2 {
+        out.push_str("  All threads are the same.\n");
+        return out;
+    }
+
+    /* the leaves different between the classes */
+    let mut keys: Vec<(u32, u32)> = classes.iter()
+        .flat_map(|class| class.pool.iter().map(|raw| (raw.leaf, raw.sub_leaf)))
+        .collect();
+    keys.sort_unstable();
+    keys.dedup();
+
+    out.push_str("[Differences]\n");
+
+    for (leaf, sub_leaf) in keys {
+        let results: Vec<CpuidResult> = classes.iter().map(|class| class.cpuid(leaf, sub_leaf)).collect();
+
+        if results.iter().all(|r| *r == results[0]) {
+            continue;
+        }
+
+        let parsed: Vec<String> = results.iter()
+            .map(|result| RawCpuid { leaf, sub_leaf, result: *result }.parse(vendor))
+            .collect();
+        let fields: Vec<Vec<&str>> = parsed.iter().map(|s| decoded_fields(s)).collect();
+
+        out.push_str(&format!("  {leaf:#010X} {sub_leaf:#X}:\n"));
+
+        for (i, (result, fields_i)) in results.iter().zip(fields.iter()).enumerate() {
+            /* the decoded fields not common to all classes */
+            let uniq: Vec<&str> = fields_i.iter()
+                .filter(|field| !fields.iter().all(|f| f.contains(field)))
+                .copied()
+                .collect();
+
+            let ln = format!(
+                "    #{i}  {:#010X} {:#010X} {:#010X} {:#010X}  {}",
+                result.eax,
+                result.ebx,
+                result.ecx,
+                result.edx,
+                uniq.join(" "),
+            );
+
+            out.push_str(&format!("{}\n", ln.trim_end()));
+        }
+    }
+
+    /* the different features on the same core type are not expected */
+    let sets: Vec<FeatureSet> = classes.iter()
+        .map(|class| FeatureSet::from_cpuid_with(&|leaf, sub_leaf| class.cpuid(leaf, sub_leaf)))
+        .collect();
+    let mut warn = String::new();
+
+    for (i, class) in classes.iter().enumerate() {
+        for (j, other) in classes.iter().enumerate().skip(i + 1) {
+            if class.core_type() != other.core_type() {
+                continue;
+            }
+
+            let missing = |a: usize, b: usize| -> Vec<String> {
+                sets[b].difference(&sets[a]).iter()
+                    .filter_map(|ftr: CpuFeature| ftr_name(&ftr).map(|name| format!("[{name}]")))
+                    .collect()
+            };
+            let [missing_i, missing_j] = [missing(i, j), missing(j, i)];
+
+            if missing_i.is_empty() && missing_j.is_empty() {
+                warn.push_str(&format!("  (!) #{i} and #{j} differ on the same core type\n"));
+            }
+
+            for (a, b, missing) in [(i, j, missing_i), (j, i, missing_j)] {
+                if !missing.is_empty() {
+                    warn.push_str(&format!(
+                        "  (!) #{a} (CPU {}) lacks the features of #{b}: {}\n",
+                        cpu_list(&classes[a].cpus),
+                        missing.join(" "),
+                    ));
+                }
+            }
+        }
+    }
+
+    if !warn.is_empty() {
+        out.push_str(&format!("[Asymmetry]\n{warn}"));
+    }
+
+    out
+}
+
+#[test]
+fn test_hetero_report() {
+    /* Ryzen 5 5600G, AVX2 (CPUID[0x7].EBX[5]) is hidden on CPU 3 */
+    let thread = |apic_id: u32| {
+        let mut pool = ryzen_5_5600g_thread(apic_id).pool;
+
+        if apic_id == 3 {
+            pool.iter_mut().filter(|raw| raw.leaf == 0x7).for_each(|raw| raw.result.ebx &= !(1 << 5));
+        }
+
+        pool
+    };
+    let threads: Vec<(usize, Vec<RawCpuid>)> = (0..4).map(|cpu| (cpu, thread(cpu as u32))).collect();
+    let s = hetero_report(&threads, &CpuVendor::AuthenticAMD);
+
+    assert!(s.contains("[Classes: 2]"));
+    assert!(s.contains("  #0    3 threads  CPU 0-2\n"));
+    assert!(s.contains("  #1    1 threads  CPU 3\n"));
+    assert!(s.contains("  0x00000007 0x0:\n"));
+    assert!(s.contains("[AVX2]"));
+    assert!(!s.contains("  0x00000001 0x0:\n"));
+    assert!(s.contains("  (!) #1 (CPU 3) lacks the features of #0: [AVX2]\n"));
+
+    let s = hetero_report(&threads[..3], &CpuVendor::AuthenticAMD);
+
+    assert!(s.contains("  All threads are the same.\n"));
+}
@@ -0,0 +1,325 @@
+use crate::*;
+use libcpuid_dump::{AmdProcTopo, CacheProp, CacheType, FamModStep, TopoCacheInfo, TopoId};
+
+/* hwloc object types below Machine, in the order of the default nesting */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ObjKind {
+    Package,
+    Die,
+    /* level, hwloc cache_type (0: Unified, 1: Data, 2: Instruction) */
+    Cache(u32, u8),
+    Core,
+}
+
+impl ObjKind {
+    fn name(&self) -> String {
+        match self {
+            Self::Package => "Package".to_string(),
+            Self::Die => "Die".to_string(),
+            Self::Cache(level, 2) => format!("L{level}iCache"),
+            Self::Cache(level, _) => format!("L{level}Cache"),
+            Self::Core => "Core".to_string(),
+        }
+    }
+}
+
+/* The object containing the thread at each level */
+#[derive(Debug, Clone)]
+struct Obj {
+    kind: ObjKind,
+    id: u64,
+    os_index: Option<u32>,
+    cache: Option<CacheProp>,
+}
+
+/* A logical processor (hwloc PU) with the IDs derived from its CPUID results */
+#[derive(Debug, Clone)]
+pub(crate) struct HwlocPu {
+    os_index: usize,
+    objs: Vec<Obj>,
+    /* hwloc x86 backend compatible, "CPUVendor", "CPUModel", ... */
+    info: Vec<(&'static str, String)>,
+}
+
+/* ceil(log2(n)) */
+fn count_order(n: u32) -> u32 {
+    u32::BITS - n.saturating_sub(1).leading_zeros()
+}
+
+/* x2APIC ID >> the shift of the level below Die, CPUID[0x1F] */
+fn intel_die_id(cpuid: &dyn Fn(u32, u32) -> CpuidResult, x2apic_id: u32) -> Option<u32> {
+    if cpuid(0x0, 0x0).eax < 0x1F {
+        return None;
+    }
+
+    let mut shift = 0;
+
+    for sub_leaf in 0x0..0x8 {
+        let topo = cpuid(0x1F, sub_leaf);
+
+        match (topo.ecx >> 8) & 0xFF {
+            0x0 => return None,
+            0x5 => return Some(x2apic_id.checked_shr(shift).unwrap_or(0)),
+            _ => shift = topo.eax & 0x1F,
+        }
+    }
+
+    None
+}
+
+impl HwlocPu {
+    pub fn from_cpuid(os_index: usize, cpuid: &dyn Fn(u32, u32) -> CpuidResult) -> Self {
+        let vendor = CpuVendor::from(&cpuid(0x0, 0x0));
+        let max_ext_leaf = cpuid(0x8000_0000, 0x0).eax;
+        let amd_topo = if vendor.is_amd_compatible() && 0x8000_001E <= max_ext_leaf {
+            Some(AmdProcTopo::from(&cpuid(0x8000_001E, 0x0)))
+        } else {
+            None
+        };
+
+        /* (x2APIC ID, Package ID, Core ID unique in the package) */
+        let (apic_id, pkg_id, core_id) = match TopoId::get_topo_info_with(&cpuid) {
+            Some(topo) => (topo.x2apic_id, topo.pkg_id, topo.core_id),
+            None => {
+                /* without CPUID[0xB], e.g. AMD Zen 1 */
+                let apic_id = match &amd_topo {
+                    Some(amd) => amd.ext_apic_id,
+                    None => cpuid(0x1, 0x0).ebx >> 24,
+                };
+                let smt_width = amd_topo.as_ref().map_or(0, |amd| count_order(amd.threads_per_core as u32));
+                let pkg_width = match (cpuid(0x8000_0008, 0x0).ecx >> 12) & 0xF {
+                    0 => count_order((cpuid(0x1, 0x0).ebx >> 16) & 0xFF),
+                    width => width,
+                };
+                let core_mask = !(u32::MAX.checked_shl(pkg_width).unwrap_or(0));
+
+                (apic_id, apic_id.checked_shr(pkg_width).unwrap_or(0), (apic_id & core_mask) >> smt_width)
+            },
+        };
+
+        let mut objs = vec![Obj { kind: ObjKind::Package, id: pkg_id as u64, os_index: Some(pkg_id), cache: None }];
+
+        let die_id = match &amd_topo {
+            Some(amd) => Some(amd.node_id as u32),
+            None => intel_die_id(cpuid, apic_id),
+        };
+
+        if let Some(die_id) = die_id {
+            objs.push(Obj { kind: ObjKind::Die, id: die_id as u64, os_index: Some(die_id), cache: None });
+        }
+
+        let mut caches = cache_props(cpuid, &vendor);
+
+        caches.sort_by_key(|cache| std::cmp::Reverse(cache.level));
+
+        for cache in caches {
+            let cache_type = match cache.cache_type {
+                CacheType::Data => 1,
+                CacheType::Instruction => 2,
+                _ => 0,
+            };
+            let id = TopoCacheInfo::get_cache_id(apic_id, cache.share_thread);
+
+            objs.push(Obj { kind: ObjKind::Cache(cache.level, cache_type), id: id as u64, os_index: None, cache: Some(cache) });
+        }
+
+        objs.push(Obj {
+            kind: ObjKind::Core,
+            id: ((pkg_id as u64) << 32) | core_id as u64,
+            os_index: Some(core_id),
+            cache: None,
+        });
+
+        let fms = FamModStep::from(&cpuid(0x1, 0x0));
+        let info = vec![
+            ("CPUVendor", vendor_string(&cpuid(0x0, 0x0))),
+            ("CPUFamilyNumber", fms.syn_fam.to_string()),
+            ("CPUModelNumber", fms.syn_mod.to_string()),
+            ("CPUModel", model_name(cpuid)),
+            ("CPUStepping", fms.step.to_string()),
+        ];
+
+        Self { os_index, objs, info }
+    }
+
+    fn obj(&self, kind: ObjKind) -> Option<&Obj> {
+        self.objs.iter().find(|obj| obj.kind == kind)
+    }
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/* hwloc bitmap string, 32-bit words from the highest, e.g. "0x00000001,0xffffffff" */
+fn cpuset(pus: &[&HwlocPu]) -> String {
+    let max = pus.iter().map(|pu| pu.os_index).max().unwrap_or(0);
+    let mut words = vec![0u32; max / 32 + 1];
+
+    for pu in pus {
+        words[pu.os_index / 32] |= 1 << (pu.os_index % 32);
+    }
+
+    while 1 < words.len() && words.last() == Some(&0) {
+        words.pop();
+    }
+
+    let words: Vec<String> = words.iter().rev().map(|word| format!("0x{word:08x}")).collect();
+
+    words.join(",")
+}
+
+struct HwlocWriter {
+    out: String,
+    gp_index: u32,
+    /* nesting order of the object types below Package */
+    kinds: Vec<ObjKind>,
+}
+
+impl HwlocWriter {
+    fn open(&mut self, indent: usize, ty: &str, os_index: Option<u32>, pus: &[&HwlocPu], attrs: &str) {
+        self.gp_index += 1;
+
+        let os_index = os_index.map_or("".to_string(), |i| format!(" os_index=\"{i}\""));
+        let set = cpuset(pus);
+
+        self.out.push_str(&format!(
+            "{:indent$}<object type=\"{ty}\"{os_index} cpuset=\"{set}\" complete_cpuset=\"{set}\"\
+            {attrs} nodeset=\"0x00000001\" complete_nodeset=\"0x00000001\" gp_index=\"{}\"",
+            "",
+            self.gp_index,
+        ));
+    }
+
+    fn write_level(&mut self, depth: usize, pus: &[&HwlocPu]) {
+        let indent = 2 * (depth + 2);
+
+        let kind = match self.kinds.get(depth) {
+            Some(kind) => *kind,
+            None => {
+                for pu in pus {
+                    self.open(indent, "PU", Some(pu.os_index as u32), &[pu], "");
+                    self.out.push_str("/>\n");
+                }
+                return;
+            },
+        };
+
+        /* group by the object ID at this level, in the order of the first thread */
+        let mut groups: Vec<(Option<&Obj>, Vec<&HwlocPu>)> = Vec::new();
+
+        for pu in pus {
+            let obj = pu.obj(kind);
+
+            match groups.iter_mut().find(|(o, _)| o.map(|o| o.id) == obj.map(|o| o.id)) {
+                Some((_, list)) => list.push(pu),
+                None => groups.push((obj, vec![pu])),
+            }
+        }
+
+        for (obj, list) in groups {
+            let obj = match obj {
+                Some(obj) => obj,
+                None => {
+                    self.write_level(depth + 1, &list);
+                    continue;
+                },
+            };
+            let attrs = match (&obj.cache, obj.kind) {
+                (Some(cache), ObjKind::Cache(level, cache_type)) => format!(
+                    " cache_size=\"{}\" depth=\"{level}\" cache_linesize=\"{}\" cache_associativity=\"{}\" cache_type=\"{cache_type}\"",
+                    cache.size,
+                    cache.line_size,
+                    cache.way,
+                ),
+                _ => "".to_string(),
+            };
+
+            self.open(indent, &kind.name(), obj.os_index, &list, &attrs);
+            self.out.push_str(">\n");
+
+            if kind == ObjKind::Package {
+                for (name, value) in &list[0].info {
+                    self.out.push_str(&format!(
+                        "{:indent$}<info name=\"{name}\" value=\"{}\"/>\n",
+                        "",
+                        xml_escape(value),
+                        indent = indent + 2,
+                    ));
+                }
+            }
+
+            self.write_level(depth + 1, &list);
+            self.out.push_str(&format!("{:indent$}</object>\n", ""));
+        }
+    }
+}
+
+/* hwloc v2 XML, same structure as `lstopo topo.xml` with a single NUMA node */
+pub(crate) fn hwloc_xml(pus: &[HwlocPu]) -> String {
+    let mut pus: Vec<&HwlocPu> = pus.iter().collect();
+
+    pus.sort_by_key(|pu| pu.os_index);
+
+    /* the number of threads in the largest object of each type, for nesting the caches shared by Dies */
+    let span = |kind: ObjKind| -> usize {
+        pus.iter()
+            .filter_map(|pu| pu.obj(kind))
+            .map(|obj| pus.iter().filter(|pu| pu.obj(kind).map(|o| o.id) == Some(obj.id)).count())
+            .max()
+            .unwrap_or(0)
+    };
+    let mut kinds: Vec<ObjKind> = Vec::new();
+
+    for pu in &pus {
+        for obj in &pu.objs {
+            if !kinds.contains(&obj.kind) {
+                kinds.push(obj.kind);
+            }
+        }
+    }
+
+    /* Package is always the outermost */
+    kinds[1..].sort_by_key(|kind| std::cmp::Reverse(span(*kind)));
+
+    let mut w = HwlocWriter { out: String::new(), gp_index: 0, kinds };
+    let all = cpuset(&pus);
+
+    w.out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    w.out.push_str("<!DOCTYPE topology SYSTEM \"hwloc2.dtd\">\n");
+    w.out.push_str("<topology version=\"2.0\">\n");
+    w.open(2, "Machine", Some(0), &pus, &format!(" allowed_cpuset=\"{all}\""));
+    w.out.push_str(" allowed_nodeset=\"0x00000001\">\n");
+    w.write_level(0, &pus);
+    /* CPUID does not report the memory, hwloc requires at least one NUMA node */
+    w.open(4, "NUMANode", Some(0), &pus, "");
+    w.out.push_str("/>\n");
+    w.out.push_str("  </object>\n</topology>\n");
+
+    w.out
+}
+
+#[test]
+fn test_hwloc_xml() {
+    /* Ryzen 5 5600G, 2 cores x 2 threads of 6 cores */
+    let pus: Vec<HwlocPu> = (0..4).map(|apic_id: u32| {
+        let snapshot = ryzen_5_5600g_thread(apic_id);
+
+        HwlocPu::from_cpuid(apic_id as usize, &|leaf, sub_leaf| snapshot.cpuid(leaf, sub_leaf))
+    }).collect();
+    let s = hwloc_xml(&pus);
+
+    assert_eq!(s.matches("<object type=\"L3Cache\"").count(), 1);
+    assert_eq!(s.matches("<object type=\"L2Cache\"").count(), 2);
+    assert_eq!(s.matches("<object type=\"Core\"").count(), 2);
+    assert_eq!(s.matches("<object type=\"PU\"").count(), 4);
+    assert!(s.contains("<object type=\"L3Cache\" cpuset=\"0x0000000f\" complete_cpuset=\"0x0000000f\" cache_size=\"16777216\" depth=\"3\""));
+    assert!(s.contains("<object type=\"Core\" os_index=\"1\" cpuset=\"0x0000000c\""));
+    assert!(s.contains("<info name=\"CPUVendor\" value=\"AuthenticAMD\"/>"));
+    /* Package > Die > L3 > L2 > L1d > L1i > Core > PU */
+    let pos: Vec<usize> = ["Package", "Die", "L3Cache", "L2Cache", "L1Cache", "L1iCache", "Core", "PU"].iter()
+        .map(|ty| s.find(&format!("<object type=\"{ty}\"")).unwrap())
+        .collect();
+
+    assert!(pos.windows(2).all(|w| w[0] < w[1]));
+}
@@ -1,109 +1,85 @@
 use crate::*;
 
-#[derive(Debug)]
-enum RegType {
-    EAX = 0,
-    EBX = 1,
-    ECX = 2,
-    EDX = 3,
-}
+/* see `LoadedThread::parse_dump` for the supported formats */
+pub fn load_file(path: &str) -> Result<Vec<LoadedThread>, Error> {
+    let s = std::fs::read_to_string(path).map_err(|err| {
+        Error::Io(std::io::Error::new(err.kind(), format!("\"{path}\": {err}")))
+    })?;
+    let threads = LoadedThread::parse_dump(&s);
 
-impl RegType {
-    fn step(&mut self) {
-        *self = match self {
-            // TODO: use add?
-            Self::EAX => Self::EBX,
-            Self::EBX => Self::ECX,
-            Self::ECX => Self::EDX,
-            Self::EDX => Self::EAX,
-        }
+    if threads.iter().all(|thread| thread.pool.is_empty()) {
+        return Err(Error::Parse(format!("\"{path}\": CPUID results not found")));
     }
-}
-
-pub fn load_file(load_path: String) {
-    use std::fs;
-    // println!("path: {}", load_path);
-    let v = fs::read_to_string(load_path)
-        .expect("Load error");
-    let mut pool: Vec<u8> = Vec::new();
 
-    pool.extend(
-        format!("   (in)EAX_xECX:  {:<10} {:<10} {:<10} {:<10}\n",
-            "(out)EAX", "(out)EBX", "(out)ECX", "(out)EDX").into_bytes()
-    );
-    pool.extend(
-        format!("{}\n", "=".repeat(TOTAL_WIDTH)).into_bytes()
-    );
+    Ok(threads)
+}
 
-    let vendor_flag = VendorFlag::all_true();
+/*
+    (logical processor number, CPUID results) of all threads in a dump file,
+    the results same as the first thread are omitted without "-no-diff" and filled from it.
+*/
+pub fn load_file_all_threads(path: &str) -> Result<Vec<(usize, Vec<RawCpuid>)>, Error> {
+    let threads: Vec<LoadedThread> = load_file(path)?
+        .into_iter()
+        .filter(|thread| !thread.pool.is_empty())
+        .collect();
+    let first = threads[0].pool.clone();
 
+    Ok(threads.into_iter().enumerate().map(|(i, thread)| {
+        let cpu = thread.cpu().unwrap_or(i);
+        let mut pool = thread.pool;
 
-    for ln in v.lines() {
-        let raw_cpuid_set = load_cpuid_dump_rs(ln);
-        if !raw_cpuid_set.check_all_zero() {
-            pool.extend(
-                raw_cpuid_set
-                    .parse_fmt(&vendor_flag)
-                    .into_bytes()
-            );
+        for raw in &first {
+            if !pool.iter().any(|r| (r.leaf, r.sub_leaf) == (raw.leaf, raw.sub_leaf)) {
+                pool.push(raw.clone());
+            }
         }
-    }
 
-    dump_write(&pool);
+        pool.sort_by_key(|raw| (raw.leaf, raw.sub_leaf));
+
+        (cpu, pool)
+    }).collect())
 }
 
-fn load_cpuid_dump_rs(line: &str) -> RawCpuid {
+/* the lib's `CpuidSnapshot::ryzen_5_5600g` is test-only and not visible from here */
+#[cfg(test)]
+pub(crate) fn ryzen_5_5600g() -> CpuidSnapshot {
+    let threads = LoadedThread::parse_dump(include_str!("../dump_result/AMD_Ryzen_5_5600G_with_Radeon_Graphics_00A50F00.txt"));
 
-    let mut raw = RawCpuid::zero();
-    let mut cpuid = CpuidResult { eax: 0x0, ebx: 0x0, ecx: 0x0, edx: 0x0 };
-    let mut reg_type = RegType::EAX;
+    CpuidSnapshot { cpu: Some(0), pool: threads[0].pool.clone() }
+}
 
-    // for ignore parsed strings
-    const LEN: usize = INPUT_WIDTH + OUTPUT_WIDTH;
+/* The same with the APIC ID of another thread (Leaf 0x1, 0xB, 0x8000_001E), 2 threads per core */
+#[cfg(test)]
+pub(crate) fn ryzen_5_5600g_thread(apic_id: u32) -> CpuidSnapshot {
+    let mut snapshot = ryzen_5_5600g();
 
-    let line = match line.get(..LEN) {
-        Some(v) => v,
-        _ => {
-            panic!("Load error: Possibly not the result file of cpuid_dump_rs")
-        },
-    };
+    for raw in snapshot.pool.iter_mut() {
+        let r = &mut raw.result;
 
-    for v in line.split(' ') {
-        match v.len() {
-            // "0x80000000".len()
-            10 => {
-                //println!("Reg: {:?} [{v}]", reg_type);
-                let v = u32::from_str_radix(&v[2..], 16).unwrap();
-                match reg_type {
-                    RegType::EAX => cpuid.eax = v,
-                    RegType::EBX => cpuid.ebx = v,
-                    RegType::ECX => cpuid.ecx = v,
-                    RegType::EDX => {
-                        cpuid.edx = v;
-                        raw.result = cpuid;
-                        continue;
-                    },
-                }
-                reg_type.step();
+        match raw.leaf {
+            0x1 => r.ebx = (r.ebx & 0x00FF_FFFF) | (apic_id << 24),
+            0xB => r.edx = apic_id,
+            0x8000_001E => {
+                r.eax = apic_id;
+                r.ebx = (r.ebx & !0xFF) | (apic_id >> 1);
             },
-            // "0x8000001F_x0:".len()
-            14 => {
-                // Base: "0x8000001F_x0:"
-                // to split: ("0x8000001F", "_x0:")
-                let (leaf, sub_leaf) = v.split_at(10);
-
-                // &leaf[2..]: "8000001F" => 0x8000001F
-                // &sub_leaf[2..3]: "0" => 0x0
-                let leaf = u32::from_str_radix(&leaf[2..], 16).unwrap();
-                let sub_leaf = u32::from_str_radix(&sub_leaf[2..3], 16).unwrap();
-                // println!("LEAFv2: 0x{:08x}, SUBv2: 0x{:08x}", leaf, sub_leaf);
-                raw.leaf = leaf;
-                raw.sub_leaf = sub_leaf;
-            }
             _ => {},
         }
-        // println!("SPLIT: \"{}\", LEN: {}", v, v.len());
     }
 
-    return raw;
+    snapshot
+}
+
+/* snapshot from constructed (leaf, sub_leaf, [eax, ebx, ecx, edx]) entries */
+#[cfg(test)]
+pub(crate) fn snapshot_from_regs(regs: &[(u32, u32, [u32; 4])]) -> CpuidSnapshot {
+    CpuidSnapshot {
+        cpu: None,
+        pool: regs.iter().map(|(leaf, sub_leaf, [eax, ebx, ecx, edx])| RawCpuid {
+            leaf: *leaf,
+            sub_leaf: *sub_leaf,
+            result: CpuidResult { eax: *eax, ebx: *ebx, ecx: *ecx, edx: *edx },
+        }).collect(),
+    }
 }
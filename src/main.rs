@@ -4,12 +4,12 @@
 use core::arch::x86_64::CpuidResult;
 use std::io;
 
-use libcpuid_dump::{cpuid, CpuVendor};
+use libcpuid_dump::{cpuid, CpuidReg, CpuidSnapshot, CpuVendor, Error, LoadedThread};
 
 pub const INPUT_WIDTH: usize = "  0x00000000 0x0:  ".len();
 pub const OUTPUT_WIDTH: usize = "0x00000000 ".len() * 4;
+/* the default width, see `term::total_width` */
 pub const TOTAL_WIDTH: usize = 100;
-pub const PARSE_WIDTH: usize = TOTAL_WIDTH - INPUT_WIDTH - OUTPUT_WIDTH - 1; // " ".len()
 // pub const VERSION_HEAD: &str = concat!("CPUID Dump ", env!("CARGO_PKG_VERSION"), "\n");
 
 mod raw_cpuid;
@@ -18,101 +18,53 @@ pub use raw_cpuid::*;
 mod parse;
 pub use parse::*;
 
-/*
-#[path = "./load_file.rs"]
+mod cli;
+use cli::*;
+
 mod load_file;
-pub use crate::load_file::*;
-*/
+pub use load_file::*;
 
-/// Main flow:
-///    pub struct RawCpuid {
-///        pub leaf: u32,
-///        pub sub_leaf: u32,
-///        // https://doc.rust-lang.org/core/arch/x86_64/struct.CpuidResult.html
-///        pub result: CpuidResult {
-///            pub eax: u32,
-///            pub ebx: u32,
-///            pub ecx: u32,
-///            pub edx: u32,
-///        },
-///    } 
-///    // src/main.rs
-///    MainOpt::parse() -> MainOpt
-///            |
-///    opt.rawcpuid_pool(&leaf_pool()) -> Vec<RawCpuid>
-///            |
-///    // src/raw_cpuid.rs
-///    let parsed_pool: Vec<u8>;
-///    cpuid_parse: {
-///        for raw_cpuid in cpuid_pool {
-///            // src/raw_cpuid.rs, src/parse/*
-///            let cpuid_parsed: String = raw_cpuid.parse();
-///            parsed_pool.extend(cpuid_parsed.into_bytes());
-///        }
-///    }
-///            |
-///    // src/main.rs
-///    dump_write(&parsed_pool) // print, write stdout
-///    
-
-fn leaf_pool() -> Vec<(u32, u32)> {
-    let mut leaf_pool: Vec<(u32, u32)> = Vec::with_capacity(64);
-
-    /* LFuncStd: largest standard function */
-    let max_std_leaf = RawCpuid::exe(0x0, 0x0).result.eax;
-    /* CPUID[Leaf=0x7, SubLeaf=0x0].EAX, StructExtFeatIdMax */
-    let leaf_07h_subc = RawCpuid::exe(0x7, 0x0).result.eax;
-    /* LFuncExt: largest extended function */
-    let max_ext_leaf = RawCpuid::exe(0x8000_0000, 0x0).result.eax;
-
-    /* Base */
-    for leaf in 0x0..=max_std_leaf {
-        match leaf {
-            /* Cache Properties, Intel */
-            0x4 => for sub_leaf in 0x0..=0x4 {
-                leaf_pool.push((leaf, sub_leaf))
-            },
-            0x7 => {
-                for sub_leaf in 0x0..=leaf_07h_subc {
-                    leaf_pool.push((leaf, sub_leaf))
-                }
-            },
-            /*  Extended Topology Enumeration, Intel, AMD Zen 2 <=
-                SMT_LEVEL = 0,
-                CORE_LEVEL = 1,
-            */
-            0xB => for sub_leaf in 0x0..=0x1 {
-                leaf_pool.push((leaf, sub_leaf))
-            },
-            /* 0xD: Processor Extended State Enumeration */
-            0xD => for sub_leaf in 0x0..0xF {
-                leaf_pool.push((leaf, sub_leaf))
-            },
-            /* 0x1F: V2 Extended Topology Enumeration Leaf, Intel */
-            0x1F => for sub_leaf in 0x0..=0x4 {
-                leaf_pool.push((0x1F, sub_leaf))
-            },
-            _ => leaf_pool.push((leaf, 0x0)),
-        }
-    }
+mod explain;
+use explain::*;
 
-    /* Ext */
-    for leaf in 0x8000_0000..=max_ext_leaf {
-        match leaf {
-            /* Cache Properties, AMD, same format as Intel Leaf 0x4 */
-            0x8000_001D => for sub_leaf in 0x0..=0x4 {
-                leaf_pool.push((leaf, sub_leaf))
-            },
-            /* AMD Platform QoS Enforcement for Memory Bandwidth */
-            0x8000_0020 => for sub_leaf in 0x0..=0x1 {
-                leaf_pool.push((leaf, sub_leaf))
-            },
-            _ => leaf_pool.push((leaf, 0x0)),
-        }
-    }
+mod codename_db;
+use codename_db::*;
 
-    leaf_pool
-}
+mod security;
+use security::*;
+
+mod vm_cpu;
+use vm_cpu::*;
+
+mod baseline;
+use baseline::*;
+
+mod binary_check;
+use binary_check::*;
+
+mod prometheus;
+use prometheus::*;
+
+mod hwloc;
+use hwloc::*;
+
+mod watch;
+use watch::*;
+
+mod hetero;
+use hetero::*;
+
+mod term;
+use term::*;
+
+mod tui;
+use tui::*;
+
+mod report;
+use report::*;
+
+mod codegen;
+use codegen::*;
 
 const LEAF_HEAD: &str = "       [Leaf.Sub]";
 const LEAF_LINE: &str = unsafe { std::str::from_utf8_unchecked(&[b'='; LEAF_HEAD.len()]) };
@@ -122,11 +74,11 @@ fn hex_head() -> String {
     const EBX: &str = "  [EBX]   ";
     const ECX: &str = "  [ECX]   ";
     const EDX: &str = "  [EDX]   ";
-    const LINE: &str = unsafe { std::str::from_utf8_unchecked(&[b'='; TOTAL_WIDTH]) };
+    let line = "=".repeat(total_width());
 
     format!("\
         {LEAF_HEAD}  {EAX} {EBX} {ECX} {EDX}\n\
-        {LINE}\
+        {line}\
     \n")
 }
 
@@ -162,10 +114,10 @@ fn topo_info_head() -> String {
     ]\n")
 }
 
-fn topo_info_thread_id_head(thread_id: usize) -> String {
+fn topo_info_thread_id_head(thread_id: usize, topo_info: Option<libcpuid_dump::TopoId>) -> String {
     use libcpuid_dump::TopoId;
 
-    let topo_info = match TopoId::get_topo_info() {
+    let topo_info = match topo_info {
         Some(topo) => topo,
         None => return format!("[Thread: {thread_id:03}]\n"),
     };
@@ -185,8 +137,32 @@ fn dump_write(pool: &[u8]) -> io::Result<()> {
     use std::io::{Write, stdout};
     let mut out = stdout().lock();
 
-    out.write_all(pool)?;
-    Ok(())
+    match out.write_all(pool) {
+        /* like "cpuid_dump | head" */
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        r => r,
+    }
+}
+
+/* Leaf 0x0 of the first thread has the vendor string */
+fn first_vendor(threads: &[(usize, Vec<RawCpuid>)]) -> CpuVendor {
+    threads[0].1.iter()
+        .find(|raw| raw.leaf == 0x0)
+        .map_or_else(CpuVendor::get, |raw| CpuVendor::from(&raw.result))
+}
+
+/* known errata of the codename and stepping, from the leaves in the pool */
+fn errata_fmt(pool: &[RawCpuid]) -> String {
+    let cpuid = |leaf: u32, sub_leaf: u32| -> CpuidResult {
+        pool.iter()
+            .find(|raw| raw.leaf == leaf && raw.sub_leaf == sub_leaf)
+            .map_or(ZERO, |raw| raw.result)
+    };
+
+    libcpuid_dump::Erratum::find_with(&cpuid)
+        .iter()
+        .map(|erratum| format!("  [Erratum: {}]\n    {}\n", erratum.id, erratum.desc))
+        .collect()
 }
 
 fn default_name() -> String {
@@ -198,45 +174,7 @@ fn default_name() -> String {
     format!("{proc_name}_{fms:08X}.txt")
 }
 
-fn help_msg() {
-    const MSG: &str = concat!(
-        "CPUID Dump ", env!("CARGO_PKG_VERSION"), "\n",
-        "https://github.com/Umio-Yasuno/cpuid_dump_rs\n",
-        "\n",
-        "USAGE:\n",
-        "    cargo run -- [options ..] or <cpuid_dump> [options ..]\n",
-        "\n",
-        "FLAGS:\n",
-        "    -a, -all\n",
-        "        Display result for all threads.\n",
-        "    -r, -raw\n",
-        "        Display raw/hex result.\n",
-        "    -bin\n",
-        "        Display binary result.\n",
-        "    -c, -compat\n",
-        "        Display the same format as `cpuid -r` (cpuid by Todd Allen)\n",
-        "    -full\n",
-        "        Combine \"-disp-zero\" and \"-no-diff\"\n",
-        "    -disp-zero\n",
-        "        Display result even if E[ABCD]X are zero.\n",
-        "    -no-diff\n",
-        "        Do not omit diff when all threads execution\n",
-        "\n",
-        "OPTIONS:\n",
-        "    --l <u32>, --leaf <u32>\n",
-        "        Display result only for the specified value, the value is Leaf/InputEAX <u32>.\n",
-        "        e.g. --leaf 1, --leaf 0x8000_0008,\n",
-        "    --sub_leaf <u32>, --subleaf <u32>\n",
-        "        Display result only for the specified value, the value is Sub_Leaf/InputECX <u32>.\n",
-        "    --s <path/filename>, --save <path/filename>\n",
-        "        Save dump result to text file.\n",
-        "        If there is no path/filename argument, will be used \"./<processor_name>\".",
-    );
-
-    println!("{MSG}")
-}
-
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 enum DumpFormat {
     Raw,
     Binary,
@@ -245,141 +183,164 @@ enum DumpFormat {
     Debug,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExportFormat {
+    /* dump of all threads */
+    Text,
+    Qemu,
+    Libvirt,
+    Prometheus,
+    Hwloc,
+    Markdown,
+    Html,
+    Rust,
+    CHeader,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CpuidBackend {
+    /* select at runtime */
+    Auto,
+    /* pin the thread to each CPU, and execute CPUID instruction */
+    Affinity,
+    /* read "/dev/cpu/<N>/cpuid", Linux only */
+    DevCpuid,
+}
+
+impl CpuidBackend {
+    #[cfg(target_os = "linux")]
+    fn detect() -> Self {
+        use libcpuid_dump::{util, CpuidDev};
+
+        if !CpuidDev::available() {
+            return Self::Affinity;
+        }
+
+        let cpu_list = match util::cpu_set_list() {
+            Ok(list) => list,
+            Err(_) => return Self::DevCpuid,
+        };
+        let first = match cpu_list.first() {
+            Some(&cpu) => cpu,
+            None => return Self::DevCpuid,
+        };
+
+        /* sched_setaffinity may be denied in restricted cgroups */
+        let pinned = std::thread::scope(|s| s.spawn(|| {
+            util::pin_thread(first).is_ok()
+        }).join().unwrap_or(false));
+
+        if !pinned {
+            return Self::DevCpuid;
+        }
+
+        match CpuidDev::cpu_list() {
+            Ok(dev_list) if dev_list.iter().any(|cpu| !cpu_list.contains(cpu)) => Self::DevCpuid,
+            _ => Self::Affinity,
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn detect() -> Self {
+        Self::Affinity
+    }
+}
+
 #[derive(Debug, Clone)]
 struct MainOpt {
+    cmd: Command,
     fmt: DumpFormat,
     dump_all: bool,
+    /* group the threads into the classes instead of dumping */
+    summary: bool,
     save_path: Option<String>,
-    leaf: Option<(u32, u32)>,
+    leaves: Vec<LeafSpec>,
+    reg: Option<CpuidReg>,
+    probe_sub_leaves: bool,
+    codename_db: Option<String>,
+    export_fmt: ExportFormat,
+    /* dump file for the export formats */
+    input: Option<String>,
     skip_zero: bool,
     diff: bool,
+    /* "--width", the terminal width if None */
+    width: Option<usize>,
+    /* ANSI colors for stdout */
+    color: bool,
+    /* "watch [SECONDS]" */
+    interval: std::time::Duration,
+    backend: CpuidBackend,
+    /* positional arguments of the command */
+    args: Vec<String>,
+    help: bool,
 }
 
 impl Default for MainOpt {
     fn default() -> Self {
         Self {
+            cmd: Command::Dump,
             fmt: DumpFormat::Parse,
             dump_all: false,
+            summary: false,
             save_path: None,
-            leaf: None,
+            leaves: Vec::new(),
+            reg: None,
+            probe_sub_leaves: false,
+            codename_db: None,
+            export_fmt: ExportFormat::Text,
+            input: None,
             skip_zero: true,
             diff: true,
+            width: None,
+            color: false,
+            interval: std::time::Duration::from_secs(1),
+            backend: CpuidBackend::Auto,
+            args: Vec::new(),
+            help: false,
         }
     }
 }
 
-impl MainOpt {
-    fn parse_value(raw_value: &str) -> u32 {
-        /* for like "0x8000_0000" */
-        let raw_value = raw_value.replace('_', "");
 
-        if let Some(stripped) = raw_value.strip_prefix("0x") {
-            u32::from_str_radix(stripped, 16).unwrap()
-        } else {
-            raw_value.parse::<u32>().unwrap()
-        }
-    }
+/* a panic in the per-thread closure becomes an error instead of aborting the whole dump */
+fn join_thread<T>(cpu: usize, handle: std::thread::ScopedJoinHandle<Result<T, Error>>) -> Result<T, Error> {
+    handle.join().unwrap_or_else(|_| Err(Error::Io(io::Error::other(format!("the thread for CPU {cpu} panicked")))))
+}
 
-    fn main_parse() -> Self {
-        let mut opt = MainOpt::default();
-        let mut skip = false;
+const ZERO: CpuidResult = CpuidResult { eax: 0x0, ebx: 0x0, ecx: 0x0, edx: 0x0 };
 
-        let args: Vec<String> = std::env::args().collect();
+/* CPUID of a thread, executed on the thread or read from "/dev/cpu/<N>/cpuid" */
+type CpuidFn<'a> = &'a dyn Fn(u32, u32) -> Result<CpuidResult, Error>;
 
-        for (idx, arg) in args.iter().enumerate() {
-            if skip {
-                skip = false;
-                continue;
-            }
+impl MainOpt {
+    fn leaf_pool(&self) -> Result<Vec<(u32, u32)>, Error> {
+        /* the range of "-probe-subleaves" */
+        const PROBE_MAX_SUB_LEAF: u32 = 0xFF;
+
+        if self.leaves.is_empty() {
+            return Ok(if self.probe_sub_leaves {
+                CpuidSnapshot::probe_leaf_pool(PROBE_MAX_SUB_LEAF)
+            } else {
+                CpuidSnapshot::leaf_pool()
+            });
+        }
 
-            if !arg.starts_with('-') {
-                // eprintln!("Unknown option: {}", args[i]);
-                continue;
-            }
+        let mut pool: Vec<(u32, u32)> = Vec::with_capacity(self.leaves.len());
 
-            let arg = arg.trim_start_matches('-');
-
-            match arg {
-                "a" | "all" => {
-                    opt.dump_all = true;
-                },
-                "r" | "raw" => {
-                    opt.fmt = DumpFormat::Raw;
-                    // opt.skip_zero = false;
-                },
-                "s" | "save" => {
-                    use std::path::Path;
-                    let mut path = default_name();
-
-                    if let Some(v) = args.get(idx+1) {
-                        if v.starts_with('-') { 
-                            opt.save_path = Some(path);
-                            continue;
-                        }
-
-                        path = if Path::new(v).is_dir() {
-                            format!("{v}{path}")
-                        } else {
-                            v.to_string()
-                        };
-                    }
-
-                    opt.save_path = Some(path);
-                },
-                "leaf" => {
-                    if let Some(v) = args.get(idx+1) {
-                        let leaf = Self::parse_value(v);
-                        opt.leaf = Some((leaf, 0x0));
-                    } else {
-                        eprintln!("missing argument <u32> to \"--leaf\"");
-                    };
-                },
-                "subleaf" | "sub_leaf" | "sub-leaf" => {
-                    if let (Some((leaf, _)), Some(sub_leaf)) = (opt.leaf, args.get(idx+1)) {
-                        let sub_leaf = Self::parse_value(sub_leaf);
-                        opt.leaf = Some((leaf, sub_leaf));
-                    } else {
-                        eprintln!("missing argument \"--sub_leaf <u32>\"");
-                    };
-                }
-                "bin" => {
-                    opt.fmt = DumpFormat::Binary;
-                },
-                "c" | "compat" => {
-                    opt.dump_all = true;
-                    opt.fmt = DumpFormat::CompatCpuid;
-                    opt.skip_zero = false;
-                    opt.diff = false;
-                },
-                "debug" => {
-                    opt.fmt = DumpFormat::Debug
-                },
-                "h" | "help" => {
-                    help_msg();
-                    std::process::exit(0);
-                },
-                "disp-zero" => {
-                    opt.skip_zero = false;
-                },
-                "no-diff" => {
-                    opt.diff = false;
-                },
-                "full" => {
-                    opt.skip_zero = false;
-                    opt.diff = false;
-                },
-                _ => eprintln!("Unknown option: {}", arg),
-            }
+        for spec in &self.leaves {
+            spec.check_supported()?;
+            pool.extend(spec.to_pool());
         }
 
-        opt
+        Ok(pool)
     }
 
-    fn rawcpuid_pool(&self, leaf_pool: &[(u32, u32)]) -> Vec<RawCpuid> {
+    fn rawcpuid_pool(&self, leaf_pool: &[(u32, u32)], cpuid: CpuidFn) -> Result<Vec<RawCpuid>, Error> {
         let mut cpuid_pool: Vec<RawCpuid> = Vec::with_capacity(leaf_pool.len());
 
         for (leaf, sub_leaf) in leaf_pool {
-            let cpuid = RawCpuid::exe(*leaf, *sub_leaf);
+            let [leaf, sub_leaf] = [*leaf, *sub_leaf];
+            let cpuid = RawCpuid { leaf, sub_leaf, result: cpuid(leaf, sub_leaf)? };
 
             if self.skip_zero && cpuid.check_result_zero() {
                 continue;
@@ -388,13 +349,13 @@ impl MainOpt {
             cpuid_pool.push(cpuid)
         }
 
-        cpuid_pool
+        Ok(cpuid_pool)
     }
 
-    fn thread_id_head(&self, thread_id: usize) -> String {
+    fn thread_id_head(&self, thread_id: usize, topo_info: Option<libcpuid_dump::TopoId>) -> String {
         match self.fmt {
             DumpFormat::CompatCpuid => format!("CPU {thread_id}:\n"),
-            _ => topo_info_thread_id_head(thread_id),
+            _ => topo_info_thread_id_head(thread_id, topo_info),
         }
     }
 
@@ -407,7 +368,7 @@ impl MainOpt {
         }
     }
 
-    fn select_pool(&self, rawcpuid_pool: &[RawCpuid]) -> Vec<u8> {
+    fn select_pool(&self, rawcpuid_pool: &[RawCpuid], vendor: &CpuVendor) -> Vec<u8> {
         let len = rawcpuid_pool.len();
         let (cap, fmt_func): (usize, fn(&RawCpuid, &CpuVendor) -> String) = match self.fmt {
             DumpFormat::Raw => (
@@ -433,130 +394,153 @@ impl MainOpt {
         };
 
         let mut parse_pool: Vec<u8> = Vec::with_capacity(cap);
-        let vendor = CpuVendor::get();
 
         for rawcpuid in rawcpuid_pool {
-            parse_pool.extend(fmt_func(rawcpuid, &vendor).into_bytes())
+            parse_pool.extend(fmt_func(rawcpuid, vendor).into_bytes())
+        }
+
+        if self.fmt == DumpFormat::Parse {
+            parse_pool.extend(errata_fmt(rawcpuid_pool).into_bytes());
         }
 
         parse_pool
     }
 
-    fn pool_all_thread(&self) -> Vec<u8> {
-        use std::thread;
-        use std::sync::Arc;
-        use libcpuid_dump::util;
+    /* call `f` for each thread with the CPUID of the thread, the results are ordered by thread id */
+    fn each_thread<T, F>(&self, f: F) -> Result<Vec<(usize, T)>, Error>
+    where
+        T: Send,
+        F: Fn(usize, CpuidFn) -> Result<T, Error> + Sync,
+    {
+        let backend = match self.backend {
+            CpuidBackend::Auto => CpuidBackend::detect(),
+            ref backend => backend.clone(),
+        };
 
-        let opt = Arc::new(self.clone());
-        let leaf_pool = Arc::new(leaf_pool());
-        let cpu_list = util::cpu_set_list().unwrap();
-        /* this with_capacity is experiental */
-        let mut main_pool = Vec::<u8>::with_capacity( if opt.diff {
-            TOTAL_WIDTH * leaf_pool.len() * cpu_list.len() / 2
-        } else {
-            TOTAL_WIDTH * leaf_pool.len() * cpu_list.len() * 2
-        });
-        let mut handles: Vec<thread::JoinHandle<_>> = Vec::with_capacity(cpu_list.len());
+        match backend {
+            #[cfg(target_os = "linux")]
+            CpuidBackend::DevCpuid => Self::each_thread_dev(&f),
+            _ => Self::each_thread_affinity(&f),
+        }
+    }
 
-        let (first_pool, topo_head) = {
-            /* To confine the effects of pin_thread */
-            thread::scope(|s| s.spawn(|| {
-                let cpu = cpu_list[0];
-                util::pin_thread(cpu).unwrap();
+    #[cfg(target_os = "linux")]
+    fn each_thread_dev<T, F>(f: &F) -> Result<Vec<(usize, T)>, Error>
+    where
+        T: Send,
+        F: Fn(usize, CpuidFn) -> Result<T, Error> + Sync,
+    {
+        use libcpuid_dump::CpuidDev;
 
-                let topo_head = opt.thread_id_head(cpu);
+        let cpu_list = CpuidDev::cpu_list()?;
 
-                (
-                    Arc::new(opt.rawcpuid_pool(&leaf_pool)),
-                    topo_head.into_bytes(),
-                )
-            }).join().unwrap())
-        };
+        /* no need to pin threads, the cpuid driver executes CPUID on the target CPU */
+        std::thread::scope(|s| {
+            let handles: Vec<_> = cpu_list.iter().map(|&cpu| s.spawn(move || {
+                let dev = CpuidDev::open(cpu)?;
 
-        main_pool.extend(topo_head);
-        main_pool.extend(opt.head_fmt().into_bytes());
-        main_pool.extend(opt.select_pool(&first_pool));
+                Ok((cpu, f(cpu, &|leaf, sub_leaf| dev.read(leaf, sub_leaf))?))
+            })).collect();
 
-        for cpu in &cpu_list[1..] {
-            let cpu = *cpu;
-            let opt = Arc::clone(&opt);
-            let leaf_pool = Arc::clone(&leaf_pool);
-            let first_pool = Arc::clone(&first_pool);
+            handles.into_iter().zip(&cpu_list).map(|(h, &cpu)| join_thread(cpu, h)).collect()
+        })
+    }
 
-            handles.push(thread::spawn(move || {
-                util::pin_thread(cpu).unwrap();
+    fn each_thread_affinity<T, F>(f: &F) -> Result<Vec<(usize, T)>, Error>
+    where
+        T: Send,
+        F: Fn(usize, CpuidFn) -> Result<T, Error> + Sync,
+    {
+        use libcpuid_dump::util;
 
-                let diff = {
-                    let mut sub_pool = opt.rawcpuid_pool(&leaf_pool);
+        let cpu_list = util::cpu_set_list()?;
 
-                    if opt.diff {
-                        let mut first_pool = first_pool.iter();
-                        sub_pool.retain(|sub| first_pool.next().unwrap() != sub );
-                    }
+        /* To confine the effects of pin_thread */
+        std::thread::scope(|s| {
+            let handles: Vec<_> = cpu_list.iter().map(|&cpu| s.spawn(move || {
+                util::pin_thread(cpu)?;
 
-                    sub_pool
-                };
+                Ok((cpu, f(cpu, &|leaf, sub_leaf| Ok(cpuid!(leaf, sub_leaf)))?))
+            })).collect();
 
-                let topo_head = opt.thread_id_head(cpu);
+            handles.into_iter().zip(&cpu_list).map(|(h, &cpu)| join_thread(cpu, h)).collect()
+        })
+    }
 
-                [
-                    topo_head.into_bytes(),
-                    opt.select_pool(&diff),
-                ].concat()
-            }));
-        }
+    fn pool_all_thread(&self) -> Result<Vec<u8>, Error> {
+        use libcpuid_dump::TopoId;
 
-        for h in handles {
-            let v = h.join().unwrap();
-            main_pool.extend(v);
+        let leaf_pool = self.leaf_pool()?;
+        let thread_pools = self.each_thread(|cpu, cpuid| {
+            let pool = self.rawcpuid_pool(&leaf_pool, cpuid)?;
+            let topo_info = TopoId::get_topo_info_with(&|leaf, sub_leaf| {
+                cpuid(leaf, sub_leaf).unwrap_or(ZERO)
+            });
+
+            Ok((self.thread_id_head(cpu, topo_info), pool))
+        })?;
+
+        /* this with_capacity is experiental */
+        let mut main_pool = Vec::<u8>::with_capacity( if self.diff {
+            TOTAL_WIDTH * leaf_pool.len() * thread_pools.len() / 2
+        } else {
+            TOTAL_WIDTH * leaf_pool.len() * thread_pools.len() * 2
+        });
+        let vendor = CpuVendor::get();
+
+        let mut thread_pools = thread_pools.into_iter();
+        let (topo_head, first_pool) = match thread_pools.next() {
+            Some((_, v)) => v,
+            None => return Ok(main_pool),
+        };
+
+        main_pool.extend(topo_head.into_bytes());
+        main_pool.extend(self.head_fmt().into_bytes());
+        main_pool.extend(self.select_pool(&first_pool, &vendor));
+
+        for (_, (topo_head, mut sub_pool)) in thread_pools {
+            if self.diff {
+                sub_pool.retain(|sub| !first_pool.contains(sub));
+            }
+
+            main_pool.extend(topo_head.into_bytes());
+            main_pool.extend(self.select_pool(&sub_pool, &vendor));
         }
 
-        main_pool
+        Ok(main_pool)
     }
 
-    fn dump_pool(&self) -> Vec<u8> {
+    fn dump_pool(&self) -> Result<Vec<u8>, Error> {
         if self.dump_all {
             return self.pool_all_thread();
         }
 
-        let rawcpuid_pool = self.rawcpuid_pool(&leaf_pool());
+        let rawcpuid_pool = self.rawcpuid_pool(
+            &self.leaf_pool()?,
+            &|leaf, sub_leaf| Ok(cpuid!(leaf, sub_leaf)),
+        )?;
 
-        [
+        Ok([
             topo_info_head().into_bytes(),
             self.head_fmt().into_bytes(),
-            self.select_pool(&rawcpuid_pool),
-        ].concat()
+            self.select_pool(&rawcpuid_pool, &CpuVendor::get()),
+        ].concat())
     }
 
-    fn only_leaf(&self, leaf: u32, sub_leaf: u32) -> io::Result<()> {
-        let raw_result = RawCpuid::exe(leaf, sub_leaf);
-        let vendor = CpuVendor::get();
-        let dump_fmt = match self.fmt {
-            DumpFormat::Raw => RawCpuid::raw_fmt,
-            DumpFormat::Binary => RawCpuid::bin_fmt,
-            DumpFormat::Parse => RawCpuid::parse_fmt,
-            DumpFormat::CompatCpuid => RawCpuid::compat_fmt,
-            DumpFormat::Debug => RawCpuid::debug_fmt,
-        };
-
-        let tmp = [
-            topo_info_head(),
-            self.head_fmt(),
-            dump_fmt(&raw_result, &vendor),
-        ]
-        .concat()
-        .into_bytes();
-
-        dump_write(&tmp)?;
+    /* the colors are only for the hex and parsed formats */
+    fn color_write(&self, pool: Vec<u8>) -> Result<(), Error> {
+        if !self.color || !matches!(self.fmt, DumpFormat::Parse | DumpFormat::Raw) {
+            return Ok(dump_write(&pool)?);
+        }
 
-        Ok(())
+        Ok(dump_write(colorize(&String::from_utf8_lossy(&pool)).as_bytes())?)
     }
 
-    fn save_file(&self, save_path: &String) -> io::Result<()> {
+    fn save_file(&self, save_path: &String) -> Result<(), Error> {
         use std::fs::File;
         use std::io::Write;
 
-        let pool = self.dump_pool();
+        let pool = self.dump_pool()?;
 
         let mut f = File::create(save_path)?;
 
@@ -566,21 +550,457 @@ impl MainOpt {
         Ok(())
     }
 
-    fn run(&self) {
-        match self {
-            Self { leaf: Some(leaf), .. } => {
-                self.only_leaf(leaf.0, leaf.1).expect("faild only_leaf")
+    /* the first thread of "--input <FILE>", or the current thread */
+    fn input_snapshot(&self) -> Result<CpuidSnapshot, Error> {
+        Ok(match &self.input {
+            Some(path) => CpuidSnapshot { cpu: None, pool: load_file(path)?.swap_remove(0).pool },
+            None => CpuidSnapshot::take(),
+        })
+    }
+
+    /* all threads of "--input <FILE>", or all threads of the processor */
+    fn input_threads(&self) -> Result<Vec<(usize, Vec<RawCpuid>)>, Error> {
+        match &self.input {
+            Some(path) => load_file_all_threads(path),
+            None => {
+                let leaf_pool = self.leaf_pool()?;
+
+                self.each_thread(|_, cpuid| self.rawcpuid_pool(&leaf_pool, cpuid))
+            },
+        }
+    }
+
+    fn hwloc_pus(&self) -> Result<Vec<HwlocPu>, Error> {
+        let pus = self.input_threads()?
+            .into_iter()
+            .map(|(cpu, pool)| {
+                let snapshot = CpuidSnapshot { cpu: Some(cpu), pool };
+
+                HwlocPu::from_cpuid(cpu, &|leaf, sub_leaf| snapshot.cpuid(leaf, sub_leaf))
+            })
+            .collect();
+
+        Ok(pus)
+    }
+
+    fn export(&self) -> Result<(), Error> {
+        let out = if self.export_fmt == ExportFormat::Hwloc {
+            hwloc_xml(&self.hwloc_pus()?)
+        } else if let ExportFormat::Markdown | ExportFormat::Html = self.export_fmt {
+            let report = Report::from_threads(&self.input_threads()?);
+
+            if self.export_fmt == ExportFormat::Markdown { report.markdown() } else { report.html() }
+        } else {
+            let snapshot = self.input_snapshot()?;
+            let cpuid = |leaf: u32, sub_leaf: u32| snapshot.cpuid(leaf, sub_leaf);
+
+            match self.export_fmt {
+                ExportFormat::Qemu => VmCpu::from_cpuid(&cpuid).qemu(),
+                ExportFormat::Libvirt => VmCpu::from_cpuid(&cpuid).libvirt(),
+                ExportFormat::Prometheus => prometheus_metrics(&cpuid),
+                ExportFormat::Rust => rust_consts(&cpuid),
+                ExportFormat::CHeader => c_header(&cpuid),
+                ExportFormat::Hwloc |
+                ExportFormat::Markdown |
+                ExportFormat::Html |
+                ExportFormat::Text => unreachable!(),
+            }
+        };
+
+        match &self.save_path {
+            Some(path) => {
+                std::fs::write(path, out)?;
+                println!("Output to \"{path}\"");
+                Ok(())
+            },
+            None => Ok(dump_write(out.as_bytes())?),
+        }
+    }
+
+    fn check(&self) -> Result<(), Error> {
+        use libcpuid_dump::{util, MicroArchLevel};
+
+        let mut ftrs = Vec::with_capacity(self.args.len());
+
+        for name in &self.args {
+            let list = find_ftr(name);
+
+            if list.is_empty() {
+                return Err(Error::Parse(format!("unknown feature: \"{name}\"")));
+            }
+
+            ftrs.push((name, list));
+        }
+
+        println!("x86-64 micro-architecture level: {:?}", MicroArchLevel::check());
+
+        let mut unsupported = false;
+
+        for (name, list) in ftrs {
+            let supported = list.iter().any(|(leaf, sub_leaf, reg, bit)| {
+                util::is_leaf_supported(*leaf)
+                    && (reg.get(&cpuid!(*leaf, *sub_leaf)) >> bit) & 1 == 1
+            });
+            let (leaf, sub_leaf, reg, bit) = list[0];
+
+            println!(
+                "  [{}] {name:<24} {leaf:#010X} {sub_leaf:#X} {reg:?}[{bit:02}]",
+                if supported { "Y" } else { "N" },
+            );
+
+            unsupported |= !supported;
+        }
+
+        if unsupported {
+            std::process::exit(1);
+        }
+
+        Ok(())
+    }
+
+    fn diff_files(&self) -> Result<(), Error> {
+        let [pool_a, pool_b] = {
+            let a = &self.args[0];
+            let first = |path: &str| -> Result<Vec<RawCpuid>, Error> {
+                Ok(load_file(path)?.swap_remove(0).pool)
+            };
+
+            let pool_b = match self.args.get(1) {
+                Some(b) => first(b)?,
+                None => self.rawcpuid_pool(&CpuidSnapshot::leaf_pool(), &|leaf, sub_leaf| Ok(cpuid!(leaf, sub_leaf)))?,
+            };
+
+            [first(a)?, pool_b]
+        };
+        let name_b = self.args.get(1).map_or("current processor", |s| s.as_str());
+
+        let mut keys: Vec<(u32, u32)> = pool_a.iter().chain(pool_b.iter())
+            .map(|raw| (raw.leaf, raw.sub_leaf))
+            .collect();
+        keys.sort_unstable();
+        keys.dedup();
+
+        let find = |pool: &[RawCpuid], key: (u32, u32)| -> Option<RawCpuid> {
+            pool.iter().find(|raw| (raw.leaf, raw.sub_leaf) == key).cloned()
+        };
+        let vendor = CpuVendor::get();
+        let mut out = format!("--- {}\n+++ {name_b}\n", self.args[0]).into_bytes();
+
+        for key in keys {
+            let [a, b] = [find(&pool_a, key), find(&pool_b, key)];
+
+            if a == b {
+                continue;
+            }
+
+            if let Some(a) = a {
+                out.extend(["-", &a.raw_fmt(&vendor)].concat().into_bytes());
+            }
+            if let Some(b) = b {
+                out.extend(["+", &b.raw_fmt(&vendor)].concat().into_bytes());
+            }
+        }
+
+        Ok(dump_write(&out)?)
+    }
+
+    fn topo(&self) -> Result<(), Error> {
+        use libcpuid_dump::{HybridInfo, TopoId};
+
+        let list = self.each_thread(|_, cpuid| {
+            let topo_info = TopoId::get_topo_info_with(&|leaf, sub_leaf| {
+                cpuid(leaf, sub_leaf).unwrap_or(ZERO)
+            });
+            let core_type = if libcpuid_dump::util::is_leaf_supported(0x1A) {
+                HybridInfo::get_core_type(&cpuid(0x1A, 0x0)?)
+            } else {
+                None
+            };
+
+            Ok((topo_info, core_type))
+        })?;
+
+        let mut out = String::from("[Thread] [Pkg] [Core] [SMT] [x2APIC] [CoreType]\n");
+
+        for (cpu, (topo_info, core_type)) in list {
+            let core_type = core_type.map_or("".to_string(), |t| format!("{t:?}"));
+
+            match topo_info {
+                Some(TopoId { pkg_id, core_id, smt_id, x2apic_id }) => out.push_str(&format!(
+                    "  {cpu:>6} {pkg_id:>5} {core_id:>6} {smt_id:>5} {x2apic_id:>8} {core_type}\n"
+                )),
+                None => out.push_str(&format!("  {cpu:>6} {:>5} {:>6} {:>5} {:>8} {core_type}\n", "-", "-", "-", "-")),
+            }
+        }
+
+        Ok(dump_write(out.as_bytes())?)
+    }
+
+    fn load(&self) -> Result<(), Error> {
+        let threads = load_file(&self.args[0])?;
+        /* Leaf 0x0 has the vendor string */
+        let vendor = threads.iter()
+            .flat_map(|thread| thread.pool.iter())
+            .find(|raw| raw.leaf == 0x0)
+            .map_or(CpuVendor::Unknown(libcpuid_dump::Vendor { ebx: 0x0, ecx: 0x0, edx: 0x0 }), |raw| {
+                CpuVendor::from(&raw.result)
+            });
+        let mut out: Vec<u8> = Vec::with_capacity(TOTAL_WIDTH * 256);
+
+        for (i, thread) in threads.iter().enumerate() {
+            if !thread.head.is_empty() {
+                out.extend(format!("{}\n", thread.head).into_bytes());
+            }
+            if i == 0 {
+                out.extend(self.head_fmt().into_bytes());
+            }
+            out.extend(self.select_pool(&thread.pool, &vendor));
+        }
+
+        self.color_write(out)
+    }
+
+    fn explain(&self) -> Result<(), Error> {
+        let regs = match self.reg {
+            Some(reg) => vec![reg],
+            None => vec![CpuidReg::EAX, CpuidReg::EBX, CpuidReg::ECX, CpuidReg::EDX],
+        };
+        let mut out = String::new();
+
+        for (leaf, sub_leaf) in self.leaf_pool()? {
+            let cpuid = cpuid!(leaf, sub_leaf);
+
+            for reg in &regs {
+                out.push_str(&explain_reg(leaf, sub_leaf, *reg, &cpuid));
+                out.push('\n');
+            }
+        }
+
+        Ok(dump_write(out.as_bytes())?)
+    }
+
+    fn security(&self) -> Result<(), Error> {
+        let out = match self.args.first() {
+            Some(path) => {
+                let pool = load_file(path)?.swap_remove(0).pool;
+                let cpuid = |leaf: u32, sub_leaf: u32| -> CpuidResult {
+                    pool.iter()
+                        .find(|raw| raw.leaf == leaf && raw.sub_leaf == sub_leaf)
+                        .map_or(ZERO, |raw| raw.result)
+                };
+
+                security_report(&cpuid, false)
             },
-            Self { save_path: Some(path), .. } => {
-                self.save_file(path).expect("faild save_file")
+            None => {
+                let cpuid = |leaf: u32, sub_leaf: u32| -> CpuidResult {
+                    if libcpuid_dump::util::is_leaf_supported(leaf) { cpuid!(leaf, sub_leaf) } else { ZERO }
+                };
+
+                security_report(&cpuid, cfg!(target_os = "linux"))
             },
+        };
+
+        Ok(dump_write(out.as_bytes())?)
+    }
+
+    fn baseline(&self) -> Result<(), Error> {
+        let mut hosts = Vec::with_capacity(self.args.len());
+
+        for path in &self.args {
+            let pool = load_file(path)?.swap_remove(0).pool;
+
+            hosts.push(Host { name: path.to_string(), snapshot: CpuidSnapshot { cpu: None, pool } });
+        }
+
+        Ok(dump_write(baseline_report(&hosts).as_bytes())?)
+    }
+
+    fn binary_check(&self) -> Result<(), Error> {
+        use libcpuid_dump::FeatureSet;
+
+        let path = &self.args[0];
+        let bytes = std::fs::read(path).map_err(|err| {
+            Error::Io(io::Error::new(err.kind(), format!("\"{path}\": {err}")))
+        })?;
+        let isa = X86IsaNote::parse(&bytes).map_err(|err| Error::Parse(format!("\"{path}\": {err}")))?;
+        let snapshot = self.input_snapshot()?;
+        let target = FeatureSet::from_cpuid_with(&|leaf, sub_leaf| snapshot.cpuid(leaf, sub_leaf));
+        let (out, supported) = binary_check_report(&isa, &target);
+
+        dump_write(out.as_bytes())?;
+
+        if !supported {
+            std::process::exit(1);
+        }
+
+        Ok(())
+    }
+
+    fn summary(&self) -> Result<(), Error> {
+        let threads = match self.cmd {
+            Command::Load => load_file_all_threads(&self.args[0])?,
             _ => {
-                dump_write(&self.dump_pool()).expect("faild dump_write")
+                let leaf_pool = self.leaf_pool()?;
+
+                self.each_thread(|_, cpuid| self.rawcpuid_pool(&leaf_pool, cpuid))?
+            },
+        };
+        let vendor = first_vendor(&threads);
+
+        Ok(dump_write(hetero_report(&threads, &vendor).as_bytes())?)
+    }
+
+    fn tui(&self) -> Result<(), Error> {
+        let threads = match self.args.first() {
+            Some(path) => load_file_all_threads(path)?,
+            None => {
+                let leaf_pool = self.leaf_pool()?;
+
+                self.each_thread(|_, cpuid| self.rawcpuid_pool(&leaf_pool, cpuid))?
+            },
+        };
+        let vendor = first_vendor(&threads);
+
+        Tui::new(threads, vendor).run()
+    }
+
+    fn watch(&self) -> Result<(), Error> {
+        use std::time::SystemTime;
+
+        let interval = self.interval;
+        let leaf_pool = self.leaf_pool()?;
+        let vendor = CpuVendor::get();
+        let sample = || self.each_thread(|_, cpuid| self.rawcpuid_pool(&leaf_pool, cpuid));
+        let mut prev = sample()?;
+
+        /* unlike `dump_write`, stop watching if the pipe is closed, like "cpuid_dump watch | head" */
+        let write = |s: &str| -> io::Result<()> {
+            use std::io::Write;
+            let mut out = io::stdout().lock();
+
+            out.write_all(s.as_bytes())?;
+            out.flush()
+        };
+        let mut out = format!(
+            "[{}] Watching {} threads every {} s, Ctrl+C to stop\n",
+            timestamp(SystemTime::now()),
+            prev.len(),
+            interval.as_secs_f64(),
+        );
+
+        loop {
+            match write(&out) {
+                Err(err) if err.kind() == io::ErrorKind::BrokenPipe => return Ok(()),
+                r => r?,
+            }
+
+            std::thread::sleep(interval);
+
+            let cur = sample()?;
+            let time = timestamp(SystemTime::now());
+            out.clear();
+
+            for (cpu, pool) in &cur {
+                match prev.iter().find(|(prev_cpu, _)| prev_cpu == cpu) {
+                    Some((_, prev_pool)) => out.push_str(&watch_diff(*cpu, prev_pool, pool, &vendor, &time)),
+                    None => out.push_str(&format!("[{time}] CPU {cpu} is online\n")),
+                }
+            }
+
+            /* hot-unplugged or removed from the affinity mask */
+            for (cpu, _) in prev.iter().filter(|(cpu, _)| !cur.iter().any(|(cur_cpu, _)| cur_cpu == cpu)) {
+                out.push_str(&format!("[{time}] CPU {cpu} is offline\n"));
+            }
+
+            prev = cur;
+        }
+    }
+
+    fn run(&self) -> Result<(), Error> {
+        if self.help {
+            println!("{}", self.help());
+            return Ok(());
+        }
+
+        if let Some(path) = self.codename_db.clone().or_else(|| std::env::var(CODENAME_DB_ENV).ok()) {
+            load_codename_db(&path)?.install();
+        }
+
+        /* the saved files have the same width regardless of the terminal */
+        set_total_width(match self.width {
+            Some(width) => width,
+            None if self.save_path.is_none() => term_width().unwrap_or(TOTAL_WIDTH),
+            None => TOTAL_WIDTH,
+        });
+
+        match self.cmd {
+            Command::Export if self.export_fmt != ExportFormat::Text => self.export(),
+            Command::Dump |
+            Command::Load if self.summary => self.summary(),
+            Command::Dump |
+            Command::Leaf |
+            Command::Export => match &self.save_path {
+                Some(path) => self.save_file(path),
+                None => self.color_write(self.dump_pool()?),
             },
+            Command::Check => self.check(),
+            Command::Diff => self.diff_files(),
+            Command::Topo => self.topo(),
+            Command::Load => self.load(),
+            Command::Explain => self.explain(),
+            Command::Security => self.security(),
+            Command::Baseline => self.baseline(),
+            Command::BinaryCheck => self.binary_check(),
+            Command::Watch => self.watch(),
+            Command::Tui => self.tui(),
         }
     }
 }
 
+fn exit_code(err: &Error) -> i32 {
+    match err {
+        Error::Parse(_) => 2,
+        Error::PinThread { .. } |
+        Error::CpuSetList(_) => 3,
+        Error::UnsupportedLeaf { .. } => 4,
+        Error::CpuidDev { .. } |
+        Error::Io(_) => 5,
+    }
+}
+
+/// Main flow:
+///    pub struct RawCpuid {
+///        pub leaf: u32,
+///        pub sub_leaf: u32,
+///        // https://doc.rust-lang.org/core/arch/x86_64/struct.CpuidResult.html
+///        pub result: CpuidResult {
+///            pub eax: u32,
+///            pub ebx: u32,
+///            pub ecx: u32,
+///            pub edx: u32,
+///        },
+///    } 
+///    // src/main.rs, src/cli.rs
+///    MainOpt::main_parse() -> Result<MainOpt, Error>
+///            |
+///    // lib/snapshot.rs
+///    opt.rawcpuid_pool(&CpuidSnapshot::leaf_pool(), cpuid) -> Result<Vec<RawCpuid>, Error>
+///            |
+///    // src/raw_cpuid.rs
+///    let parsed_pool: Vec<u8>;
+///    cpuid_parse: {
+///        for raw_cpuid in cpuid_pool {
+///            // src/raw_cpuid.rs, src/parse/*
+///            let cpuid_parsed: String = raw_cpuid.parse();
+///            parsed_pool.extend(cpuid_parsed.into_bytes());
+///        }
+///    }
+///            |
+///    // src/main.rs
+///    dump_write(&parsed_pool) // print, write stdout
 fn main() {
-    MainOpt::main_parse().run();
+    if let Err(err) = MainOpt::main_parse().and_then(|opt| opt.run()) {
+        eprintln!("cpuid_dump: {err}");
+        std::process::exit(exit_code(&err));
+    }
 }
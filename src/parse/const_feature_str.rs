@@ -1,3 +1,5 @@
+use libcpuid_dump::{CpuFeature, CpuidReg};
+
 pub(crate) const fn ftr_00_01_edx_x0() -> [&'static str; 32] {
     let mut ftr = [""; 32];
 
@@ -217,8 +219,8 @@ pub(crate) const fn ftr_00_07_edx_x0() -> [&'static str; 32] {
     ftr[26] = "IBRS";
     ftr[27] = "STIBP";
     ftr[28] = "L1D_FLUSH";
-    // ftr[29] = "IA32_ARCH_CAPABILITIES";
-    // ftr[30] = "IA32_CORE_CAPABILITIES";
+    ftr[29] = "ARCH_CAPABILITIES"; // IA32_ARCH_CAPABILITIES MSR
+    ftr[30] = "CORE_CAPABILITIES"; // IA32_CORE_CAPABILITIES MSR
     ftr[31] = "SSBD";
 
     ftr
@@ -257,6 +259,19 @@ pub(crate) const fn ftr_00_07_edx_x1() -> [&'static str; 32] {
     ftr
 }
 
+pub(crate) const fn ftr_00_07_edx_x2() -> [&'static str; 32] {
+    let mut ftr = [""; 32];
+
+    ftr[0] = "PSFD"; // Predictive Store Forwarding Disable
+    ftr[1] = "IPRED_CTRL";
+    ftr[2] = "RRSBA_CTRL";
+    ftr[3] = "DDPD_U"; // Data Dependent Prefetcher Disable
+    ftr[4] = "BHI_CTRL";
+    ftr[5] = "MCDT_NO";
+
+    ftr
+}
+
 /* Ref: https://github.com/torvalds/linux/blob/master/arch/x86/kernel/fpu/xstate.c */
 pub(crate) const fn xfeature_mask_00_0d_eax_x0() -> [&'static str; 32] {
     let mut ftr = [""; 32];
@@ -411,6 +426,8 @@ pub(crate) const fn ftr_80_08_ebx_x0() -> [&'static str; 32] {
     ftr[26] = "SSBD_Not_Required";
     ftr[27] = "";
     ftr[28] = "PSFD"; // Predictive Store Forward Disable
+    ftr[29] = "BTC_NO"; // Branch Type Confusion
+    ftr[30] = "IBPB_RET"; // IBPB clears the return address predictor
 
     ftr
 }
@@ -526,7 +543,7 @@ pub(crate) const fn ftr_amd_80_21_eax_x0() -> [&'static str; 32] {
     ftr[2] = "LFenceAlwaysSerializing";
     ftr[3] = "SmmPgCfgLock";
     // ftr[4] = "";
-    // ftr[5] = "";
+    ftr[5] = "VERW_CLEAR"; // VERW clears the CPU buffers
     ftr[6] = "NullSelectClearsBase";
     ftr[7] = "UpperAddressIgnore";
     ftr[8] = "AutomaticIBRS";
@@ -538,6 +555,101 @@ pub(crate) const fn ftr_amd_80_21_eax_x0() -> [&'static str; 32] {
     /* */
     ftr[17] = "CpuidUserDis"; // GpOnUserCpuid
     ftr[18] = "EPSF"; // Enhanced Predictive Store Forwarding
+    /* */
+    ftr[27] = "SBPB"; // Selective Branch Predictor Barrier
+    ftr[28] = "IBPB_BRTYPE"; // IBPB flushes all branch type predictions
+    ftr[29] = "SRSO_NO"; // not vulnerable to Speculative Return Stack Overflow
+    ftr[30] = "SRSO_USER_KERNEL_NO";
+    ftr[31] = "SRSO_MSR_FIX";
 
     ftr
 }
+
+pub(crate) const fn ftr_amd_80_21_ecx_x0() -> [&'static str; 32] {
+    let mut ftr = [""; 32];
+
+    ftr[1] = "TSA_SQ_NO"; // not vulnerable to Transient Scheduler Attacks (store queue)
+    ftr[2] = "TSA_L1_NO"; // not vulnerable to Transient Scheduler Attacks (L1)
+
+    ftr
+}
+
+/* Zhaoxin/VIA PadLock and GMI (SM2/SM3/SM4), the same names as /proc/cpuinfo */
+pub(crate) const fn ftr_centaur_c0_01_edx_x0() -> [&'static str; 32] {
+    let mut ftr = [""; 32];
+
+    ftr[0] = "SM2";
+    ftr[1] = "SM2_EN";
+    ftr[2] = "RNG";
+    ftr[3] = "RNG_EN";
+    ftr[4] = "CCS"; // SM3, SM4
+    ftr[5] = "CCS_EN";
+    ftr[6] = "ACE";
+    ftr[7] = "ACE_EN";
+    ftr[8] = "ACE2";
+    ftr[9] = "ACE2_EN";
+    ftr[10] = "PHE";
+    ftr[11] = "PHE_EN";
+    ftr[12] = "PMM";
+    ftr[13] = "PMM_EN";
+
+    ftr
+}
+
+/* (Leaf, SubLeaf, Register, Feature strings), the registers of feature flags */
+pub(crate) const FTR_TABLE: &[(u32, u32, CpuidReg, [&str; 32])] = &[
+    (0x1, 0x0, CpuidReg::EDX, ftr_00_01_edx_x0()),
+    (0x1, 0x0, CpuidReg::ECX, ftr_00_01_ecx_x0()),
+    (0x6, 0x0, CpuidReg::EAX, ftr_00_06_eax_x0()),
+    (0x7, 0x0, CpuidReg::EBX, ftr_00_07_ebx_x0()),
+    (0x7, 0x0, CpuidReg::ECX, ftr_00_07_ecx_x0()),
+    (0x7, 0x0, CpuidReg::EDX, ftr_00_07_edx_x0()),
+    (0x7, 0x1, CpuidReg::EAX, ftr_00_07_eax_x1()),
+    (0x7, 0x1, CpuidReg::EDX, ftr_00_07_edx_x1()),
+    (0x7, 0x2, CpuidReg::EDX, ftr_00_07_edx_x2()),
+    (0xD, 0x1, CpuidReg::EAX, xsave_00_0d_eax_x1()),
+    (0x8000_0001, 0x0, CpuidReg::ECX, ftr_80_01_ecx_x0()),
+    (0x8000_0001, 0x0, CpuidReg::EDX, ftr_80_01_edx_x0()),
+    (0x8000_0007, 0x0, CpuidReg::EDX, ftr_amd_80_07_edx_x0()),
+    (0x8000_0008, 0x0, CpuidReg::EBX, ftr_80_08_ebx_x0()),
+    (0x8000_000A, 0x0, CpuidReg::EDX, ftr_amd_80_0a_edx_x0()),
+    (0x8000_001A, 0x0, CpuidReg::EAX, ftr_amd_80_1a_eax_x0()),
+    (0x8000_001B, 0x0, CpuidReg::EAX, ftr_amd_80_1b_eax_x0()),
+    (0x8000_001F, 0x0, CpuidReg::EAX, ftr_amd_80_1f_eax_x0()),
+    (0x8000_0021, 0x0, CpuidReg::EAX, ftr_amd_80_21_eax_x0()),
+    (0x8000_0021, 0x0, CpuidReg::ECX, ftr_amd_80_21_ecx_x0()),
+    (0xC000_0001, 0x0, CpuidReg::EDX, ftr_centaur_c0_01_edx_x0()),
+];
+
+/* ignore case and symbols, "sse4_1" matches "SSE4.1", "lahf-sahf" matches "LAHF/SAHF" */
+fn normalize_ftr_name(name: &str) -> String {
+    name.chars().filter(|c| c.is_ascii_alphanumeric()).map(|c| c.to_ascii_uppercase()).collect()
+}
+
+/* (Leaf, SubLeaf, Register, Bit) */
+pub(crate) fn find_ftr(name: &str) -> Vec<(u32, u32, CpuidReg, usize)> {
+    let name = normalize_ftr_name(name);
+    let mut list = Vec::new();
+
+    if name.is_empty() {
+        return list;
+    }
+
+    for (leaf, sub_leaf, reg, ftr_str) in FTR_TABLE {
+        for (bit, ftr) in ftr_str.iter().enumerate() {
+            if normalize_ftr_name(ftr) == name {
+                list.push((*leaf, *sub_leaf, *reg, bit));
+            }
+        }
+    }
+
+    list
+}
+
+/* The name in FTR_TABLE, `None` for the reserved bits and the registers not in the table */
+pub(crate) fn ftr_name(ftr: &CpuFeature) -> Option<&'static str> {
+    FTR_TABLE.iter()
+        .find(|(leaf, sub_leaf, reg, _)| (*leaf, *sub_leaf, *reg) == (ftr.leaf, ftr.sub_leaf, ftr.reg))
+        .map(|(_, _, _, ftr_str)| ftr_str[ftr.bit as usize])
+        .filter(|name| !name.is_empty())
+}
@@ -179,7 +179,10 @@ impl ParseAMD for CpuidResult {
     }
 
     fn ext_amd_80_21h(&self) -> String {
-        let ftr = align_mold_ftr(&str_detect_ftr(self.eax, &ftr_amd_80_21_eax_x0()));
+        let ftr = align_mold_ftr(&[
+            str_detect_ftr(self.eax, &ftr_amd_80_21_eax_x0()),
+            str_detect_ftr(self.ecx, &ftr_amd_80_21_ecx_x0()),
+        ].concat());
         let ucode_patch_size = self.ebx & 0xFFF;
 
         if 0 < ucode_patch_size {
@@ -8,6 +8,7 @@ pub trait ParseGeneric {
     fn thermal_power_00_06h(&self) -> String;
     fn feature_00_07h_x0(&self) -> String;
     fn feature_00_07h_x1(&self) -> String;
+    fn feature_00_07h_x2(&self) -> String;
     fn topo_ext_00_0bh(&self) -> String;
     fn xstate_00_0dh(&self, sub_leaf: u32) -> String;
     fn feature_80_01h(&self) -> String;
@@ -129,6 +130,10 @@ impl ParseGeneric for CpuidResult {
         ].concat())
     }
 
+    fn feature_00_07h_x2(&self) -> String {
+        align_mold_ftr(&str_detect_ftr(self.edx, &ftr_00_07_edx_x2()))
+    }
+
     fn topo_ext_00_0bh(&self) -> String {
         let topo = libcpuid_dump::IntelExtTopo::from(self);
 
@@ -201,7 +206,7 @@ impl ParseGeneric for CpuidResult {
     fn cpu_name(&self) -> String {
         let name = libcpuid_dump::ProcName::dec_cpuid(self);
 
-        String::from_utf8(name).unwrap()
+        String::from_utf8_lossy(&name).to_string()
     }
 
     fn cache_prop(&self) -> String {
@@ -1,4 +1,4 @@
-use crate::{INPUT_WIDTH, OUTPUT_WIDTH, PARSE_WIDTH};
+use crate::{parse_width, INPUT_WIDTH, OUTPUT_WIDTH};
 
 const PAD_WIDTH: usize = INPUT_WIDTH + OUTPUT_WIDTH + 1;
 // pub const PAD: &str = unsafe { std::str::from_utf8_unchecked(&[b' '; PAD_WIDTH]) };
@@ -43,7 +43,8 @@ pub(crate) fn str_detect_ftr(reg: u32, ftr_str: &[&str]) -> Vec<String> {
 }
 
 pub(crate) fn align_mold_ftr(ftrs: &[String]) -> String {
-    let mut rest: usize = PARSE_WIDTH;
+    let width = parse_width();
+    let mut rest: usize = width;
     let mut mold = String::with_capacity(ftrs.len() * 48);
 
     const DECO_LEN: usize = "[] ".len();
@@ -51,14 +52,17 @@ pub(crate) fn align_mold_ftr(ftrs: &[String]) -> String {
     for f in ftrs {
         let len = f.len() + DECO_LEN;
 
-        if len <= rest {
-            rest -= len;
+        /* a field longer than the width is not moved to the next line */
+        if len <= rest || mold.is_empty() {
+            rest = rest.saturating_sub(len);
         } else {
             mold += LN_PAD;
-            rest = PARSE_WIDTH.saturating_sub(len);
+            rest = width.saturating_sub(len);
         }
 
-        [ "[", f, "] " ].map(|s| mold.push_str(s));
+        for s in [ "[", f, "] " ] {
+            mold.push_str(s);
+        }
     }
 
     mold
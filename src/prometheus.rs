@@ -0,0 +1,135 @@
+use crate::*;
+use libcpuid_dump::{CacheProp, CacheType, CpuFeature, FamModStep, FeatureSet, MicroArchLevel, ProcInfo, ProcName};
+
+/* label value of the text exposition format, escape '\\', '"' and '\n' */
+fn label(s: &str) -> String {
+    s.trim().replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+pub(crate) fn vendor_string(cpuid: &CpuidResult) -> String {
+    let bytes: Vec<u8> = [cpuid.ebx, cpuid.edx, cpuid.ecx].iter().flat_map(|reg| reg.to_le_bytes()).collect();
+
+    String::from_utf8_lossy(&bytes).trim_matches(char::from(0)).to_string()
+}
+
+/* Deterministic cache parameters, CPUID[0x4] or AMD CPUID[0x8000_001D] */
+pub(crate) fn cache_props(cpuid: &dyn Fn(u32, u32) -> CpuidResult, vendor: &CpuVendor) -> Vec<CacheProp> {
+    let leaf = if vendor.is_amd_compatible() {
+        /* AMD TopologyExtensions: CPUID[Leaf=0x8000_0001, SubLeaf=0x0].ECX[22] */
+        if (cpuid(0x8000_0001, 0x0).ecx >> 22) & 0b1 == 0 {
+            return Vec::new();
+        }
+
+        0x8000_001D
+    } else {
+        if cpuid(0x0, 0x0).eax < 0x4 {
+            return Vec::new();
+        }
+
+        0x4
+    };
+
+    (0x0..0x10).map_while(|sub_leaf| CacheProp::option_from_cpuid(&cpuid(leaf, sub_leaf))).collect()
+}
+
+/* Processor Brand String, CPUID[0x8000_0002..=0x8000_0004] */
+pub(crate) fn model_name(cpuid: &dyn Fn(u32, u32) -> CpuidResult) -> String {
+    let bytes: Vec<u8> = (0x8000_0002..=0x8000_0004)
+        .flat_map(|leaf| ProcName::dec_cpuid(&cpuid(leaf, 0x0)))
+        .filter(|byte| *byte != 0)
+        .collect();
+
+    String::from_utf8_lossy(&bytes).trim().to_string()
+}
+
+/* all named feature flags, a name appearing in more than one register is merged */
+pub(crate) fn feature_flags(cpuid: &dyn Fn(u32, u32) -> CpuidResult) -> Vec<(&'static str, bool)> {
+    let set = FeatureSet::from_cpuid_with(&cpuid);
+    let mut ftrs: Vec<(&str, bool)> = Vec::with_capacity(512);
+    let all = FeatureSet::WORDS.iter()
+        .flat_map(|(leaf, sub_leaf, reg)| (0..32).map(move |bit| CpuFeature::new(*leaf, *sub_leaf, *reg, bit)));
+
+    for ftr in all {
+        let name = match ftr_name(&ftr) {
+            Some(name) => name,
+            None => continue,
+        };
+
+        match ftrs.iter_mut().find(|(n, _)| n.eq_ignore_ascii_case(name)) {
+            Some((_, has)) => *has |= set.has(ftr),
+            None => ftrs.push((name, set.has(ftr))),
+        }
+    }
+
+    ftrs
+}
+
+/* node_exporter textfile collector, "*.prom" */
+pub(crate) fn prometheus_metrics(cpuid: &dyn Fn(u32, u32) -> CpuidResult) -> String {
+    let vendor = CpuVendor::from(&cpuid(0x0, 0x0));
+    let fms = FamModStep::from(&cpuid(0x1, 0x0));
+    let info = ProcInfo::from_fms(&fms, &vendor);
+    let mut out = String::new();
+
+    out.push_str("# HELP cpuid_info Processor information decoded from CPUID.\n");
+    out.push_str("# TYPE cpuid_info gauge\n");
+    out.push_str(&format!(
+        "cpuid_info{{vendor=\"{}\",codename=\"{}\",microarch=\"{}\",model_name=\"{}\",\
+        family=\"{:#X}\",model=\"{:#X}\",stepping=\"{:#X}\"}} 1\n",
+        label(&vendor_string(&cpuid(0x0, 0x0))),
+        label(&info.codename.to_string()),
+        label(&info.archname.to_string()),
+        label(&model_name(cpuid)),
+        fms.syn_fam,
+        fms.syn_mod,
+        fms.step,
+    ));
+
+    out.push_str("# HELP cpuid_microarch_level x86-64 micro-architecture level, 0 if not x86-64.\n");
+    out.push_str("# TYPE cpuid_microarch_level gauge\n");
+    out.push_str(&format!("cpuid_microarch_level {}\n", MicroArchLevel::check_with(&cpuid) as u8));
+
+    out.push_str("# HELP cpuid_cache_bytes Cache size in bytes.\n");
+    out.push_str("# TYPE cpuid_cache_bytes gauge\n");
+
+    for cache in cache_props(cpuid, &vendor) {
+        let cache_type = match cache.cache_type {
+            CacheType::Data => "data",
+            CacheType::Instruction => "instruction",
+            CacheType::Unified => "unified",
+            CacheType::Unknown => "unknown",
+        };
+
+        out.push_str(&format!(
+            "cpuid_cache_bytes{{level=\"{}\",type=\"{cache_type}\"}} {}\n",
+            cache.level,
+            cache.size,
+        ));
+    }
+
+    out.push_str("# HELP cpuid_feature Whether the feature flag is reported by CPUID.\n");
+    out.push_str("# TYPE cpuid_feature gauge\n");
+
+    for (name, has) in feature_flags(cpuid) {
+        out.push_str(&format!("cpuid_feature{{name=\"{}\"}} {}\n", label(&name.to_lowercase()), has as u8));
+    }
+
+    out
+}
+
+#[test]
+fn test_prometheus_metrics() {
+    /* Ryzen 5 5600G */
+    let snapshot = ryzen_5_5600g();
+    let s = prometheus_metrics(&|leaf, sub_leaf| snapshot.cpuid(leaf, sub_leaf));
+
+    assert!(s.contains("vendor=\"AuthenticAMD\""));
+    assert!(s.contains("model_name=\"AMD Ryzen 5 5600G with Radeon Graphics\""));
+    assert!(s.contains("family=\"0x19\",model=\"0x50\",stepping=\"0x0\"} 1\n"));
+    assert!(s.contains("cpuid_microarch_level 3\n"));
+    assert!(s.contains("cpuid_cache_bytes{level=\"1\",type=\"data\"} 32768\n"));
+    assert!(s.contains("cpuid_cache_bytes{level=\"3\",type=\"unified\"} 16777216\n"));
+    assert!(s.contains("cpuid_feature{name=\"avx2\"} 1\n"));
+    assert!(s.contains("cpuid_feature{name=\"avx512f\"} 0\n"));
+    assert!(s.contains("cpuid_feature{name=\"syscall/sysret\"} 1\n"));
+}
@@ -1,26 +1,33 @@
 use crate::{CpuidResult, CpuVendor};
 use super::*;
 
-#[derive(Debug, PartialEq, Eq)]
-pub struct RawCpuid {
-    pub leaf: u32,
-    pub sub_leaf: u32,
-    pub result: CpuidResult,
+pub use libcpuid_dump::RawCpuid;
+
+/* "00000000_10100101_00001111_00000000", bit 31 first */
+pub(crate) fn bin_separate(reg: u32) -> String {
+    let tmp = format!("{reg:032b}");
+
+    format!(
+        "{}_{}_{}_{}",
+        &tmp[..8],
+        &tmp[8..16],
+        &tmp[16..24],
+        &tmp[24..32],
+    )
 }
 
-impl RawCpuid {
-    pub fn exe(leaf: u32, sub_leaf: u32) -> Self {
-        Self {
-            leaf,
-            sub_leaf,
-            result: cpuid!(leaf, sub_leaf),
-        }
-    }
-
-    pub fn check_result_zero(&self) -> bool {
-        self.result == CpuidResult { eax: 0x0, ebx: 0x0, ecx: 0x0, edx: 0x0 }
-    }
+/* formatting of the dump, RawCpuid is defined in libcpuid_dump */
+pub trait RawCpuidFmt {
+    fn parse(&self, vendor: &CpuVendor) -> String;
+    fn result(&self, end_str: &str) -> String;
+    fn raw_fmt(&self, _: &CpuVendor) -> String;
+    fn parse_fmt(&self, vendor: &CpuVendor) -> String;
+    fn bin_fmt(&self, _: &CpuVendor) -> String;
+    fn compat_fmt(&self, _: &CpuVendor) -> String;
+    fn debug_fmt(&self, _: &CpuVendor) -> String;
+}
 
+impl RawCpuidFmt for RawCpuid {
     fn parse(&self, vendor: &CpuVendor) -> String {
         let cpuid = self.result;
 
@@ -36,12 +43,13 @@ impl RawCpuid {
             0x7 => match self.sub_leaf {
                 0x0 => cpuid.feature_00_07h_x0(),
                 0x1 => cpuid.feature_00_07h_x1(),
+                0x2 => cpuid.feature_00_07h_x2(),
                 _ => "".to_string(),
             },
             0xB => cpuid.topo_ext_00_0bh(),
             0xD => cpuid.xstate_00_0dh(self.sub_leaf),
             0x8000_0001 => [
-                if let CpuVendor::AuthenticAMD = vendor {
+                if vendor.is_amd_compatible() {
                     [cpuid.pkgtype_amd_80_01h(), lnpad!()].concat()
                 } else {
                     "".to_string()
@@ -53,14 +61,15 @@ impl RawCpuid {
                 cpuid.addr_size_80_08h(),
                 lnpad!(),
                 cpuid.ftr_ext_id_80_08h_ebx(),
-                if let CpuVendor::AuthenticAMD = vendor {
+                if vendor.is_amd_compatible() {
                     [lnpad!(), cpuid.size_id_amd_80_08h()].concat()
                 } else {
                     "".to_string()
                 },
             ].concat(),
             _ => match vendor {
-                CpuVendor::AuthenticAMD => match self.leaf {
+                CpuVendor::AuthenticAMD |
+                CpuVendor::HygonGenuine => match self.leaf {
                     0x8000_0005 => cpuid.l1_amd_80_05h(),
                     0x8000_0006 => cpuid.l2_amd_80_06h(),
                     0x8000_0007 => cpuid.apmi_amd_80_07h(),
@@ -106,48 +115,36 @@ impl RawCpuid {
         )
     }
 
-    pub fn raw_fmt(&self, _: &CpuVendor) -> String {
+    fn raw_fmt(&self, _: &CpuVendor) -> String {
         self.result("")
     }
 
-    pub fn parse_fmt(&self, vendor: &CpuVendor) -> String {
+    fn parse_fmt(&self, vendor: &CpuVendor) -> String {
         self.result(&self.parse(vendor))
     }
 
-    pub fn bin_fmt(&self, _: &CpuVendor) -> String {
-        let separate = |reg: u32| -> String {
-            let tmp = format!("{reg:032b}");
-
-            format!(
-                "{}_{}_{}_{}",
-                &tmp[..8],
-                &tmp[8..16],
-                &tmp[16..24],
-                &tmp[24..32],
-            )
-        };
-
+    fn bin_fmt(&self, _: &CpuVendor) -> String {
         let [leaf, sub_leaf] = [self.leaf, self.sub_leaf];
         let [eax, ebx, ecx, edx] = [
             self.result.eax,
             self.result.ebx,
             self.result.ecx,
             self.result.edx,
-        ].map(separate);
+        ].map(bin_separate);
 
         const PAD: &str = unsafe { std::str::from_utf8_unchecked(&[b' '; 18]) };
 
         format!("  {leaf:#010X} {sub_leaf:#03X}:  {eax}  {ebx} \n{PAD} {ecx}  {edx} \n")
     }
 
-    pub fn compat_fmt(&self, _: &CpuVendor) -> String {
+    fn compat_fmt(&self, _: &CpuVendor) -> String {
         let [leaf, sub_leaf] = [self.leaf, self.sub_leaf];
         let CpuidResult { eax, ebx, ecx, edx } = self.result;
 
         format!("   {leaf:#010x} {sub_leaf:#04x}: eax={eax:#010x} ebx={ebx:#010x} ecx={ecx:#010x} edx={edx:#010x}\n")
     }
 
-    pub fn debug_fmt(&self, _: &CpuVendor) -> String {
+    fn debug_fmt(&self, _: &CpuVendor) -> String {
         format!("{:#X?}\n", self)
     }
 }
@@ -0,0 +1,379 @@
+use crate::*;
+use libcpuid_dump::{AmdPkgType, CacheType, FamModStep, IntelTlb, MicroArchLevel, ProcInfo, Tlb, TlbAssoc, TlbInfo, TlbType, TopoId};
+
+enum Block {
+    Text(String),
+    Table { head: &'static [&'static str], rows: Vec<Vec<String>> },
+}
+
+struct Section {
+    title: &'static str,
+    blocks: Vec<Block>,
+}
+
+/* A human-readable report for the bug reports, rendered to Markdown or HTML */
+pub(crate) struct Report {
+    title: String,
+    sections: Vec<Section>,
+}
+
+fn kv(key: &str, value: String) -> Vec<String> {
+    vec![key.to_string(), value]
+}
+
+/* "32 KiB", "16 MiB" */
+fn size_fmt(byte: u32) -> String {
+    match byte {
+        0x4000_0000.. => format!("{} GiB", byte >> 30),
+        0x10_0000.. => format!("{} MiB", byte >> 20),
+        0x400.. => format!("{} KiB", byte >> 10),
+        _ => format!("{byte} B"),
+    }
+}
+
+fn tlb_fmt(info: &TlbInfo) -> String {
+    match info.assoc {
+        TlbAssoc::Disabled |
+        TlbAssoc::Invalid => "-".to_string(),
+        TlbAssoc::Full => format!("{} entries, fully associative", info.size),
+        ref assoc => format!("{} entries, {}-way", info.size, assoc.to_string().trim()),
+    }
+}
+
+fn processor_section(cpuid: &dyn Fn(u32, u32) -> CpuidResult) -> Section {
+    let vendor = CpuVendor::from(&cpuid(0x0, 0x0));
+    let fms = FamModStep::from(&cpuid(0x1, 0x0));
+    let info = ProcInfo::from_fms(&fms, &vendor);
+    let pkg_type = if vendor.is_amd_compatible() {
+        match AmdPkgType::from(&cpuid(0x8000_0001, 0x0)) {
+            AmdPkgType::Unknown(_) => "Unknown".to_string(),
+            pkg_type => pkg_type.to_string(),
+        }
+    } else {
+        "-".to_string()
+    };
+    let level = MicroArchLevel::check_with(&cpuid) as u8;
+
+    Section {
+        title: "Processor",
+        blocks: vec![Block::Table {
+            head: &["Item", "Value"],
+            rows: vec![
+                kv("Name", model_name(cpuid)),
+                kv("Vendor", vendor_string(&cpuid(0x0, 0x0))),
+                kv("Codename", info.codename.to_string()),
+                kv("Microarchitecture", info.archname.to_string()),
+                kv("Stepping", info.step_info.to_string()),
+                kv("Process node", info.node.map_or("-".to_string(), |node| node.to_string())),
+                kv("Package", pkg_type),
+                kv("Family / Model / Stepping", format!("{:#X} / {:#X} / {:#X}", fms.syn_fam, fms.syn_mod, fms.step)),
+                kv("ISA level", if level == 0 { "-".to_string() } else { format!("x86-64-v{level}") }),
+            ],
+        }],
+    }
+}
+
+fn feature_section(cpuid: &dyn Fn(u32, u32) -> CpuidResult) -> Section {
+    let ftrs: Vec<&str> = feature_flags(cpuid).into_iter()
+        .filter_map(|(name, has)| if has { Some(name) } else { None })
+        .collect();
+    let categories = FtrCategory::ALL.iter().map(|category| Some(*category)).chain([None]);
+    let mut rows = Vec::new();
+
+    for category in categories {
+        let names: Vec<&str> = ftrs.iter()
+            .filter(|name| FtrCategory::from_name(name) == category)
+            .copied()
+            .collect();
+
+        rows.push(vec![
+            category.map_or("Other", |category| category.name()).to_string(),
+            names.len().to_string(),
+            names.join(", "),
+        ]);
+    }
+
+    Section {
+        title: "Features",
+        blocks: vec![Block::Table { head: &["Category", "Count", "Features"], rows }],
+    }
+}
+
+fn cache_section(cpuid: &dyn Fn(u32, u32) -> CpuidResult) -> Section {
+    let vendor = CpuVendor::from(&cpuid(0x0, 0x0));
+    let rows: Vec<Vec<String>> = cache_props(cpuid, &vendor).iter().map(|cache| {
+        let cache_type = match cache.cache_type {
+            CacheType::Data => "Data",
+            CacheType::Instruction => "Instruction",
+            CacheType::Unified => "Unified",
+            CacheType::Unknown => "Unknown",
+        };
+
+        vec![
+            format!("L{}", cache.level),
+            cache_type.to_string(),
+            size_fmt(cache.size),
+            cache.way.to_string(),
+            cache.set.to_string(),
+            format!("{} B", cache.line_size),
+            cache.share_thread.to_string(),
+            if cache.inclusive { "Yes" } else { "No" }.to_string(),
+        ]
+    }).collect();
+    let mut blocks = vec![if rows.is_empty() {
+        Block::Text("The deterministic cache parameters are not reported.".to_string())
+    } else {
+        Block::Table {
+            head: &["Level", "Type", "Size", "Ways", "Sets", "Line size", "Shared by threads", "Inclusive"],
+            rows,
+        }
+    }];
+
+    /* AMD L1/L2 TLB: CPUID[0x8000_0005], CPUID[0x8000_0006] */
+    if vendor.is_amd_compatible() {
+        let [l1, l2] = [cpuid(0x8000_0005, 0x0), cpuid(0x8000_0006, 0x0)];
+        let tlbs = [
+            Tlb::reg(TlbType::L1i, (l1.ebx & 0xFFFF) as u16, (l1.eax & 0xFFFF) as u16),
+            Tlb::reg(TlbType::L1d, (l1.ebx >> 16) as u16, (l1.eax >> 16) as u16),
+            Tlb::reg(TlbType::L2i, (l2.ebx & 0xFFFF) as u16, (l2.eax & 0xFFFF) as u16),
+            Tlb::reg(TlbType::L2d, (l2.ebx >> 16) as u16, (l2.eax >> 16) as u16),
+        ];
+
+        blocks.push(Block::Table {
+            head: &["TLB", "4K pages", "2M pages", "4M pages"],
+            rows: tlbs.iter().map(|tlb| vec![
+                format!("{}TLB", tlb.type_),
+                tlb_fmt(&tlb.page_4k),
+                tlb_fmt(&tlb.page_2m),
+                tlb_fmt(&tlb.page_4m),
+            ]).collect(),
+        });
+    } else {
+        /* Intel: CPUID[0x18], Deterministic Address Translation Parameters */
+        let tlbs = IntelTlb::get_all_with(&|leaf, sub_leaf| cpuid(leaf, sub_leaf));
+        let page_fmt = |tlb: &IntelTlb, supported: bool| if supported {
+            tlb_fmt(&tlb.info)
+        } else {
+            "-".to_string()
+        };
+
+        blocks.push(if tlbs.is_empty() {
+            Block::Text("TLB information is not reported.".to_string())
+        } else {
+            Block::Table {
+                head: &["TLB", "4K pages", "2M pages", "4M pages", "1G pages"],
+                rows: tlbs.iter().map(|tlb| vec![
+                    tlb.to_string(),
+                    page_fmt(tlb, tlb.page_4k),
+                    page_fmt(tlb, tlb.page_2m),
+                    page_fmt(tlb, tlb.page_4m),
+                    page_fmt(tlb, tlb.page_1g),
+                ]).collect(),
+            }
+        });
+    }
+
+    Section { title: "Caches and TLBs", blocks }
+}
+
+fn topology_section(threads: &[(usize, Vec<RawCpuid>)]) -> Section {
+    let ids: Vec<(usize, Option<TopoId>)> = threads.iter().map(|(cpu, pool)| {
+        let snapshot = CpuidSnapshot { cpu: Some(*cpu), pool: pool.clone() };
+
+        (*cpu, TopoId::get_topo_info_with(&|leaf, sub_leaf| snapshot.cpuid(leaf, sub_leaf)))
+    }).collect();
+    let mut pkgs: Vec<u32> = ids.iter().filter_map(|(_, id)| id.as_ref().map(|id| id.pkg_id)).collect();
+    let mut cores: Vec<(u32, u32)> = ids.iter().filter_map(|(_, id)| id.as_ref().map(|id| (id.pkg_id, id.core_id))).collect();
+
+    pkgs.sort_unstable();
+    pkgs.dedup();
+    cores.sort_unstable();
+    cores.dedup();
+
+    let rows = ids.iter().map(|(cpu, id)| match id {
+        Some(TopoId { pkg_id, core_id, smt_id, x2apic_id }) => vec![
+            cpu.to_string(),
+            pkg_id.to_string(),
+            core_id.to_string(),
+            smt_id.to_string(),
+            x2apic_id.to_string(),
+        ],
+        None => vec![cpu.to_string(), "-".to_string(), "-".to_string(), "-".to_string(), "-".to_string()],
+    }).collect();
+
+    Section {
+        title: "Topology",
+        blocks: vec![
+            Block::Text(format!("{} threads, {} cores, {} packages", ids.len(), cores.len(), pkgs.len())),
+            Block::Table { head: &["CPU", "Package", "Core", "SMT", "x2APIC ID"], rows },
+        ],
+    }
+}
+
+fn raw_section(pool: &[RawCpuid]) -> Section {
+    let rows = pool.iter().map(|raw| vec![
+        format!("{:#010X}", raw.leaf),
+        format!("{:#X}", raw.sub_leaf),
+        format!("{:#010X}", raw.result.eax),
+        format!("{:#010X}", raw.result.ebx),
+        format!("{:#010X}", raw.result.ecx),
+        format!("{:#010X}", raw.result.edx),
+    ]).collect();
+
+    Section {
+        title: "Appendix: Raw CPUID",
+        blocks: vec![Block::Table { head: &["Leaf", "Sub-leaf", "EAX", "EBX", "ECX", "EDX"], rows }],
+    }
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+impl Report {
+    /* the first thread for the processor information, all threads for the topology */
+    pub(crate) fn from_threads(threads: &[(usize, Vec<RawCpuid>)]) -> Self {
+        let snapshot = CpuidSnapshot { cpu: Some(threads[0].0), pool: threads[0].1.clone() };
+        let cpuid = |leaf: u32, sub_leaf: u32| snapshot.cpuid(leaf, sub_leaf);
+        let name = model_name(&cpuid);
+
+        Self {
+            title: format!("CPUID Report: {}", if name.is_empty() { "Unknown processor" } else { &name }),
+            sections: vec![
+                processor_section(&cpuid),
+                feature_section(&cpuid),
+                cache_section(&cpuid),
+                topology_section(threads),
+                raw_section(&threads[0].1),
+            ],
+        }
+    }
+
+    pub(crate) fn markdown(&self) -> String {
+        let cell = |s: &str| s.replace('|', "\\|");
+        let mut out = format!("# {}\n", self.title);
+
+        for section in &self.sections {
+            out.push_str(&format!("\n## {}\n", section.title));
+
+            for block in &section.blocks {
+                match block {
+                    Block::Text(text) => out.push_str(&format!("\n{}\n", cell(text))),
+                    Block::Table { head, rows } => {
+                        out.push_str(&format!("\n| {} |\n", head.join(" | ")));
+                        out.push_str(&format!("|{}\n", "---|".repeat(head.len())));
+
+                        for row in rows {
+                            let row: Vec<String> = row.iter().map(|s| cell(s)).collect();
+                            out.push_str(&format!("| {} |\n", row.join(" | ")));
+                        }
+                    },
+                }
+            }
+        }
+
+        out
+    }
+
+    pub(crate) fn html(&self) -> String {
+        let title = html_escape(&self.title);
+        let mut out = format!("\
+            <!DOCTYPE html>\n\
+            <html>\n\
+            <head>\n\
+            <meta charset=\"utf-8\">\n\
+            <title>{title}</title>\n\
+            <style>\n\
+            table {{ border-collapse: collapse; margin-bottom: 1em; }}\n\
+            th, td {{ border: 1px solid #999; padding: 2px 8px; text-align: left; }}\n\
+            </style>\n\
+            </head>\n\
+            <body>\n\
+            <h1>{title}</h1>\n\
+        ");
+
+        for section in &self.sections {
+            out.push_str(&format!("<h2>{}</h2>\n", html_escape(section.title)));
+
+            for block in &section.blocks {
+                match block {
+                    Block::Text(text) => out.push_str(&format!("<p>{}</p>\n", html_escape(text))),
+                    Block::Table { head, rows } => {
+                        out.push_str("<table>\n<tr>");
+                        for h in head.iter() {
+                            out.push_str(&format!("<th>{}</th>", html_escape(h)));
+                        }
+                        out.push_str("</tr>\n");
+
+                        for row in rows {
+                            out.push_str("<tr>");
+                            for s in row {
+                                out.push_str(&format!("<td>{}</td>", html_escape(s)));
+                            }
+                            out.push_str("</tr>\n");
+                        }
+                        out.push_str("</table>\n");
+                    },
+                }
+            }
+        }
+
+        out.push_str("</body>\n</html>\n");
+
+        out
+    }
+}
+
+#[test]
+fn test_report() {
+    /* Ryzen 5 5600G, CPU 0 and CPU 1 (SMT sibling) */
+    let thread = |apic_id: u32| ryzen_5_5600g_thread(apic_id).pool;
+    let report = Report::from_threads(&[(0, thread(0)), (1, thread(1))]);
+    let md = report.markdown();
+
+    assert!(md.starts_with("# CPUID Report: AMD Ryzen 5 5600G with Radeon Graphics\n"));
+    assert!(md.contains("| Vendor | AuthenticAMD |\n"));
+    assert!(md.contains("| Package | AM4 |\n"));
+    assert!(md.contains("| ISA level | x86-64-v3 |\n"));
+    assert!(md.contains("| SIMD | "));
+    assert!(md.contains(", AVX2, "));
+    assert!(md.contains("| L3 | Unified | 16 MiB | 16 | 16384 | 64 B | 12 | No |\n"));
+    assert!(md.contains("| L1dTLB | 64 entries, fully associative | "));
+    assert!(md.contains("\n2 threads, 1 cores, 1 packages\n"));
+    assert!(md.contains("| 1 | 0 | 0 | 1 | 1 |\n"));
+    assert!(md.contains("| 0x00000001 | 0x0 | 0x00A50F00 | 0x000C0800 | 0x7EF8320B | 0x178BFBFF |\n"));
+
+    let html = report.html();
+
+    assert!(html.contains("<title>CPUID Report: AMD Ryzen 5 5600G with Radeon Graphics</title>"));
+    assert!(html.contains("<tr><td>Vendor</td><td>AuthenticAMD</td></tr>\n"));
+    assert!(html.contains("<h2>Appendix: Raw CPUID</h2>"));
+    assert!(html.ends_with("</body>\n</html>\n"));
+}
+
+#[test]
+fn test_report_intel_tlb() {
+    let markdown = |snapshot: CpuidSnapshot| Report::from_threads(&[(0, snapshot.pool)]).markdown();
+
+    /* constructed leaf 0x18 layout: L1i, L1d (load), L1d (store, fully associative), L2 */
+    let md = markdown(snapshot_from_regs(&[
+        (0x0, 0x0, [0x00000020, 0x756E6547, 0x6C65746E, 0x49656E69]),
+        (0x18, 0x0, [0x00000004, 0x00000000, 0x00000000, 0x00000000]),
+        (0x18, 0x1, [0x00000000, 0x00080001, 0x00000010, 0x00004022]),
+        (0x18, 0x2, [0x00000000, 0x00040001, 0x00000010, 0x00004024]),
+        (0x18, 0x3, [0x00000000, 0x0010000F, 0x00000001, 0x00004125]),
+        (0x18, 0x4, [0x00000000, 0x00080003, 0x00000100, 0x00004043]),
+    ]));
+
+    assert!(md.contains("| TLB | 4K pages | 2M pages | 4M pages | 1G pages |\n"));
+    assert!(md.contains("| L1iTLB | 128 entries, 8-way | - | - | - |\n"));
+    assert!(md.contains("| L1dTLB (store) | 16 entries, fully associative | 16 entries, fully associative | "));
+    assert!(md.contains("| L2TLB | 2048 entries, 8-way | 2048 entries, 8-way | - | - |\n"));
+
+    /* no leaf 0x18 */
+    let md = markdown(snapshot_from_regs(&[
+        (0x0, 0x0, [0x00000016, 0x756E6547, 0x6C65746E, 0x49656E69]),
+    ]));
+
+    assert!(md.contains("\nTLB information is not reported.\n"));
+}
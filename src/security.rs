@@ -0,0 +1,105 @@
+use crate::*;
+
+/* Linux: "/sys/devices/system/cpu/vulnerabilities/<name>" */
+const SYSFS_VULN: &str = "/sys/devices/system/cpu/vulnerabilities";
+
+/*
+    (Vulnerability, Mitigation capabilities), the names are the same as sysfs and FTR_TABLE.
+    "IBRS", "STIBP", "SSBD" and "PSFD" have both of the Intel and AMD bits.
+*/
+const VULNERABILITIES: &[(&str, &[&str])] = &[
+    ("spectre_v1", &["LFenceAlwaysSerializing"]),
+    ("spectre_v2", &[
+        "IBRS", "IBPB", "STIBP", "IBRS_Always_On", "STIBP_Always_On", "IBRS_Preferred",
+        "IBRS_Same_Mode", "AutomaticIBRS", "IPRED_CTRL", "RRSBA_CTRL", "BHI_CTRL",
+    ]),
+    ("spec_store_bypass", &["SSBD", "SSBD_Virt_Spec_Ctrl", "SSBD_Not_Required", "PSFD"]),
+    ("l1tf", &["L1D_FLUSH"]),
+    ("mds", &["MD_CLEAR"]),
+    ("tsx_async_abort", &["MD_CLEAR", "RTM_ALWAYS_ABORT"]),
+    ("mmio_stale_data", &["MD_CLEAR"]),
+    ("srbds", &["SRBDS_CTRL"]),
+    ("retbleed", &["BTC_NO", "IBPB_RET"]),
+    ("spec_rstack_overflow", &["SBPB", "IBPB_BRTYPE", "SRSO_NO", "SRSO_USER_KERNEL_NO", "SRSO_MSR_FIX"]),
+    ("tsa", &["VERW_CLEAR", "TSA_SQ_NO", "TSA_L1_NO"]),
+];
+
+/* not a vulnerability, the *_NO bits are in the IA32_ARCH_CAPABILITIES MSR */
+const CAPABILITIES: &[&str] = &["ARCH_CAPABILITIES", "CORE_CAPABILITIES"];
+
+fn read_sysfs(name: &str) -> Option<String> {
+    std::fs::read_to_string(format!("{SYSFS_VULN}/{name}")).ok().map(|s| s.trim_end().to_string())
+}
+
+/* a line per feature, returns whether any of them is supported */
+fn ftr_lines(out: &mut String, cpuid: &dyn Fn(u32, u32) -> CpuidResult, ftrs: &[&str]) -> bool {
+    let mut any_supported = false;
+
+    for name in ftrs {
+        let list = find_ftr(name);
+        let supported = list.iter().find(|(leaf, sub_leaf, reg, bit)| {
+            (reg.get(&cpuid(*leaf, *sub_leaf)) >> bit) & 1 == 1
+        });
+        let (leaf, sub_leaf, reg, bit) = match supported.or(list.first()) {
+            Some(v) => v,
+            None => {
+                out.push_str(&format!("  [?] {name:<24} not in the feature table\n"));
+                continue;
+            },
+        };
+
+        out.push_str(&format!(
+            "  [{}] {name:<24} {leaf:#010X} {sub_leaf:#X} {reg:?}[{bit:02}]\n",
+            if supported.is_some() { "Y" } else { "N" },
+        ));
+
+        any_supported |= supported.is_some();
+    }
+
+    any_supported
+}
+
+/* `sysfs`: compare with the kernel status of the running system */
+pub(crate) fn security_report(cpuid: &dyn Fn(u32, u32) -> CpuidResult, sysfs: bool) -> String {
+    let mut out = String::from("[Capabilities]\n");
+
+    ftr_lines(&mut out, cpuid, CAPABILITIES);
+
+    for (vuln, ftrs) in VULNERABILITIES {
+        out.push_str(&format!("[{vuln}]\n"));
+
+        let any_supported = ftr_lines(&mut out, cpuid, ftrs);
+
+        if let Some(status) = sysfs.then(|| read_sysfs(vuln)).flatten() {
+            out.push_str(&format!("  Linux: {status}\n"));
+
+            /* e.g. "Vulnerable", "Mitigation: ...; BHI: Vulnerable" */
+            if status.contains("Vulnerable") && any_supported {
+                out.push_str("  (!) reported as vulnerable, though the processor has mitigation capabilities\n");
+            }
+        }
+    }
+
+    out
+}
+
+#[test]
+fn test_security_report() {
+    /* Ryzen 5 5600G */
+    let snapshot = ryzen_5_5600g();
+    let s = security_report(&|leaf, sub_leaf| snapshot.cpuid(leaf, sub_leaf), false);
+
+    assert!(s.contains("[Y] IBPB                     0x80000008 0x0 EBX[12]"));
+    assert!(s.contains("[Y] SSBD                     0x80000008 0x0 EBX[24]"));
+    assert!(s.contains("[N] MD_CLEAR                 0x00000007 0x0 EDX[10]"));
+    assert!(s.contains("[Y] LFenceAlwaysSerializing  0x80000021 0x0 EAX[02]"));
+    assert!(!s.contains("Linux:"));
+    assert!(s.starts_with("[Capabilities]\n"));
+    assert!(!s.contains("[arch_capabilities]"));
+    assert!(!s.contains("[?]"));
+
+    /* every name is in FTR_TABLE */
+    for name in CAPABILITIES.iter().chain(VULNERABILITIES.iter().flat_map(|(_, ftrs)| ftrs.iter())) {
+        assert!(!find_ftr(name).is_empty(), "{}", name);
+    }
+}
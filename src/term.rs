@@ -0,0 +1,185 @@
+use crate::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/* the width of the dump, set by "--width" or the terminal */
+static WIDTH: AtomicUsize = AtomicUsize::new(TOTAL_WIDTH);
+
+/* the decoded fields are wrapped at least this width on narrow terminals */
+const MIN_PARSE_WIDTH: usize = 24;
+
+pub(crate) fn set_total_width(width: usize) {
+    WIDTH.store(width, Ordering::Relaxed);
+}
+
+pub(crate) fn total_width() -> usize {
+    WIDTH.load(Ordering::Relaxed)
+}
+
+pub(crate) fn parse_width() -> usize {
+    /* " ".len() */
+    total_width().saturating_sub(INPUT_WIDTH + OUTPUT_WIDTH + 1).max(MIN_PARSE_WIDTH)
+}
+
+/* the columns of the terminal connected to stdout, or $COLUMNS, None for pipes and files */
+pub(crate) fn term_width() -> Option<usize> {
+    use std::io::IsTerminal;
+
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+
+    #[cfg(unix)]
+    unsafe {
+        use libc::{ioctl, winsize, STDOUT_FILENO, TIOCGWINSZ};
+
+        let mut ws = std::mem::zeroed::<winsize>();
+
+        if ioctl(STDOUT_FILENO, TIOCGWINSZ, &mut ws) == 0 && ws.ws_col != 0 {
+            return Some(ws.ws_col as usize);
+        }
+    }
+
+    std::env::var("COLUMNS").ok()?.parse().ok().filter(|width| *width != 0)
+}
+
+/* https://no-color.org/, and no escape sequences in pipes and files */
+pub(crate) fn use_color() -> bool {
+    use std::io::IsTerminal;
+
+    std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+}
+
+pub(crate) const RESET: &str = "\x1b[0m";
+const LEAF: &str = "\x1b[1;36m";
+const ZERO_REG: &str = "\x1b[2m";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FtrCategory {
+    Simd,
+    Security,
+    Virtualization,
+}
+
+impl FtrCategory {
+    /* "AVX512F", "SEV-ES" and "AVX512 opmask" (XSAVE component) are matched by the prefix */
+    const SIMD: &'static [&'static str] = &[
+        "MMX", "SSE", "SSSE3", "AVX", "AMX", "FMA", "F16C", "XOP", "3DNow", "MisAlignSSE",
+        "AES", "PCLMULQDQ", "VPCLMULQDQ", "VAES", "GFNI", "SHA", "FP128", "FP256",
+    ];
+    const SECURITY: &'static [&'static str] = &[
+        "NXbit", "SMEP", "SMAP", "UMIP", "PKU", "OSPKE", "PKS", "CET", "SupervisorShadowStack",
+        "SGX", "SMX", "TME", "KL", "SME", "SEV", "VMPL", "SecureTSC",
+        "IBRS", "IBPB", "STIBP", "SSBD", "L1D_FLUSH", "MD_CLEAR", "SRBDS_CTRL", "ARCH_CAPABILITIES",
+        "PSFD", "IPRED_CTRL", "RRSBA_CTRL", "BHI_CTRL", "MCDT_NO", "BTC_NO", "SBPB", "SRSO",
+        "VERW_CLEAR", "AutomaticIBRS", "TSA_",
+    ];
+    const VIRTUALIZATION: &'static [&'static str] = &[
+        "VMX", "SVM", "NestedPaging", "LbrVirt", "NRIP_Save", "TSC_Rate_MSR", "VMCB_Clean",
+        "FlushByASID", "DecodeAssists", "PauseFilter", "AVIC", "x2AVIC", "V_VMSAVE_VMLOAD", "vGIF",
+        "GMET", "GuestSpecCtrl", "ROGPT", "HOST_MCE_OVERRIDE", "VNMI", "IbsVirt",
+    ];
+
+    pub(crate) const ALL: [Self; 3] = [Self::Simd, Self::Security, Self::Virtualization];
+
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        let find = |list: &[&str]| list.iter().any(|prefix| name.starts_with(prefix));
+
+        if find(Self::SECURITY) {
+            Some(Self::Security)
+        } else if find(Self::VIRTUALIZATION) {
+            Some(Self::Virtualization)
+        } else if find(Self::SIMD) {
+            Some(Self::Simd)
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Self::Simd => "SIMD",
+            Self::Security => "Security",
+            Self::Virtualization => "Virtualization",
+        }
+    }
+
+    fn color(&self) -> &'static str {
+        match self {
+            Self::Simd => "\x1b[32m",
+            Self::Security => "\x1b[33m",
+            Self::Virtualization => "\x1b[35m",
+        }
+    }
+}
+
+/* "[AVX2]" is colored by the category, "[APIC ID: 1]" is not */
+fn color_fields(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() * 2);
+    let mut rest = s;
+
+    for field in decoded_fields(s) {
+        let (head, tail) = rest.split_at(rest.find(field).unwrap());
+        let name = &field[1..field.len()-1];
+
+        out.push_str(head);
+
+        match FtrCategory::from_name(name) {
+            Some(category) => out.push_str(&format!("[{}{name}{RESET}]", category.color())),
+            None => out.push_str(field),
+        }
+
+        rest = &tail[field.len()..];
+    }
+
+    out.push_str(rest);
+
+    out
+}
+
+/* ANSI escape sequences for the leaf headers, zero registers and feature names */
+pub(crate) fn colorize(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() * 2);
+
+    for ln in s.split_inclusive('\n') {
+        let is_leaf = ln.starts_with("  0x")
+            && ln.get(INPUT_WIDTH-3..INPUT_WIDTH) == Some(":  ");
+
+        if !is_leaf {
+            out.push_str(&color_fields(ln));
+            continue;
+        }
+
+        let (head, body) = ln.split_at(INPUT_WIDTH);
+        let (regs, parsed) = body.split_at(body.len().min(OUTPUT_WIDTH));
+
+        out.push_str(&format!("  {LEAF}{}{RESET}:  ", &head[2..INPUT_WIDTH-3]));
+
+        for reg in regs.split_inclusive(' ') {
+            if reg.trim_end() == "0x00000000" {
+                out.push_str(&format!("{ZERO_REG}{}{RESET}{}", reg.trim_end(), &reg[10..]));
+            } else {
+                out.push_str(reg);
+            }
+        }
+
+        out.push_str(&color_fields(parsed));
+    }
+
+    out
+}
+
+#[test]
+fn test_colorize() {
+    let ln = "  0x00000007 0x0:  0x00000000 0x219C97A9 0x0040068C 0x00000010  [FSGSBASE] [SMEP] [AVX2]\n";
+    let s = colorize(ln);
+
+    assert!(s.starts_with("  \x1b[1;36m0x00000007 0x0\x1b[0m:  \x1b[2m0x00000000\x1b[0m 0x219C97A9 "));
+    assert!(s.contains(" [FSGSBASE] "));
+    assert!(s.contains(" [\x1b[33mSMEP\x1b[0m] "));
+    assert!(s.ends_with(" [\x1b[32mAVX2\x1b[0m]\n"));
+
+    let ln = "                                                                [SVM] [APIC ID: 1]\n";
+
+    assert_eq!(colorize(ln), ln.replace("[SVM]", "[\x1b[35mSVM\x1b[0m]"));
+    assert_eq!(colorize("[Thread: 000]\n"), "[Thread: 000]\n");
+}
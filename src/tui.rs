@@ -0,0 +1,342 @@
+use crate::*;
+use crossterm::event::KeyCode;
+
+/* " 0x00000000 0x0    * " */
+const LIST_WIDTH: usize = 21;
+/* "EAX  00000000_00000000_00000000_00000000 " */
+const REG_WIDTH: usize = 41;
+const MIN_FIELD_WIDTH: usize = 24;
+const SEP: &str = "\u{2502}";
+const REVERSE: &str = "\x1b[7m";
+
+/* pad or truncate to the columns, `s` has no escape sequences */
+fn fit(s: &str, width: usize) -> String {
+    format!("{:<width$.width$}", s)
+}
+
+/* Leaf browser, the left pane lists the leaves, the middle pane has the registers
+   and the right pane has the decoded fields */
+pub(crate) struct Tui {
+    threads: Vec<(usize, Vec<RawCpuid>)>,
+    vendor: CpuVendor,
+    /* index of `threads` */
+    cpu: usize,
+    /* index of the filtered leaves */
+    sel: usize,
+    /* the first leaf in the left pane */
+    top: usize,
+    search: String,
+    editing: bool,
+}
+
+impl Tui {
+    pub(crate) fn new(threads: Vec<(usize, Vec<RawCpuid>)>, vendor: CpuVendor) -> Self {
+        Self { threads, vendor, cpu: 0, sel: 0, top: 0, search: String::new(), editing: false }
+    }
+
+    /* the leaves of the current CPU, which have the search word in the decoded fields */
+    fn leaves(&self) -> Vec<&RawCpuid> {
+        let word = self.search.to_lowercase();
+
+        self.threads[self.cpu].1.iter()
+            .filter(|raw| word.is_empty() || raw.parse(&self.vendor).to_lowercase().contains(&word))
+            .collect()
+    }
+
+    /* the result of CPU 0 (the first thread) */
+    fn base(&self, raw: &RawCpuid) -> CpuidResult {
+        self.threads[0].1.iter()
+            .find(|base| (base.leaf, base.sub_leaf) == (raw.leaf, raw.sub_leaf))
+            .map_or(ZERO, |base| base.result)
+    }
+
+    fn switch_cpu(&mut self, cpu: usize) {
+        let key = self.leaves().get(self.sel).map(|raw| (raw.leaf, raw.sub_leaf));
+
+        self.cpu = cpu;
+        /* keep the selected leaf if the CPU has it */
+        self.sel = self.leaves().iter()
+            .position(|raw| Some((raw.leaf, raw.sub_leaf)) == key)
+            .unwrap_or(0);
+    }
+
+    /* returns false to quit, `rows` is the height of the panes */
+    pub(crate) fn key(&mut self, code: KeyCode, rows: usize) -> bool {
+        if self.editing {
+            match code {
+                KeyCode::Char(c) => self.search.push(c),
+                KeyCode::Backspace => { self.search.pop(); },
+                KeyCode::Enter => self.editing = false,
+                KeyCode::Esc => {
+                    self.search.clear();
+                    self.editing = false;
+                },
+                _ => {},
+            }
+            self.sel = 0;
+
+            return true;
+        }
+
+        let last = self.leaves().len().saturating_sub(1);
+        let cpus = self.threads.len();
+
+        match code {
+            KeyCode::Char('q') => return false,
+            KeyCode::Up | KeyCode::Char('k') => self.sel = self.sel.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.sel = (self.sel + 1).min(last),
+            KeyCode::PageUp => self.sel = self.sel.saturating_sub(rows),
+            KeyCode::PageDown => self.sel = (self.sel + rows).min(last),
+            KeyCode::Home | KeyCode::Char('g') => self.sel = 0,
+            KeyCode::End | KeyCode::Char('G') => self.sel = last,
+            KeyCode::Left | KeyCode::Char('h') => self.switch_cpu((self.cpu + cpus - 1) % cpus),
+            KeyCode::Right | KeyCode::Char('l') | KeyCode::Tab => self.switch_cpu((self.cpu + 1) % cpus),
+            KeyCode::Char('/') => {
+                self.search.clear();
+                self.editing = true;
+            },
+            KeyCode::Esc => {
+                self.search.clear();
+                self.sel = 0;
+            },
+            _ => {},
+        }
+
+        true
+    }
+
+    /* the hex and binary of the registers, the bits different from CPU 0 are reversed */
+    fn reg_pane(&self, raw: &RawCpuid) -> Vec<String> {
+        let base = self.base(raw);
+        let regs = [
+            ("EAX", raw.result.eax, base.eax),
+            ("EBX", raw.result.ebx, base.ebx),
+            ("ECX", raw.result.ecx, base.ecx),
+            ("EDX", raw.result.edx, base.edx),
+        ];
+        let mut lines = Vec::with_capacity(16);
+
+        for (name, reg, base) in regs {
+            let mut bin = String::with_capacity(64);
+            let mut bit = 32;
+
+            for c in bin_separate(reg).chars() {
+                if c == '_' {
+                    bin.push(c);
+                    continue;
+                }
+                bit -= 1;
+
+                if ((reg ^ base) >> bit) & 0b1 != 0 {
+                    bin.push_str(&format!("{REVERSE}{c}{RESET}"));
+                } else {
+                    bin.push(c);
+                }
+            }
+
+            lines.push(fit(&format!("{name}  {reg:#010X}"), REG_WIDTH));
+            lines.push(format!("     {bin} "));
+            lines.push(fit("", REG_WIDTH));
+        }
+
+        if raw.result != base {
+            lines.push(fit(&format!("Differs from CPU {}", self.threads[0].0), REG_WIDTH));
+        }
+
+        lines
+    }
+
+    /* the decoded fields wrapped at the width, the fields with the search word are reversed */
+    fn field_pane(&self, raw: &RawCpuid, width: usize) -> Vec<String> {
+        let parsed = raw.parse(&self.vendor);
+        let word = self.search.to_lowercase();
+        let mut lines: Vec<String> = Vec::new();
+        let mut ln = String::new();
+        let mut len = 0;
+
+        for field in decoded_fields(&parsed) {
+            let field: String = field.chars().take(width).collect();
+            let field_len = field.chars().count();
+
+            if 0 < len && width < len + 1 + field_len {
+                lines.push(format!("{ln}{}", fit("", width - len)));
+                ln.clear();
+                len = 0;
+            }
+            if 0 < len {
+                ln.push(' ');
+                len += 1;
+            }
+
+            if !word.is_empty() && field.to_lowercase().contains(&word) {
+                ln.push_str(&format!("{REVERSE}{field}{RESET}"));
+            } else {
+                ln.push_str(&field);
+            }
+            len += field_len;
+        }
+
+        if 0 < len {
+            lines.push(format!("{ln}{}", fit("", width - len)));
+        }
+
+        lines
+    }
+
+    /* `height` lines of `width` columns */
+    pub(crate) fn render(&mut self, width: usize, height: usize) -> Vec<String> {
+        const MIN_HEIGHT: usize = 16;
+        let field_width = width.saturating_sub(LIST_WIDTH + REG_WIDTH + 2);
+        let rows = height.saturating_sub(2);
+
+        if field_width < MIN_FIELD_WIDTH || height < MIN_HEIGHT {
+            let msg = format!(
+                "The terminal is too small ({width}x{height}), at least {}x{MIN_HEIGHT} is needed.",
+                LIST_WIDTH + REG_WIDTH + 2 + MIN_FIELD_WIDTH,
+            );
+
+            return (0..height).map(|i| fit(if i == 0 { &msg } else { "" }, width)).collect();
+        }
+
+        let leaves = self.leaves();
+        let sel = self.sel.min(leaves.len().saturating_sub(1));
+        let mut top = self.top.min(sel);
+
+        if top + rows <= sel {
+            top = sel + 1 - rows;
+        }
+
+        let (cpu, _) = self.threads[self.cpu];
+        let mut head = format!(" CPU {cpu} ({}/{})  {}", self.cpu + 1, self.threads.len(), self.vendor);
+        let (regs, fields) = match leaves.get(sel) {
+            Some(raw) => {
+                head.push_str(&format!("  Leaf {:#010X} Sub-leaf {:#X}", raw.leaf, raw.sub_leaf));
+                (self.reg_pane(raw), self.field_pane(raw, field_width))
+            },
+            None => (Vec::new(), Vec::new()),
+        };
+        let mut lines = Vec::with_capacity(height);
+
+        lines.push(format!("{REVERSE}{}{RESET}", fit(&head, width)));
+
+        for row in 0..rows {
+            let list = match leaves.get(top + row) {
+                Some(raw) => {
+                    let mark = if raw.result != self.base(raw) { "*" } else { " " };
+                    let ln = fit(&format!(" {:#010X} {:<6} {mark}", raw.leaf, format!("{:#X}", raw.sub_leaf)), LIST_WIDTH);
+
+                    if top + row == sel { format!("{REVERSE}{ln}{RESET}") } else { ln }
+                },
+                None => fit("", LIST_WIDTH),
+            };
+            let reg = regs.get(row).cloned().unwrap_or_else(|| fit("", REG_WIDTH));
+            let field = fields.get(row).cloned().unwrap_or_else(|| fit("", field_width));
+
+            lines.push(format!("{list}{SEP}{reg}{SEP}{field}"));
+        }
+
+        let status = if self.editing {
+            format!(" /{}_", self.search)
+        } else if !self.search.is_empty() {
+            format!(" \"{}\": {} leaves  Esc: clear  q: quit", self.search, leaves.len())
+        } else {
+            " Up/Down: leaf  Left/Right: CPU  /: search  q: quit  (*: differs from the first CPU)".to_string()
+        };
+
+        lines.push(fit(&status, width));
+
+        self.sel = sel;
+        self.top = top;
+
+        lines
+    }
+
+    fn event_loop(&mut self, out: &mut io::Stdout) -> Result<(), Error> {
+        use crossterm::{cursor, event, queue, style::Print, terminal};
+        use crossterm::event::{Event, KeyEventKind, KeyModifiers};
+        use std::io::Write;
+
+        loop {
+            let (width, height) = terminal::size()?;
+
+            for (row, ln) in self.render(width as usize, height as usize).iter().enumerate() {
+                queue!(out, cursor::MoveTo(0, row as u16), Print(ln))?;
+            }
+            out.flush()?;
+
+            /* redraw on the other events, e.g. resize */
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+                    return Ok(());
+                }
+                if !self.key(key.code, (height as usize).saturating_sub(2)) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    pub(crate) fn run(&mut self) -> Result<(), Error> {
+        use crossterm::{cursor, execute, terminal};
+        use std::io::IsTerminal;
+
+        let mut out = io::stdout();
+
+        if !out.is_terminal() {
+            return Err(io::Error::new(io::ErrorKind::Unsupported, "stdout is not a terminal").into());
+        }
+
+        terminal::enable_raw_mode()?;
+        execute!(out, terminal::EnterAlternateScreen, cursor::Hide)?;
+
+        /* restore the terminal even if failed */
+        let res = self.event_loop(&mut out);
+
+        execute!(out, cursor::Show, terminal::LeaveAlternateScreen)?;
+        terminal::disable_raw_mode()?;
+
+        res
+    }
+}
+
+#[test]
+fn test_tui() {
+    /* Ryzen 5 5600G, the initial APIC ID of CPU 1 is 1 */
+    let thread = |apic_id: u32| ryzen_5_5600g_thread(apic_id).pool;
+    let mut tui = Tui::new(vec![(0, thread(0)), (1, thread(1))], CpuVendor::AuthenticAMD);
+    let lines = tui.render(120, 24);
+
+    assert_eq!(lines.len(), 24);
+    assert!(lines[0].contains(" CPU 0 (1/2)  AuthenticAMD  Leaf 0x00000000 Sub-leaf 0x0"));
+    assert!(lines[2].starts_with(" 0x00000001 0x0      "));
+    assert!(lines[1].contains("[AuthenticAMD]"));
+
+    /* CPU 1, Leaf 0x1 */
+    assert!(tui.key(KeyCode::Right, 22));
+    assert!(tui.key(KeyCode::Down, 22));
+    let lines = tui.render(120, 24);
+
+    assert!(lines[0].contains(" CPU 1 (2/2)  AuthenticAMD  Leaf 0x00000001 Sub-leaf 0x0"));
+    assert!(lines[2].contains(" 0x00000001 0x0    *"));
+    assert!(lines[5].contains(&format!("     0000000{REVERSE}1{RESET}_")));
+    assert!(lines[13].contains("Differs from CPU 0"));
+
+    /* search by the feature name */
+    for code in [KeyCode::Char('/'), KeyCode::Char('a'), KeyCode::Char('v'), KeyCode::Char('x'), KeyCode::Char('2'), KeyCode::Enter] {
+        assert!(tui.key(code, 22));
+    }
+    let lines = tui.render(120, 24);
+
+    assert!(lines[1].starts_with(&format!("{REVERSE} 0x00000007 0x0 ")));
+    assert!(lines[1].contains(&format!("{REVERSE}[AVX2]{RESET}")));
+    /* "[AVX256]" of the XSAVE components also matches */
+    assert!(lines[2].starts_with(" 0x0000000D 0x0 "));
+    assert!(lines[3].starts_with(&" ".repeat(LIST_WIDTH)));
+    assert!(lines[23].starts_with(" \"avx2\": 2 leaves"));
+
+    assert!(tui.render(60, 24)[0].starts_with("The terminal is too small (60x24)"));
+    assert!(!tui.key(KeyCode::Char('q'), 22));
+}
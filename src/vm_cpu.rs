@@ -0,0 +1,507 @@
+use crate::*;
+use libcpuid_dump::{DbVendor, FamModStep, ProcInfo};
+
+/*
+    (Leaf, SubLeaf, Register, Bit, QEMU feature name)
+    ref: https://gitlab.com/qemu-project/qemu/-/blob/master/target/i386/cpu.c (feature_word_info)
+    The bits set by the OS or the hypervisor (OSXSAVE, OSPKE, Hypervisor) are not included.
+*/
+const QEMU_FTRS: &[(u32, u32, CpuidReg, u32, &str)] = {
+    use CpuidReg::*;
+
+    &[
+        (0x1, 0x0, EDX, 0, "fpu"),
+        (0x1, 0x0, EDX, 1, "vme"),
+        (0x1, 0x0, EDX, 2, "de"),
+        (0x1, 0x0, EDX, 3, "pse"),
+        (0x1, 0x0, EDX, 4, "tsc"),
+        (0x1, 0x0, EDX, 5, "msr"),
+        (0x1, 0x0, EDX, 6, "pae"),
+        (0x1, 0x0, EDX, 7, "mce"),
+        (0x1, 0x0, EDX, 8, "cx8"),
+        (0x1, 0x0, EDX, 9, "apic"),
+        (0x1, 0x0, EDX, 11, "sep"),
+        (0x1, 0x0, EDX, 12, "mtrr"),
+        (0x1, 0x0, EDX, 13, "pge"),
+        (0x1, 0x0, EDX, 14, "mca"),
+        (0x1, 0x0, EDX, 15, "cmov"),
+        (0x1, 0x0, EDX, 16, "pat"),
+        (0x1, 0x0, EDX, 17, "pse36"),
+        (0x1, 0x0, EDX, 19, "clflush"),
+        (0x1, 0x0, EDX, 23, "mmx"),
+        (0x1, 0x0, EDX, 24, "fxsr"),
+        (0x1, 0x0, EDX, 25, "sse"),
+        (0x1, 0x0, EDX, 26, "sse2"),
+        (0x1, 0x0, ECX, 0, "pni"),
+        (0x1, 0x0, ECX, 1, "pclmulqdq"),
+        (0x1, 0x0, ECX, 5, "vmx"),
+        (0x1, 0x0, ECX, 9, "ssse3"),
+        (0x1, 0x0, ECX, 12, "fma"),
+        (0x1, 0x0, ECX, 13, "cx16"),
+        (0x1, 0x0, ECX, 15, "pdcm"),
+        (0x1, 0x0, ECX, 17, "pcid"),
+        (0x1, 0x0, ECX, 19, "sse4.1"),
+        (0x1, 0x0, ECX, 20, "sse4.2"),
+        (0x1, 0x0, ECX, 21, "x2apic"),
+        (0x1, 0x0, ECX, 22, "movbe"),
+        (0x1, 0x0, ECX, 23, "popcnt"),
+        (0x1, 0x0, ECX, 24, "tsc-deadline"),
+        (0x1, 0x0, ECX, 25, "aes"),
+        (0x1, 0x0, ECX, 26, "xsave"),
+        (0x1, 0x0, ECX, 28, "avx"),
+        (0x1, 0x0, ECX, 29, "f16c"),
+        (0x1, 0x0, ECX, 30, "rdrand"),
+        (0x7, 0x0, EBX, 0, "fsgsbase"),
+        (0x7, 0x0, EBX, 1, "tsc-adjust"),
+        (0x7, 0x0, EBX, 3, "bmi1"),
+        (0x7, 0x0, EBX, 4, "hle"),
+        (0x7, 0x0, EBX, 5, "avx2"),
+        (0x7, 0x0, EBX, 7, "smep"),
+        (0x7, 0x0, EBX, 8, "bmi2"),
+        (0x7, 0x0, EBX, 9, "erms"),
+        (0x7, 0x0, EBX, 10, "invpcid"),
+        (0x7, 0x0, EBX, 11, "rtm"),
+        (0x7, 0x0, EBX, 14, "mpx"),
+        (0x7, 0x0, EBX, 16, "avx512f"),
+        (0x7, 0x0, EBX, 17, "avx512dq"),
+        (0x7, 0x0, EBX, 18, "rdseed"),
+        (0x7, 0x0, EBX, 19, "adx"),
+        (0x7, 0x0, EBX, 20, "smap"),
+        (0x7, 0x0, EBX, 21, "avx512ifma"),
+        (0x7, 0x0, EBX, 23, "clflushopt"),
+        (0x7, 0x0, EBX, 24, "clwb"),
+        (0x7, 0x0, EBX, 26, "avx512pf"),
+        (0x7, 0x0, EBX, 27, "avx512er"),
+        (0x7, 0x0, EBX, 28, "avx512cd"),
+        (0x7, 0x0, EBX, 29, "sha-ni"),
+        (0x7, 0x0, EBX, 30, "avx512bw"),
+        (0x7, 0x0, EBX, 31, "avx512vl"),
+        (0x7, 0x0, ECX, 1, "avx512vbmi"),
+        (0x7, 0x0, ECX, 2, "umip"),
+        (0x7, 0x0, ECX, 3, "pku"),
+        (0x7, 0x0, ECX, 5, "waitpkg"),
+        (0x7, 0x0, ECX, 6, "avx512vbmi2"),
+        (0x7, 0x0, ECX, 8, "gfni"),
+        (0x7, 0x0, ECX, 9, "vaes"),
+        (0x7, 0x0, ECX, 10, "vpclmulqdq"),
+        (0x7, 0x0, ECX, 11, "avx512vnni"),
+        (0x7, 0x0, ECX, 12, "avx512bitalg"),
+        (0x7, 0x0, ECX, 14, "avx512-vpopcntdq"),
+        (0x7, 0x0, ECX, 16, "la57"),
+        (0x7, 0x0, ECX, 22, "rdpid"),
+        (0x7, 0x0, ECX, 24, "bus-lock-detect"),
+        (0x7, 0x0, ECX, 25, "cldemote"),
+        (0x7, 0x0, ECX, 27, "movdiri"),
+        (0x7, 0x0, ECX, 28, "movdir64b"),
+        (0x7, 0x0, EDX, 4, "fsrm"),
+        (0x7, 0x0, EDX, 8, "avx512-vp2intersect"),
+        (0x7, 0x0, EDX, 10, "md-clear"),
+        (0x7, 0x0, EDX, 14, "serialize"),
+        (0x7, 0x0, EDX, 16, "tsx-ldtrk"),
+        (0x7, 0x0, EDX, 22, "amx-bf16"),
+        (0x7, 0x0, EDX, 23, "avx512-fp16"),
+        (0x7, 0x0, EDX, 24, "amx-tile"),
+        (0x7, 0x0, EDX, 25, "amx-int8"),
+        (0x7, 0x0, EDX, 26, "spec-ctrl"),
+        (0x7, 0x0, EDX, 27, "stibp"),
+        (0x7, 0x0, EDX, 29, "arch-capabilities"),
+        (0x7, 0x0, EDX, 30, "core-capability"),
+        (0x7, 0x0, EDX, 31, "ssbd"),
+        (0x7, 0x1, EAX, 4, "avx-vnni"),
+        (0x7, 0x1, EAX, 5, "avx512-bf16"),
+        (0x7, 0x1, EAX, 10, "fzrm"),
+        (0x7, 0x1, EAX, 11, "fsrs"),
+        (0x7, 0x1, EAX, 12, "fsrc"),
+        (0x7, 0x1, EAX, 21, "amx-fp16"),
+        (0x7, 0x1, EAX, 23, "avx-ifma"),
+        (0x7, 0x1, EDX, 4, "avx-vnni-int8"),
+        (0x7, 0x1, EDX, 5, "avx-ne-convert"),
+        (0x7, 0x1, EDX, 14, "prefetchiti"),
+        (0xD, 0x1, EAX, 0, "xsaveopt"),
+        (0xD, 0x1, EAX, 1, "xsavec"),
+        (0xD, 0x1, EAX, 2, "xgetbv1"),
+        (0xD, 0x1, EAX, 3, "xsaves"),
+        (0x8000_0001, 0x0, ECX, 0, "lahf-lm"),
+        (0x8000_0001, 0x0, ECX, 1, "cmp-legacy"),
+        (0x8000_0001, 0x0, ECX, 2, "svm"),
+        (0x8000_0001, 0x0, ECX, 3, "extapic"),
+        (0x8000_0001, 0x0, ECX, 4, "cr8legacy"),
+        (0x8000_0001, 0x0, ECX, 5, "abm"),
+        (0x8000_0001, 0x0, ECX, 6, "sse4a"),
+        (0x8000_0001, 0x0, ECX, 7, "misalignsse"),
+        (0x8000_0001, 0x0, ECX, 8, "3dnowprefetch"),
+        (0x8000_0001, 0x0, ECX, 9, "osvw"),
+        (0x8000_0001, 0x0, ECX, 10, "ibs"),
+        (0x8000_0001, 0x0, ECX, 11, "xop"),
+        (0x8000_0001, 0x0, ECX, 12, "skinit"),
+        (0x8000_0001, 0x0, ECX, 13, "wdt"),
+        (0x8000_0001, 0x0, ECX, 15, "lwp"),
+        (0x8000_0001, 0x0, ECX, 16, "fma4"),
+        (0x8000_0001, 0x0, ECX, 17, "tce"),
+        (0x8000_0001, 0x0, ECX, 19, "nodeid-msr"),
+        (0x8000_0001, 0x0, ECX, 21, "tbm"),
+        (0x8000_0001, 0x0, ECX, 22, "topoext"),
+        (0x8000_0001, 0x0, ECX, 23, "perfctr-core"),
+        (0x8000_0001, 0x0, ECX, 24, "perfctr-nb"),
+        (0x8000_0001, 0x0, EDX, 11, "syscall"),
+        (0x8000_0001, 0x0, EDX, 20, "nx"),
+        (0x8000_0001, 0x0, EDX, 22, "mmxext"),
+        (0x8000_0001, 0x0, EDX, 25, "fxsr-opt"),
+        (0x8000_0001, 0x0, EDX, 26, "pdpe1gb"),
+        (0x8000_0001, 0x0, EDX, 27, "rdtscp"),
+        (0x8000_0001, 0x0, EDX, 29, "lm"),
+        (0x8000_0001, 0x0, EDX, 30, "3dnowext"),
+        (0x8000_0001, 0x0, EDX, 31, "3dnow"),
+        (0x8000_0007, 0x0, EDX, 8, "invtsc"),
+        (0x8000_0008, 0x0, EBX, 0, "clzero"),
+        (0x8000_0008, 0x0, EBX, 2, "xsaveerptr"),
+        (0x8000_0008, 0x0, EBX, 9, "wbnoinvd"),
+        (0x8000_0008, 0x0, EBX, 12, "ibpb"),
+        (0x8000_0008, 0x0, EBX, 14, "ibrs"),
+        (0x8000_0008, 0x0, EBX, 15, "amd-stibp"),
+        (0x8000_0008, 0x0, EBX, 24, "amd-ssbd"),
+        (0x8000_0008, 0x0, EBX, 25, "virt-ssbd"),
+        (0x8000_0008, 0x0, EBX, 26, "amd-no-ssb"),
+        (0x8000_0008, 0x0, EBX, 28, "amd-psfd"),
+        (0x8000_0021, 0x0, EAX, 0, "no-nested-data-bp"),
+        (0x8000_0021, 0x0, EAX, 2, "lfence-always-serializing"),
+        (0x8000_0021, 0x0, EAX, 6, "null-sel-clr-base"),
+        (0x8000_0021, 0x0, EAX, 8, "auto-ibrs"),
+    ]
+};
+
+/* ref: https://gitlab.com/libvirt/libvirt/-/blob/master/src/cpu_map/x86_features.xml */
+const LIBVIRT_NAMES: &[(&str, &str)] = &[
+    ("pclmulqdq", "pclmuldq"),
+    ("tsc-adjust", "tsc_adjust"),
+    ("lahf-lm", "lahf_lm"),
+    ("cmp-legacy", "cmp_legacy"),
+    ("fxsr-opt", "fxsr_opt"),
+    ("nodeid-msr", "nodeid_msr"),
+    ("perfctr-core", "perfctr_core"),
+    ("perfctr-nb", "perfctr_nb"),
+];
+
+/* A named CPU model of QEMU, the features are of the first version (e.g. "EPYC-Milan-v1") */
+struct CpuModel {
+    name: &'static str,
+    /* `None` for any vendor */
+    vendor: Option<DbVendor>,
+    /* microarchitecture in the codename database, for `ProcInfo::is_same_or_newer` */
+    microarch: &'static str,
+    parent: Option<&'static str>,
+    /* in addition to the features of the parent */
+    features: &'static [&'static str],
+}
+
+const CPU_MODELS: &[CpuModel] = &[
+    CpuModel {
+        name: "qemu64",
+        vendor: None,
+        microarch: "",
+        parent: None,
+        features: &[
+            "fpu", "de", "pse", "tsc", "msr", "pae", "mce", "cx8", "apic", "sep", "mtrr", "pge",
+            "mca", "cmov", "pat", "pse36", "clflush", "mmx", "fxsr", "sse", "sse2", "pni", "cx16",
+            "syscall", "nx", "lm", "lahf-lm", "svm",
+        ],
+    },
+    /* Intel */
+    CpuModel {
+        name: "Nehalem",
+        vendor: Some(DbVendor::Intel),
+        microarch: "Nehalem",
+        parent: None,
+        features: &[
+            "fpu", "vme", "de", "pse", "tsc", "msr", "pae", "mce", "cx8", "apic", "sep", "mtrr",
+            "pge", "mca", "cmov", "pat", "pse36", "clflush", "mmx", "fxsr", "sse", "sse2", "pni",
+            "ssse3", "cx16", "sse4.1", "sse4.2", "popcnt", "syscall", "nx", "lm", "lahf-lm",
+        ],
+    },
+    CpuModel {
+        name: "Westmere",
+        vendor: Some(DbVendor::Intel),
+        microarch: "Westmere",
+        parent: Some("Nehalem"),
+        features: &["aes", "pclmulqdq"],
+    },
+    CpuModel {
+        name: "SandyBridge",
+        vendor: Some(DbVendor::Intel),
+        microarch: "SandyBridge",
+        parent: Some("Westmere"),
+        features: &["x2apic", "tsc-deadline", "xsave", "avx", "rdtscp", "xsaveopt"],
+    },
+    CpuModel {
+        name: "IvyBridge",
+        vendor: Some(DbVendor::Intel),
+        microarch: "IvyBridge",
+        parent: Some("SandyBridge"),
+        features: &["f16c", "rdrand", "fsgsbase", "smep", "erms"],
+    },
+    CpuModel {
+        name: "Haswell",
+        vendor: Some(DbVendor::Intel),
+        microarch: "Haswell",
+        parent: Some("IvyBridge"),
+        features: &["fma", "pcid", "movbe", "bmi1", "hle", "avx2", "bmi2", "invpcid", "rtm", "abm"],
+    },
+    CpuModel {
+        name: "Broadwell",
+        vendor: Some(DbVendor::Intel),
+        microarch: "Broadwell",
+        parent: Some("Haswell"),
+        features: &["rdseed", "adx", "smap", "3dnowprefetch"],
+    },
+    CpuModel {
+        name: "Skylake-Client",
+        vendor: Some(DbVendor::Intel),
+        microarch: "Skylake",
+        parent: Some("Broadwell"),
+        features: &["mpx", "xsavec", "xgetbv1"],
+    },
+    CpuModel {
+        name: "Skylake-Server",
+        vendor: Some(DbVendor::Intel),
+        microarch: "Skylake_AVX512",
+        parent: Some("Skylake-Client"),
+        features: &[
+            "pdpe1gb", "clflushopt", "clwb", "avx512f", "avx512dq", "avx512cd", "avx512bw",
+            "avx512vl", "pku",
+        ],
+    },
+    CpuModel {
+        name: "Cascadelake-Server",
+        vendor: Some(DbVendor::Intel),
+        microarch: "Skylake_AVX512_VNNI",
+        parent: Some("Skylake-Server"),
+        features: &["avx512vnni"],
+    },
+    CpuModel {
+        name: "Cooperlake",
+        vendor: Some(DbVendor::Intel),
+        microarch: "Skylake_AVX512_VNNI_BF16",
+        parent: Some("Cascadelake-Server"),
+        features: &["avx512-bf16"],
+    },
+    CpuModel {
+        name: "Icelake-Server",
+        vendor: Some(DbVendor::Intel),
+        microarch: "SunnyCove",
+        parent: Some("Cascadelake-Server"),
+        features: &[
+            "avx512vbmi", "umip", "avx512vbmi2", "gfni", "vaes", "vpclmulqdq", "avx512bitalg",
+            "avx512-vpopcntdq", "la57", "rdpid", "wbnoinvd",
+        ],
+    },
+    CpuModel {
+        name: "SapphireRapids",
+        vendor: Some(DbVendor::Intel),
+        microarch: "GoldenCove",
+        parent: Some("Icelake-Server"),
+        features: &[
+            "avx512ifma", "sha-ni", "movdiri", "movdir64b", "fsrm", "serialize", "tsx-ldtrk",
+            "amx-bf16", "avx512-fp16", "amx-tile", "amx-int8", "avx-vnni", "avx512-bf16", "fzrm",
+            "fsrs", "fsrc", "bus-lock-detect",
+        ],
+    },
+    CpuModel {
+        name: "GraniteRapids",
+        vendor: Some(DbVendor::Intel),
+        microarch: "RedwoodCove",
+        parent: Some("SapphireRapids"),
+        features: &["amx-fp16", "prefetchiti"],
+    },
+    /* AMD */
+    CpuModel {
+        name: "Opteron_G4",
+        vendor: Some(DbVendor::Amd),
+        microarch: "Bulldozer",
+        parent: None,
+        features: &[
+            "fpu", "vme", "de", "pse", "tsc", "msr", "pae", "mce", "cx8", "apic", "sep", "mtrr",
+            "pge", "mca", "cmov", "pat", "pse36", "clflush", "mmx", "fxsr", "sse", "sse2", "pni",
+            "pclmulqdq", "ssse3", "cx16", "sse4.1", "sse4.2", "popcnt", "aes", "xsave", "avx",
+            "syscall", "nx", "pdpe1gb", "rdtscp", "lm", "lahf-lm", "svm", "abm", "sse4a",
+            "misalignsse", "3dnowprefetch", "xop", "fma4",
+        ],
+    },
+    CpuModel {
+        name: "Opteron_G5",
+        vendor: Some(DbVendor::Amd),
+        microarch: "Piledriver",
+        parent: Some("Opteron_G4"),
+        features: &["fma", "f16c", "tbm"],
+    },
+    CpuModel {
+        name: "EPYC",
+        vendor: Some(DbVendor::Amd),
+        microarch: "Zen",
+        parent: None,
+        features: &[
+            "fpu", "vme", "de", "pse", "tsc", "msr", "pae", "mce", "cx8", "apic", "sep", "mtrr",
+            "pge", "mca", "cmov", "pat", "pse36", "clflush", "mmx", "fxsr", "sse", "sse2", "pni",
+            "pclmulqdq", "ssse3", "fma", "cx16", "sse4.1", "sse4.2", "movbe", "popcnt", "aes",
+            "xsave", "avx", "f16c", "rdrand", "syscall", "nx", "mmxext", "fxsr-opt", "pdpe1gb",
+            "rdtscp", "lm", "lahf-lm", "svm", "cr8legacy", "abm", "sse4a", "misalignsse",
+            "3dnowprefetch", "osvw", "topoext", "perfctr-core", "fsgsbase", "bmi1", "avx2", "smep",
+            "bmi2", "rdseed", "adx", "smap", "clflushopt", "sha-ni", "xsaveopt", "xsavec", "xgetbv1",
+        ],
+    },
+    CpuModel {
+        name: "EPYC-Rome",
+        vendor: Some(DbVendor::Amd),
+        microarch: "Zen 2",
+        parent: Some("EPYC"),
+        features: &["clwb", "umip", "rdpid", "clzero", "xsaveerptr", "wbnoinvd", "ibpb", "amd-stibp"],
+    },
+    CpuModel {
+        name: "EPYC-Milan",
+        vendor: Some(DbVendor::Amd),
+        microarch: "Zen 3",
+        parent: Some("EPYC-Rome"),
+        features: &["pcid", "erms", "invpcid", "pku", "fsrm", "ibrs", "amd-ssbd"],
+    },
+    CpuModel {
+        name: "EPYC-Genoa",
+        vendor: Some(DbVendor::Amd),
+        microarch: "Zen 4",
+        parent: Some("EPYC-Milan"),
+        features: &[
+            "avx512f", "avx512dq", "avx512ifma", "avx512cd", "avx512bw", "avx512vl", "avx512vbmi",
+            "avx512vbmi2", "gfni", "vaes", "vpclmulqdq", "avx512vnni", "avx512bitalg",
+            "avx512-vpopcntdq", "la57", "avx512-bf16", "no-nested-data-bp",
+            "lfence-always-serializing", "null-sel-clr-base", "auto-ibrs",
+        ],
+    },
+    CpuModel {
+        name: "EPYC-Turin",
+        vendor: Some(DbVendor::Amd),
+        microarch: "Zen 5",
+        parent: Some("EPYC-Genoa"),
+        features: &["movdiri", "movdir64b", "avx-vnni", "avx512-vp2intersect"],
+    },
+    /* Hygon, Zhaoxin */
+    CpuModel {
+        name: "Dhyana",
+        vendor: Some(DbVendor::Hygon),
+        microarch: "Dhyana",
+        parent: Some("EPYC"),
+        features: &[],
+    },
+    CpuModel {
+        name: "YongFeng",
+        vendor: Some(DbVendor::Zhaoxin),
+        microarch: "Yongfeng",
+        parent: Some("Westmere"),
+        features: &[
+            "x2apic", "tsc-deadline", "xsave", "avx", "rdtscp", "xsaveopt", "f16c", "rdrand",
+            "fsgsbase", "smep", "erms", "fma", "movbe", "bmi1", "avx2", "bmi2", "abm", "rdseed",
+            "adx", "smap", "sha-ni", "clflushopt",
+        ],
+    },
+];
+
+impl CpuModel {
+    fn find(name: &str) -> &'static Self {
+        CPU_MODELS.iter().find(|model| model.name == name).unwrap()
+    }
+
+    fn all_features(&self) -> Vec<&'static str> {
+        let mut ftrs = self.parent.map_or_else(Vec::new, |parent| Self::find(parent).all_features());
+
+        ftrs.extend_from_slice(self.features);
+        ftrs
+    }
+}
+
+/* The closest named model and the features to enable/disable to reproduce the processor */
+pub(crate) struct VmCpu {
+    pub model: &'static str,
+    pub vendor: Option<DbVendor>,
+    pub info: ProcInfo,
+    pub enable: Vec<&'static str>,
+    pub disable: Vec<&'static str>,
+}
+
+impl VmCpu {
+    pub fn from_cpuid(cpuid: &dyn Fn(u32, u32) -> CpuidResult) -> Self {
+        let vendor = CpuVendor::from(&cpuid(0x0, 0x0));
+        let db_vendor = DbVendor::from_cpu_vendor(&vendor);
+        let info = ProcInfo::from_fms(&FamModStep::from(&cpuid(0x1, 0x0)), &vendor);
+        let has_gen = info.generation().is_some();
+
+        let host: Vec<&str> = QEMU_FTRS.iter()
+            .filter(|(leaf, sub_leaf, reg, bit, _)| (reg.get(&cpuid(*leaf, *sub_leaf)) >> bit) & 1 == 1)
+            .map(|ftr| ftr.4)
+            .collect();
+
+        /* the models not newer than the processor, the least changes, the newer if the same */
+        let (model, enable, disable) = CPU_MODELS.iter()
+            .filter(|model| model.vendor.is_none() || (model.vendor == db_vendor
+                && (!has_gen || info.is_same_or_newer(model.microarch) == Some(true))))
+            .map(|model| {
+                let ftrs = model.all_features();
+                let enable: Vec<&str> = host.iter().filter(|f| !ftrs.contains(f)).copied().collect();
+                let disable: Vec<&str> = ftrs.into_iter().filter(|f| !host.contains(f)).collect();
+
+                (model.name, enable, disable)
+            })
+            .rev()
+            .min_by_key(|(_, enable, disable)| enable.len() + disable.len())
+            .unwrap();
+
+        Self { model, vendor: db_vendor, info, enable, disable }
+    }
+
+    /* "-cpu EPYC-Milan,+x2apic,-pcid" */
+    pub fn qemu(&self) -> String {
+        let enable = self.enable.iter().map(|f| format!(",+{f}"));
+        let disable = self.disable.iter().map(|f| format!(",-{f}"));
+
+        format!("-cpu {}{}\n", self.model, enable.chain(disable).collect::<String>())
+    }
+
+    /* <cpu mode='custom'> of the libvirt domain XML */
+    pub fn libvirt(&self) -> String {
+        let name = |f: &str| LIBVIRT_NAMES.iter().find(|(q, _)| *q == f).map_or(f.to_string(), |(_, l)| l.to_string());
+        let vendor = match self.vendor {
+            Some(DbVendor::Amd) => "AMD",
+            Some(DbVendor::Intel) => "Intel",
+            Some(DbVendor::Hygon) => "Hygon",
+            _ => "",
+        };
+        let mut out = format!(
+            "<!-- {}, {} -->\n<cpu mode='custom' match='exact' check='partial'>\n  <model fallback='forbid'>{}</model>\n",
+            self.info.codename,
+            self.info.archname,
+            self.model,
+        );
+
+        if !vendor.is_empty() {
+            out.push_str(&format!("  <vendor>{vendor}</vendor>\n"));
+        }
+
+        for (policy, ftrs) in [("require", &self.enable), ("disable", &self.disable)] {
+            for f in ftrs {
+                out.push_str(&format!("  <feature policy='{policy}' name='{}'/>\n", name(f)));
+            }
+        }
+
+        out.push_str("</cpu>\n");
+        out
+    }
+}
+
+#[test]
+fn test_vm_cpu() {
+    /* Ryzen 5 5600G */
+    let snapshot = ryzen_5_5600g();
+    let vm = VmCpu::from_cpuid(&|leaf, sub_leaf| snapshot.cpuid(leaf, sub_leaf));
+
+    assert_eq!(vm.model, "EPYC-Milan");
+    assert!(vm.enable.contains(&"x2apic"));
+    assert_eq!(vm.disable, ["pcid"]);
+    assert!(vm.qemu().starts_with("-cpu EPYC-Milan,+"));
+    assert!(vm.libvirt().contains("  <feature policy='require' name='cmp_legacy'/>\n"));
+}
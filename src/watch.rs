@@ -0,0 +1,129 @@
+use crate::*;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/* RFC 3339 in UTC, "2026-01-01T00:00:00.000Z" */
+pub(crate) fn timestamp(t: SystemTime) -> String {
+    let dur = t.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = dur.as_secs();
+    let (days, sec_of_day) = ((secs / 86400) as i64, secs % 86400);
+
+    /* civil_from_days, http://howardhinnant.github.io/date_algorithms.html */
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        sec_of_day / 3600,
+        (sec_of_day / 60) % 60,
+        sec_of_day % 60,
+        dur.subsec_millis(),
+    )
+}
+
+/* "[AVX2]", "[APIC ID: 1]" of the decoded results */
+pub(crate) fn decoded_fields(s: &str) -> Vec<&str> {
+    let mut fields = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+
+    for (i, c) in s.char_indices() {
+        match c {
+            '[' => {
+                if depth == 0 {
+                    start = i;
+                }
+                depth += 1;
+            },
+            ']' if 0 < depth => {
+                depth -= 1;
+                if depth == 0 {
+                    fields.push(&s[start..=i]);
+                }
+            },
+            _ => {},
+        }
+    }
+
+    fields
+}
+
+/* The leaves changed from `old` to `new` with the decoded fields, empty if not changed */
+pub(crate) fn watch_diff(cpu: usize, old: &[RawCpuid], new: &[RawCpuid], vendor: &CpuVendor, time: &str) -> String {
+    let mut keys: Vec<(u32, u32)> = old.iter().chain(new.iter())
+        .map(|raw| (raw.leaf, raw.sub_leaf))
+        .collect();
+    keys.sort_unstable();
+    keys.dedup();
+
+    let find = |pool: &[RawCpuid], key: (u32, u32)| -> Option<RawCpuid> {
+        pool.iter().find(|raw| (raw.leaf, raw.sub_leaf) == key).cloned()
+    };
+    let mut out = String::new();
+
+    for key in keys {
+        let [a, b] = [find(old, key), find(new, key)];
+
+        if a == b {
+            continue;
+        }
+
+        if out.is_empty() {
+            out.push_str(&format!("[{time}] CPU {cpu}\n"));
+        }
+
+        let [parse_a, parse_b] = [&a, &b].map(|raw| raw.as_ref().map_or("".to_string(), |raw| raw.parse(vendor)));
+        let [fields_a, fields_b] = [decoded_fields(&parse_a), decoded_fields(&parse_b)];
+
+        if let Some(a) = a {
+            out.push_str(&["-", &a.raw_fmt(vendor)].concat());
+        }
+        if let Some(b) = b {
+            out.push_str(&["+", &b.raw_fmt(vendor)].concat());
+        }
+
+        for field in fields_a.iter().filter(|field| !fields_b.contains(field)) {
+            out.push_str(&format!("{:INPUT_WIDTH$}- {field}\n", ""));
+        }
+        for field in fields_b.iter().filter(|field| !fields_a.contains(field)) {
+            out.push_str(&format!("{:INPUT_WIDTH$}+ {field}\n", ""));
+        }
+    }
+
+    out
+}
+
+#[test]
+fn test_watch_diff() {
+    let raw = |leaf: u32, [eax, ebx, ecx, edx]: [u32; 4]| RawCpuid {
+        leaf,
+        sub_leaf: 0x0,
+        result: CpuidResult { eax, ebx, ecx, edx },
+    };
+    /* OSXSAVE (CPUID[0x1].ECX[27]) is cleared, Ryzen 5 5600G */
+    let old = [
+        raw(0x0, [0x00000010, 0x68747541, 0x444D4163, 0x69746E65]),
+        raw(0x1, [0x00A50F00, 0x000C0800, 0x7EF8320B, 0x178BFBFF]),
+    ];
+    let new = [
+        raw(0x0, [0x00000010, 0x68747541, 0x444D4163, 0x69746E65]),
+        raw(0x1, [0x00A50F00, 0x000C0800, 0x76F8320B, 0x178BFBFF]),
+    ];
+    let vendor = CpuVendor::AuthenticAMD;
+    let time = timestamp(UNIX_EPOCH + std::time::Duration::from_millis(1_700_000_000_123));
+    let s = watch_diff(3, &old, &new, &vendor, &time);
+
+    assert_eq!(time, "2023-11-14T22:13:20.123Z");
+    assert!(s.starts_with("[2023-11-14T22:13:20.123Z] CPU 3\n"));
+    assert!(s.contains("-  0x00000001 0x0:  0x00A50F00 0x000C0800 0x7EF8320B 0x178BFBFF"));
+    assert!(s.contains("+  0x00000001 0x0:  0x00A50F00 0x000C0800 0x76F8320B 0x178BFBFF"));
+    assert!(s.contains("- [OSXSAVE]\n"));
+    assert!(!s.contains("0x00000000 0x0:"));
+    assert!(watch_diff(0, &old, &old, &vendor, &time).is_empty());
+}